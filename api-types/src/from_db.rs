@@ -0,0 +1,146 @@
+use crate::request_output::{ApiKeyOutput, EscalationLevelOutput, EscalationPolicyOutput, GetWebsiteOutput, IncidentEventOutput, IncidentOutput, MaintenanceWindowOutput, NotificationDeliveryOutput, OnCallScheduleOutput, StatusPageComponentOutput, StatusPageOutput, TeamMemberOutput};
+
+/// How many upcoming occurrences a maintenance window lists.
+const UPCOMING_OCCURRENCES: usize = 5;
+
+impl From<db::models::website::Website> for GetWebsiteOutput{
+  fn from(w:db::models::website::Website)->Self{
+    Self{
+      url:w.url,
+      id:w.id,
+      paused:w.paused,
+      group_id:w.group_id
+    }
+  }
+}
+
+impl From<db::models::api_key::ApiKey> for ApiKeyOutput{
+  fn from(k:db::models::api_key::ApiKey)->Self{
+    Self{
+      id:k.id,
+      name:k.name,
+      prefix:k.prefix,
+      key:None,
+      time_added:k.time_added.and_utc().to_rfc3339(),
+      last_used_at:k.last_used_at.map(|t| t.and_utc().to_rfc3339())
+    }
+  }
+}
+
+impl From<db::models::team_member::TeamMembership> for TeamMemberOutput{
+  fn from(m:db::models::team_member::TeamMembership)->Self{
+    Self{
+      id:m.member.id,
+      owner_id:m.member.owner_id,
+      owner_username:m.owner_username,
+      member_id:m.member.member_id,
+      member_username:m.member_username,
+      invited_at:m.member.invited_at.and_utc().to_rfc3339(),
+      accepted_at:m.member.accepted_at.map(|t| t.and_utc().to_rfc3339())
+    }
+  }
+}
+
+impl From<db::models::notification_delivery::NotificationDelivery> for NotificationDeliveryOutput{
+  fn from(d:db::models::notification_delivery::NotificationDelivery)->Self{
+    Self{
+      id:d.id,
+      incident_id:d.incident_id,
+      channel_id:d.channel_id,
+      attempt:d.attempt,
+      status_code:d.status_code,
+      latency_ms:d.latency_ms,
+      error:d.error,
+      attempted_at:d.attempted_at.and_utc().to_rfc3339()
+    }
+  }
+}
+
+impl From<db::models::incident::Incident> for IncidentOutput{
+  fn from(i:db::models::incident::Incident)->Self{
+    Self{
+      id:i.id,
+      website_id:i.website_id,
+      status:i.status,
+      opened_at:i.opened_at.and_utc().to_rfc3339(),
+      acknowledged_at:i.acknowledged_at.map(|t| t.and_utc().to_rfc3339()),
+      acknowledged_by:i.acknowledged_by,
+      resolved_at:i.resolved_at.map(|t| t.and_utc().to_rfc3339())
+    }
+  }
+}
+
+impl From<(db::models::escalation::EscalationPolicy,Vec<db::models::escalation::EscalationLevel>)> for EscalationPolicyOutput{
+  fn from((policy,levels):(db::models::escalation::EscalationPolicy,Vec<db::models::escalation::EscalationLevel>))->Self{
+    Self{
+      id:policy.id,
+      name:policy.name,
+      levels:levels.into_iter().map(|l| EscalationLevelOutput{
+        position:l.position,
+        delay_minutes:l.delay_minutes,
+        channel_id:l.channel_id,
+        schedule_id:l.schedule_id
+      }).collect()
+    }
+  }
+}
+
+impl From<db::models::on_call::OnCallSchedule> for OnCallScheduleOutput{
+  fn from(s:db::models::on_call::OnCallSchedule)->Self{
+    Self{
+      id:s.id,
+      name:s.name,
+      rotation:s.rotation,
+      rotation_start:s.rotation_start.and_utc().to_rfc3339(),
+      participants:s.participants
+    }
+  }
+}
+
+impl From<db::models::incident_event::IncidentEvent> for IncidentEventOutput{
+  fn from(e:db::models::incident_event::IncidentEvent)->Self{
+    Self{
+      id:e.id,
+      kind:e.kind,
+      user_id:e.user_id,
+      message:e.message,
+      created_at:e.created_at.and_utc().to_rfc3339()
+    }
+  }
+}
+
+impl From<db::models::maintenance_window::MaintenanceWindow> for MaintenanceWindowOutput{
+  fn from(w:db::models::maintenance_window::MaintenanceWindow)->Self{
+    let upcoming=w.occurrences_after(chrono::Utc::now().naive_utc(), UPCOMING_OCCURRENCES)
+      .into_iter()
+      .map(|(start,end)| [start.and_utc().to_rfc3339(),end.and_utc().to_rfc3339()])
+      .collect();
+    Self{
+      id:w.id,
+      website_id:w.website_id,
+      group_id:w.group_id,
+      title:w.title,
+      starts_at:w.starts_at.and_utc().to_rfc3339(),
+      duration_minutes:w.duration_minutes,
+      recurrence:w.recurrence,
+      until:w.until.map(|u| u.and_utc().to_rfc3339()),
+      upcoming
+    }
+  }
+}
+
+impl From<(db::models::status_page::StatusPage,Vec<db::models::status_page::StatusPageComponent>)> for StatusPageOutput{
+  fn from((page,components):(db::models::status_page::StatusPage,Vec<db::models::status_page::StatusPageComponent>))->Self{
+    Self{
+      id:page.id,
+      slug:page.slug,
+      title:page.title,
+      logo_url:page.logo_url,
+      components:components.into_iter().map(|c| StatusPageComponentOutput{
+        id:c.id,
+        website_id:c.website_id,
+        name:c.name
+      }).collect()
+    }
+  }
+}
@@ -0,0 +1,6 @@
+//! Bodies the api accepts and returns, shared with its clients.
+
+pub mod request_input;
+pub mod request_output;
+#[cfg(feature="db")]
+mod from_db;
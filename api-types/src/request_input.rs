@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+use chrono::{DateTime, Utc};
+#[cfg(feature="openapi")]
+use poem_openapi::Object;
+use serde::{Serialize,Deserialize};
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct CreateWebsiteInput{
+    pub url:String
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct UpdateWebsiteInput{
+    pub url:String
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct CreateUserInput{
+    pub username:String,
+    pub password:String
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct CreateApiKeyInput{
+    /// What the key is for, e.g. `deploy script`.
+    pub name:String
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct InviteTeamMemberInput{
+    /// Who to invite; they can be put on your on-call schedules once they accept.
+    pub username:String
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct CreateNotificationChannelInput{
+    pub kind:String,
+    pub target:String,
+    pub secret:Option<String>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct EscalationLevelInput{
+    pub delay_minutes:i32,
+    pub channel_id:Option<String>,
+    pub schedule_id:Option<String>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct CreateEscalationPolicyInput{
+    pub name:String,
+    pub levels:Vec<EscalationLevelInput>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct SetEscalationPolicyInput{
+    pub policy_id:Option<String>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct CreateOnCallScheduleInput{
+    pub name:String,
+    pub rotation:String,
+    pub rotation_start:DateTime<Utc>,
+    pub participants:Vec<String>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct CreateOnCallOverrideInput{
+    pub user_id:String,
+    pub starts_at:DateTime<Utc>,
+    pub ends_at:DateTime<Utc>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct MaintenanceWindowInput{
+    /// Exactly one of `website_id` / `group_id`.
+    pub website_id:Option<String>,
+    pub group_id:Option<String>,
+    pub title:String,
+    pub starts_at:DateTime<Utc>,
+    pub duration_minutes:i32,
+    /// Cron expression (UTC); omit for a one-off window.
+    pub recurrence:Option<String>,
+    pub until:Option<DateTime<Utc>>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct WebsiteGroupInput{
+    pub name:String,
+    pub parent_id:Option<String>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct SetWebsiteGroupInput{
+    pub group_id:Option<String>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct SetTagsInput{
+    pub tags:BTreeMap<String,String>
+}
+
+/// Websites to act on: the listed ids plus every website matching the filter.
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct BulkWebsiteInput{
+    #[serde(default)]
+    #[cfg_attr(feature="openapi",oai(default))]
+    pub ids:Vec<String>,
+    pub group_id:Option<String>,
+    /// Comma separated `key:value` pairs, all of which must match.
+    pub tag:Option<String>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct SetDependenciesInput{
+    pub depends_on:Vec<String>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct StatusPageComponentInput{
+    pub website_id:String,
+    pub name:String
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct StatusPageInput{
+    pub slug:String,
+    pub title:String,
+    pub logo_url:Option<String>,
+    /// Shown in this order.
+    pub components:Vec<StatusPageComponentInput>
+}
+
+/// Exactly one of `email` and `webhook_url`.
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct SubscribeInput{
+    pub email:Option<String>,
+    pub webhook_url:Option<String>,
+    /// Components to follow, every component of the page when empty.
+    #[serde(default)]
+    #[cfg_attr(feature="openapi",oai(default))]
+    pub component_ids:Vec<String>
+}
+
+/// Everything managed as code. Each resource is listed under a key of your choosing that
+/// stays the same across edits, so changing a url updates the website instead of replacing it.
+/// Managed resources missing from the document are deleted; resources never named by one are left alone.
+#[derive(Serialize,Deserialize,Debug,Clone,Default)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(feature="openapi",derive(Object))]
+#[cfg_attr(feature="openapi",oai(deny_unknown_fields))]
+pub struct MonitorConfigInput{
+    #[serde(default)]
+    #[cfg_attr(feature="openapi",oai(default))]
+    pub websites:BTreeMap<String,WebsiteConfigInput>,
+    #[serde(default)]
+    #[cfg_attr(feature="openapi",oai(default))]
+    pub channels:BTreeMap<String,ChannelConfigInput>,
+    #[serde(default)]
+    #[cfg_attr(feature="openapi",oai(default))]
+    pub status_pages:BTreeMap<String,StatusPageConfigInput>
+}
+
+/// Fields left out take their default, e.g. no tags or no group.
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(feature="openapi",derive(Object))]
+#[cfg_attr(feature="openapi",oai(deny_unknown_fields))]
+pub struct WebsiteConfigInput{
+    pub url:String,
+    #[serde(default)]
+    #[cfg_attr(feature="openapi",oai(default))]
+    pub paused:bool,
+    pub group_id:Option<String>,
+    #[serde(default)]
+    #[cfg_attr(feature="openapi",oai(default))]
+    pub tags:BTreeMap<String,String>,
+    /// Keys of other websites in the document.
+    #[serde(default)]
+    #[cfg_attr(feature="openapi",oai(default))]
+    pub depends_on:Vec<String>,
+    pub escalation_policy_id:Option<String>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(feature="openapi",derive(Object))]
+#[cfg_attr(feature="openapi",oai(deny_unknown_fields))]
+pub struct ChannelConfigInput{
+    pub kind:String,
+    pub target:String,
+    /// Left out, webhooks get a generated secret and an existing secret is kept.
+    pub secret:Option<String>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(feature="openapi",derive(Object))]
+#[cfg_attr(feature="openapi",oai(deny_unknown_fields))]
+pub struct StatusPageConfigInput{
+    pub slug:String,
+    pub title:String,
+    pub logo_url:Option<String>,
+    /// Shown in this order.
+    #[serde(default)]
+    #[cfg_attr(feature="openapi",oai(default))]
+    pub components:Vec<ComponentConfigInput>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[serde(deny_unknown_fields)]
+#[cfg_attr(feature="openapi",derive(Object))]
+#[cfg_attr(feature="openapi",oai(deny_unknown_fields))]
+pub struct ComponentConfigInput{
+    /// Key of a website in the document.
+    pub website:String,
+    pub name:String
+}
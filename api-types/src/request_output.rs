@@ -0,0 +1,380 @@
+use std::collections::BTreeMap;
+#[cfg(feature="openapi")]
+use poem_openapi::Object;
+use serde::{Serialize,Deserialize};
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct CreateWebsiteOutput{
+  pub id:String
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct CreateUserOutput{
+  pub id:String
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct SigninOutput{
+  pub jwt:String
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct ApiKeyOutput{
+  pub id:String,
+  pub name:String,
+  /// The start of the key, to tell keys apart.
+  pub prefix:String,
+  /// Only returned when the key is created; send it in the `authorization` header.
+  pub key:Option<String>,
+  pub time_added:String,
+  pub last_used_at:Option<String>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct TeamMemberOutput{
+  pub id:String,
+  /// Whose team it is.
+  pub owner_id:String,
+  pub owner_username:String,
+  pub member_id:String,
+  pub member_username:String,
+  pub invited_at:String,
+  /// `None` while the invitation is pending.
+  pub accepted_at:Option<String>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct GetWebsiteOutput{
+  pub url:String,
+  pub id:String,
+  pub paused:bool,
+  pub group_id:Option<String>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct NotificationChannelOutput{
+  pub id:String,
+  pub kind:String,
+  pub target:String,
+  pub secret:Option<String>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct NotificationDeliveryOutput{
+  pub id:String,
+  pub incident_id:String,
+  pub channel_id:String,
+  pub attempt:i32,
+  pub status_code:Option<i32>,
+  pub latency_ms:i32,
+  pub error:Option<String>,
+  pub attempted_at:String
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct IncidentOutput{
+  pub id:String,
+  pub website_id:String,
+  pub status:String,
+  pub opened_at:String,
+  pub acknowledged_at:Option<String>,
+  pub acknowledged_by:Option<String>,
+  pub resolved_at:Option<String>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct EscalationLevelOutput{
+  pub position:i32,
+  pub delay_minutes:i32,
+  pub channel_id:Option<String>,
+  pub schedule_id:Option<String>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct EscalationPolicyOutput{
+  pub id:String,
+  pub name:String,
+  pub levels:Vec<EscalationLevelOutput>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct OnCallScheduleOutput{
+  pub id:String,
+  pub name:String,
+  pub rotation:String,
+  pub rotation_start:String,
+  pub participants:Vec<String>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct OnCallOverrideOutput{
+  pub id:String,
+  pub user_id:String,
+  pub starts_at:String,
+  pub ends_at:String
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct OnCallOutput{
+  pub schedule_id:String,
+  pub user_id:Option<String>,
+  pub overrides:Vec<OnCallOverrideOutput>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct IncidentEventOutput{
+  pub id:String,
+  pub kind:String,
+  pub user_id:Option<String>,
+  pub message:String,
+  pub created_at:String
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct SlackLinkCodeOutput{
+  pub code:String,
+  pub expires_at:String
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct MaintenanceWindowOutput{
+  pub id:String,
+  pub website_id:Option<String>,
+  pub group_id:Option<String>,
+  pub title:String,
+  pub starts_at:String,
+  pub duration_minutes:i32,
+  pub recurrence:Option<String>,
+  pub until:Option<String>,
+  /// Next occurrences as [start, end] pairs.
+  pub upcoming:Vec<[String;2]>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct UptimeOutput{
+  pub website_id:String,
+  pub since:String,
+  pub up_ticks:i64,
+  pub down_ticks:i64,
+  pub maintenance_ticks:i64,
+  /// `null` until the website has results outside maintenance.
+  pub uptime_percentage:Option<f64>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct DependenciesOutput{
+  pub website_id:String,
+  pub depends_on:Vec<String>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct WebsiteSummaryOutput{
+  pub id:String,
+  pub url:String,
+  pub paused:bool,
+  pub group_id:Option<String>,
+  /// `up`, `down`, `unknown` or `paused`.
+  pub status:String,
+  pub tags:BTreeMap<String,String>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct WebsiteGroupOutput{
+  pub id:String,
+  pub name:String,
+  pub parent_id:Option<String>,
+  /// Worst status among the websites in the group and its subgroups.
+  pub status:String,
+  pub website_count:usize
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct TagsOutput{
+  pub website_id:String,
+  pub tags:BTreeMap<String,String>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct StatusPageComponentOutput{
+  pub id:String,
+  pub website_id:String,
+  pub name:String
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct StatusPageOutput{
+  pub id:String,
+  pub slug:String,
+  pub title:String,
+  pub logo_url:Option<String>,
+  pub components:Vec<StatusPageComponentOutput>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct UptimeDayOutput{
+  /// `YYYY-MM-DD`, UTC.
+  pub date:String,
+  /// `null` for days without results.
+  pub uptime_percentage:Option<f64>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct PublicComponentOutput{
+  /// What subscribers pick when following only some components.
+  pub id:String,
+  pub name:String,
+  pub status:String,
+  pub uptime_percentage:Option<f64>,
+  /// Oldest day first.
+  pub days:Vec<UptimeDayOutput>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct PublicIncidentOutput{
+  pub id:String,
+  pub component:String,
+  pub status:String,
+  pub opened_at:String,
+  pub resolved_at:Option<String>
+}
+
+/// What `GET /status-page/:slug` shows the public; no ids of websites or users.
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct PublicStatusPageOutput{
+  pub slug:String,
+  pub title:String,
+  pub logo_url:Option<String>,
+  /// Worst status among the components.
+  pub status:String,
+  pub components:Vec<PublicComponentOutput>,
+  pub incidents:Vec<PublicIncidentOutput>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct SubscriptionOutput{
+  pub id:String,
+  pub kind:String,
+  /// Every component when empty.
+  pub component_ids:Vec<String>,
+  /// Email subscriptions stay unconfirmed until the link in the confirmation email is opened.
+  pub confirmed:bool,
+  /// Webhook signing secret, only returned when the subscription is created.
+  pub secret:Option<String>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct FeedTokenOutput{
+  pub token:String,
+  pub incidents_url:String,
+  pub maintenance_url:String
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct BadgeOutput{
+  pub status_url:String,
+  /// Takes `?period=30d`.
+  pub uptime_url:String,
+  /// Takes `?period=24h`.
+  pub response_time_url:String
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct LiveEventOutput{
+  /// `tick`, `status` or `resync`.
+  pub event:String,
+  pub data:serde_json::Value
+}
+
+/// What it takes to make the account match a monitors-as-code document. Empty when it already does.
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct ConfigPlanOutput{
+  pub changes:Vec<ConfigChangeOutput>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct ConfigChangeOutput{
+  /// `create`, `update` or `delete`.
+  pub action:String,
+  /// `website`, `channel` or `status_page`.
+  pub resource:String,
+  pub key:String,
+  /// `null` for resources a plan would create.
+  pub id:Option<String>,
+  pub fields:Vec<FieldChangeOutput>
+}
+
+/// What an import changed, or with `dry_run` would change, plus entries it left out and why.
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct ImportOutput{
+  pub changes:Vec<ConfigChangeOutput>,
+  pub skipped:Vec<SkippedImportOutput>
+}
+
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct SkippedImportOutput{
+  /// The entry's id in the export, or its line for CSV.
+  pub source:String,
+  pub url:Option<String>,
+  pub reason:String
+}
+
+/// Values as text. Secrets are hidden, except one `apply` generated, which is shown this once.
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct FieldChangeOutput{
+  pub field:String,
+  pub from:Option<String>,
+  pub to:Option<String>
+}
+
+/// RFC 9457 problem details, the body of every error response.
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[cfg_attr(feature="openapi",derive(Object))]
+pub struct Problem{
+  /// Always `about:blank`, the status says what happened.
+  #[serde(rename="type")]
+  #[cfg_attr(feature="openapi",oai(rename="type"))]
+  pub kind:String,
+  pub title:String,
+  pub status:u16,
+  /// What exactly was wrong, when there's more to say than the title.
+  #[serde(skip_serializing_if="Option::is_none")]
+  #[cfg_attr(feature="openapi",oai(skip_serializing_if_is_none))]
+  pub detail:Option<String>
+}
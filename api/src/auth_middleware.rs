@@ -1,24 +1,25 @@
 use jsonwebtoken::{decode, DecodingKey, Validation};
-use poem::{FromRequest,Error,http::StatusCode,Result};
+use poem::{Request,Result};
+use poem_openapi::{auth::ApiKey, SecurityScheme};
+use db::{db::Db, models::api_key::API_KEY_PREFIX};
 
-use crate::routes::user::Claims;
+use crate::{error::ApiError, routes::v1::user::Claims};
 
+/// The jwt from `/user/signin` or a key from `/user/api-key`, sent as is in the `authorization` header.
+#[derive(SecurityScheme)]
+#[oai(rename="jwt",ty="api_key",key_in="header",key_name="authorization",checker="user_id_from_token")]
 pub struct UserId(pub String);
 
-impl <'a> FromRequest<'a> for UserId{
-    async fn from_request(
-            req: &'a poem::Request,
-            _body: &mut poem::RequestBody,
-        ) ->Result<Self> {
-          let token: &str = req
-                .headers()
-                .get("authorization")
-                .and_then(|value| value.to_str().ok())
-                .ok_or_else(|| Error::from_string("missing token", StatusCode::UNAUTHORIZED))?;
+async fn user_id_from_token(req:&Request,token:ApiKey)->Result<String>{
+    if token.key.starts_with(API_KEY_PREFIX){
+        let s=req.data::<Db>().ok_or(ApiError::Internal)?;
+        let user_id=s.get_user_id_by_api_key(&token.key).await.map_err(|e| match e{
+            db::error::DbError::InvalidCredentials=>ApiError::Unauthorized("unknown or revoked api key".to_string()),
+            e=>ApiError::from(e)
+        })?;
+        return Ok(user_id);
+    }
+    let token_data = decode::<Claims>(&token.key, &DecodingKey::from_secret("secret".as_ref()), &Validation::default()).map_err(|_| ApiError::Unauthorized("token malformed".to_string()))?;
 
-                let token_data = decode::<Claims>(&token, &DecodingKey::from_secret("secret".as_ref()), &Validation::default()).map_err(|_| Error::from_string("token malformed", StatusCode::UNAUTHORIZED))?;
-
-                Ok(UserId(token_data.claims.sub))
-
-        }
-}
\ No newline at end of file
+    Ok(token_data.claims.sub)
+}
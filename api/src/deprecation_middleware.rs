@@ -0,0 +1,28 @@
+use std::sync::Arc;
+use poem::{http::{Method, Uri}, Endpoint, IntoResponse, Request, Response, Result};
+
+use crate::routes::v1;
+
+/// When the unversioned paths were deprecated, 2026-10-19, as an RFC 9745 date.
+const DEPRECATED_AT: &str = "@1792368000";
+/// When they stop being served, as an RFC 8594 HTTP date.
+const SUNSET_AT: &str = "Mon, 19 Apr 2027 00:00:00 GMT";
+
+/// Wraps the unversioned paths, which serve v1 for clients that predate versioning,
+/// and points every response at its `/v1` successor.
+pub async fn deprecated<E:Endpoint>(ep:Arc<E>,mut req:Request)->Result<Response>{
+    // creating a website was `POST /websites` before versioning, v1 lists websites there
+    if req.method()==Method::POST && req.uri().path()=="/websites"{
+        *req.uri_mut()=Uri::from_static("/website");
+    }
+    let successor=format!("<{}{}>; rel=\"successor-version\"", v1::PREFIX, req.uri().path());
+    let mut response=ep.call(req).await?.into_response();
+
+    let headers=response.headers_mut();
+    headers.insert("deprecation",DEPRECATED_AT.parse().expect("valid header"));
+    headers.insert("sunset",SUNSET_AT.parse().expect("valid header"));
+    if let Ok(link)=successor.parse(){
+        headers.append("link",link);
+    }
+    Ok(response)
+}
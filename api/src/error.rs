@@ -0,0 +1,135 @@
+use std::fmt;
+use db::error::DbError;
+use poem::{
+    error::ResponseError, http::StatusCode, web::Json, Error, IntoResponse, Response
+};
+use api_types::request_output::Problem;
+use poem_openapi::{registry::{MetaMediaType, MetaResponse, MetaResponses, Registry}, types::Type, ApiResponse};
+
+/// Errors handlers return. Every error response, these and poem's own, is rendered
+/// as an RFC 9457 problem details body by [`render_error`].
+#[derive(Debug)]
+pub enum ApiError{
+    BadRequest(String),
+    Unauthorized(String),
+    NotFound(String),
+    Conflict(String),
+    /// The link or token was valid once but has expired.
+    Gone(String),
+    /// Well-formed but refers to something that doesn't exist, e.g. an unknown website id.
+    Unprocessable(String),
+    /// A service we depend on, like the mail server, failed.
+    BadGateway(String),
+    /// The database or a feature's backing service can't be used right now.
+    Unavailable(String),
+    Internal
+}
+
+impl fmt::Display for ApiError{
+    fn fmt(&self,f:&mut fmt::Formatter<'_>)->fmt::Result{
+        match self{
+            ApiError::BadRequest(detail)
+            | ApiError::Unauthorized(detail)
+            | ApiError::NotFound(detail)
+            | ApiError::Conflict(detail)
+            | ApiError::Gone(detail)
+            | ApiError::Unprocessable(detail)
+            | ApiError::BadGateway(detail)
+            | ApiError::Unavailable(detail)=>write!(f,"{}",detail),
+            ApiError::Internal=>write!(f,"something went wrong on our side")
+        }
+    }
+}
+
+impl std::error::Error for ApiError{}
+
+impl ResponseError for ApiError{
+    fn status(&self)->StatusCode{
+        match self{
+            ApiError::BadRequest(_)=>StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized(_)=>StatusCode::UNAUTHORIZED,
+            ApiError::NotFound(_)=>StatusCode::NOT_FOUND,
+            ApiError::Conflict(_)=>StatusCode::CONFLICT,
+            ApiError::Gone(_)=>StatusCode::GONE,
+            ApiError::Unprocessable(_)=>StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::BadGateway(_)=>StatusCode::BAD_GATEWAY,
+            ApiError::Unavailable(_)=>StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Internal=>StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+
+    fn as_response(&self)->Response{
+        problem(self.status(),self.to_string())
+    }
+}
+
+/// Documents every error status in the OpenAPI document, each with a problem details body.
+impl ApiResponse for ApiError{
+    fn meta()->MetaResponses{
+        let statuses=[
+            (StatusCode::BAD_REQUEST,"The request is invalid"),
+            (StatusCode::UNAUTHORIZED,"Missing or invalid credentials"),
+            (StatusCode::NOT_FOUND,"Doesn't exist or belongs to someone else"),
+            (StatusCode::CONFLICT,"Conflicts with something that already exists"),
+            (StatusCode::GONE,"The link or token has expired"),
+            (StatusCode::UNPROCESSABLE_ENTITY,"Refers to something that doesn't exist"),
+            (StatusCode::BAD_GATEWAY,"A service we depend on failed"),
+            (StatusCode::SERVICE_UNAVAILABLE,"Temporarily unavailable"),
+            (StatusCode::INTERNAL_SERVER_ERROR,"Something went wrong on our side")
+        ];
+        MetaResponses{
+            responses:statuses.into_iter().map(|(status,description)| MetaResponse{
+                description,
+                status:Some(status.as_u16()),
+                status_range:None,
+                content:vec![MetaMediaType{
+                    content_type:PROBLEM_CONTENT_TYPE,
+                    schema:Problem::schema_ref()
+                }],
+                headers:vec![]
+            }).collect()
+        }
+    }
+
+    fn register(registry:&mut Registry){
+        Problem::register(registry);
+    }
+}
+
+impl From<DbError> for ApiError{
+    fn from(e:DbError)->Self{
+        match e{
+            DbError::NotFound=>ApiError::NotFound("not found".to_string()),
+            DbError::Conflict(_)=>ApiError::Conflict("this already exists".to_string()),
+            DbError::InvalidReference(_)=>ApiError::Unprocessable("refers to something that doesn't exist".to_string()),
+            DbError::InvalidCredentials=>ApiError::Unauthorized("invalid username or password".to_string()),
+            DbError::Unavailable(_)=>ApiError::Unavailable("the database is temporarily unavailable, try again shortly".to_string()),
+            DbError::Query(_)=>ApiError::Internal
+        }
+    }
+}
+
+const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+fn problem(status:StatusCode,detail:String)->Response{
+    let title=status.canonical_reason().unwrap_or("Error");
+    let body=Problem{
+        kind:"about:blank".to_string(),
+        // poem's bare status errors only repeat the title
+        detail:Some(detail).filter(|d| d!=title),
+        title:title.to_string(),
+        status:status.as_u16()
+    };
+    Json(body)
+        .with_content_type(PROBLEM_CONTENT_TYPE)
+        .with_status(status)
+        .into_response()
+}
+
+/// Last stop for every error the app returns, so clients always get problem details.
+pub async fn render_error(e:Error)->Response{
+    if e.is::<ApiError>(){
+        return e.into_response();
+    }
+    problem(e.status(),e.to_string())
+}
@@ -1,26 +1,46 @@
 pub mod routes;
-pub mod request_input;
-pub mod request_output;
 pub mod auth_middleware;
+pub mod deprecation_middleware;
+pub mod error;
 
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use poem::{
-    get, EndpointExt,listener::TcpListener, post, Route, Server
+    EndpointExt,listener::TcpListener, Route, Server
 };
-use crate::{ routes::{user::{sign_in, sign_up}, website::{create_website, get_website,get_websites}}};
+use crate::{ deprecation_middleware::deprecated, error::render_error, routes::v1::{self, live::spawn_live_relay}};
 use db::db::Db;
+use redisstream::init_redis;
+use notifier::{config::NotifierConfig, email::Mailer, ssrf::{GuardedClient, SsrfPolicy}};
 
 #[tokio::main]
 async fn main() -> Result<(), std::io::Error> {
 
-    let  s=Arc::new(Mutex::new(Db::new().unwrap()));
+    let  s=Db::new().map_err(|e| std::io::Error::other(format!("could not set up the database pool: {}", e)))?;
+    let redis_url=std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1/".into());
+    init_redis(&redis_url).await
+        .map_err(|e| std::io::Error::other(format!("invalid REDIS_URL {}: {}", redis_url, e)))?;
+    let live=spawn_live_relay();
+    let notifier_config=Arc::new(NotifierConfig::default());
+    // status page email subscriptions are refused without SMTP
+    let mailer=Mailer::from_config(&notifier_config)
+        .map_err(std::io::Error::other)?
+        .map(Arc::new);
+    // subscriber webhooks are posted to from here too, for the confirmation request
+    let policy=SsrfPolicy::from_env().map_err(std::io::Error::other)?;
+    let http=GuardedClient::new(Arc::new(policy),std::time::Duration::from_secs(10)).map_err(std::io::Error::other)?;
+    // the paths from before versioning keep serving v1 until the sunset date
+    let legacy=v1::routes(&notifier_config.api_url)
+        .catch_all_error(render_error)
+        .around(deprecated);
     let app = Route::new()
-        .at("/status/:website_id", get(get_website))
-        .at("/website",post(create_website))
-        .at("/user/signin",post(sign_in))
-        .at("/user/signup",post(sign_up))
-        .at("/websites",post(get_websites))
-        .data(s);
+        .nest(v1::PREFIX,v1::routes(&notifier_config.api_url))
+        .nest("/",legacy)
+        .data(s)
+        .data(notifier_config)
+        .data(mailer)
+        .data(http)
+        .data(live)
+        .catch_all_error(render_error);
 
     Server::new(TcpListener::bind("0.0.0.0:3003"))
         .name("hello-world")
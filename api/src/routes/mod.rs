@@ -1,2 +1,45 @@
-pub mod website;
-pub mod user;
\ No newline at end of file
+//! One module per api version, each mounted under its own prefix. Versions can share
+//! handlers, a new version only needs its own copy of what changed.
+
+use poem_openapi::{ApiResponse, Tags};
+
+pub mod v1;
+
+/// Success without a body.
+#[derive(ApiResponse)]
+pub enum NoContent{
+    #[oai(status=204)]
+    NoContent
+}
+
+/// Groups the operations in the OpenAPI document.
+#[derive(Tags)]
+pub enum ApiTags{
+    /// Monitored websites, their uptime, tags and dependencies.
+    Websites,
+    Users,
+    /// Website groups and their rolled up status.
+    Groups,
+    Incidents,
+    /// Where alerts are delivered.
+    Notifications,
+    /// Who gets alerted, and after how long.
+    Escalation,
+    OnCall,
+    Maintenance,
+    /// Slash commands and buttons of the Slack app.
+    Slack,
+    StatusPages,
+    /// Status page updates by email or webhook.
+    Subscriptions,
+    /// Atom and iCalendar feeds.
+    Feeds,
+    /// Embeddable SVG badges.
+    Badges,
+    /// Streams of check results and status changes.
+    Live,
+    /// Websites, channels and status pages described by a document and kept in line with it.
+    Config,
+    /// Exporting an account's monitors and importing them, also from other services.
+    Transfer
+}
@@ -0,0 +1,203 @@
+use std::sync::Arc;
+use chrono::{Duration, Utc};
+use poem::{web::Data, Request};
+use poem_openapi::{param::{Path, Query}, payload::{Json, PlainText}, ApiResponse, OpenApi};
+use crate::{auth_middleware::UserId, error::ApiError, routes::ApiTags};
+use api_types::request_output::BadgeOutput;
+use db::{db::Db, models::{website::Website, website_group::MonitorState}};
+use notifier::config::NotifierConfig;
+use sha2::{Digest, Sha256};
+
+/// Status changes should show up quickly, uptime and response time move slowly.
+const STATUS_MAX_AGE_SECS: u32 = 60;
+const AGGREGATE_MAX_AGE_SECS: u32 = 300;
+
+const GREEN: &str = "#4c1";
+const YELLOW_GREEN: &str = "#a4a61d";
+const YELLOW: &str = "#dfb317";
+const RED: &str = "#e05d44";
+const GREY: &str = "#9f9f9f";
+
+/// `30d` or `12h`, at most a year.
+fn parse_period(period:Option<&str>,default:&str)->Result<(Duration,String),ApiError>{
+    let period=period.unwrap_or(default);
+    let invalid=|| ApiError::BadRequest("period must look like 30d or 24h, at most 365d".to_string());
+    let (amount,hours_per_unit)=match (period.strip_suffix('d'),period.strip_suffix('h')){
+        (Some(days),_)=>(days,24),
+        (_,Some(hours))=>(hours,1),
+        _=>return Err(invalid())
+    };
+    let amount:i64=amount.parse().map_err(|_| invalid())?;
+    // checked before building the Duration, which panics on huge values
+    if !(1..=365*24).contains(&(amount.saturating_mul(hours_per_unit))){
+        return Err(invalid());
+    }
+    Ok((Duration::hours(amount*hours_per_unit),period.to_string()))
+}
+
+/// Rough Verdana 11px advance, close enough for badges to look like shields.io ones.
+fn text_width(text:&str)->usize{
+    text.chars().map(|c| match c{
+        'i'|'l'|'.'|':'|'|'|'!'|'\''=>3,
+        'm'|'w'|'M'|'W'|'%'=>10,
+        ' '=>4,
+        _=>7
+    }).sum()
+}
+
+fn escape_xml(s:&str)->String{
+    s.replace('&',"&amp;").replace('<',"&lt;").replace('>',"&gt;").replace('"',"&quot;").replace('\'',"&#39;")
+}
+
+fn render_badge(label:&str,value:&str,color:&str)->String{
+    let label_width=text_width(label)+10;
+    let value_width=text_width(value)+10;
+    let width=label_width+value_width;
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"20\" role=\"img\" aria-label=\"{label}: {value}\">\
+         <title>{label}: {value}</title>\
+         <linearGradient id=\"s\" x2=\"0\" y2=\"100%\"><stop offset=\"0\" stop-color=\"#bbb\" stop-opacity=\".1\"/><stop offset=\"1\" stop-opacity=\".1\"/></linearGradient>\
+         <clipPath id=\"r\"><rect width=\"{width}\" height=\"20\" rx=\"3\" fill=\"#fff\"/></clipPath>\
+         <g clip-path=\"url(#r)\"><rect width=\"{label_width}\" height=\"20\" fill=\"#555\"/><rect x=\"{label_width}\" width=\"{value_width}\" height=\"20\" fill=\"{color}\"/><rect width=\"{width}\" height=\"20\" fill=\"url(#s)\"/></g>\
+         <g fill=\"#fff\" text-anchor=\"middle\" font-family=\"Verdana,Geneva,DejaVu Sans,sans-serif\" font-size=\"11\">\
+         <text x=\"{label_x}\" y=\"15\" fill=\"#010101\" fill-opacity=\".3\">{label}</text><text x=\"{label_x}\" y=\"14\">{label}</text>\
+         <text x=\"{value_x}\" y=\"15\" fill=\"#010101\" fill-opacity=\".3\">{value}</text><text x=\"{value_x}\" y=\"14\">{value}</text></g></svg>",
+        width=width,
+        label_width=label_width,
+        value_width=value_width,
+        label_x=label_width/2,
+        value_x=label_width+value_width/2,
+        label=escape_xml(label),
+        value=escape_xml(value),
+        color=color
+    )
+}
+
+#[derive(ApiResponse)]
+pub enum BadgeResponse{
+    #[oai(status=200,content_type="image/svg+xml; charset=utf-8")]
+    Svg(PlainText<String>,#[oai(header="etag")] String,#[oai(header="cache-control")] String),
+    /// The badge matches the client's `If-None-Match`.
+    #[oai(status=304)]
+    NotModified(#[oai(header="etag")] String,#[oai(header="cache-control")] String)
+}
+
+/// The svg with caching headers; answers 304 when the client's ETag still matches.
+fn badge_response(req:&Request,svg:String,max_age:u32)->BadgeResponse{
+    // stable across builds and instances, unlike std's hasher
+    let digest=Sha256::digest(svg.as_bytes());
+    let etag=format!("\"{}\"", digest[..16].iter().map(|b| format!("{:02x}", b)).collect::<String>());
+    let cache_control=format!("public, max-age={}", max_age);
+
+    let not_modified=req.header("if-none-match")
+        .is_some_and(|tags| tags.split(',').any(|t| t.trim()==etag || t.trim()=="*"));
+    if not_modified{
+        return BadgeResponse::NotModified(etag,cache_control);
+    }
+    BadgeResponse::Svg(PlainText(svg),etag,cache_control)
+}
+
+async fn badge_website(s:&Db,token:String)->Result<Website,ApiError>{
+    s.get_website_by_badge_token(token).await.map_err(ApiError::from)
+}
+
+fn badge_output(config:&NotifierConfig,website:&Website)->BadgeOutput{
+    let base=format!("{}/badge/{}", config.versioned_api_url(), website.badge_token);
+    BadgeOutput{
+        status_url:format!("{}/status.svg", base),
+        uptime_url:format!("{}/uptime.svg", base),
+        response_time_url:format!("{}/response-time.svg", base)
+    }
+}
+
+pub struct BadgeApi;
+
+#[OpenApi(tag="ApiTags::Badges")]
+impl BadgeApi{
+    /// Unauthenticated, the token in the url is the only thing identifying the website.
+    #[oai(path="/badge/:token/status.svg",method="get")]
+    async fn status_badge(&self,req:&Request,Path(token): Path<String>,Data(s):Data<&Db>)->Result<BadgeResponse,ApiError>{
+        let website=badge_website(s,token).await?;
+        let state=s.get_monitor_states(std::slice::from_ref(&website)).await?
+            .get(&website.id).copied().unwrap_or(MonitorState::Unknown);
+        let color=match state{
+            MonitorState::Up=>GREEN,
+            MonitorState::Down=>RED,
+            MonitorState::Paused|MonitorState::Unknown=>GREY
+        };
+
+        Ok(badge_response(req,render_badge("status",state.as_str(),color),STATUS_MAX_AGE_SECS))
+    }
+
+    /// `?period=30d` by default, maintenance windows and pauses don't count.
+    #[oai(path="/badge/:token/uptime.svg",method="get")]
+    async fn uptime_badge(&self,req:&Request,Path(token): Path<String>,Query(period):Query<Option<String>>,Data(s):Data<&Db>)->Result<BadgeResponse,ApiError>{
+        let (period,label)=parse_period(period.as_deref(),"30d")?;
+        let website=badge_website(s,token).await?;
+        let uptime=s.get_uptime(website.id,(Utc::now()-period).naive_utc()).await?;
+        let (value,color)=match uptime.percentage(){
+            None=>("no data".to_string(),GREY),
+            Some(p)=>(format!("{:.2}%", p),match p{
+                p if p>=99.9=>GREEN,
+                p if p>=99.0=>YELLOW_GREEN,
+                p if p>=95.0=>YELLOW,
+                _=>RED
+            })
+        };
+
+        Ok(badge_response(req,render_badge(&format!("uptime {}", label),&value,color),AGGREGATE_MAX_AGE_SECS))
+    }
+
+    /// Mean response time of successful checks, `?period=24h` by default.
+    #[oai(path="/badge/:token/response-time.svg",method="get")]
+    async fn response_time_badge(&self,req:&Request,Path(token): Path<String>,Query(period):Query<Option<String>>,Data(s):Data<&Db>)->Result<BadgeResponse,ApiError>{
+        let (period,label)=parse_period(period.as_deref(),"24h")?;
+        let website=badge_website(s,token).await?;
+        let average=s.get_average_response_time(website.id,(Utc::now()-period).naive_utc()).await?;
+        let (value,color)=match average{
+            None=>("no data".to_string(),GREY),
+            Some(ms)=>(format!("{:.0} ms", ms),match ms{
+                ms if ms<300.0=>GREEN,
+                ms if ms<1000.0=>YELLOW,
+                _=>RED
+            })
+        };
+
+        Ok(badge_response(req,render_badge(&format!("response time {}", label),&value,color),AGGREGATE_MAX_AGE_SECS))
+    }
+
+    #[oai(path="/website/:id/badges",method="get")]
+    async fn get_badges(&self,Path(id): Path<String>,Data(s):Data<&Db>,Data(config):Data<&Arc<NotifierConfig>>,UserId(user_id):UserId)->Result<Json<BadgeOutput>,ApiError>{
+        let website=s.get_website(user_id,id).await?;
+
+        Ok(Json(badge_output(config,&website)))
+    }
+
+    /// New badge urls for the website; the old ones stop working.
+    #[oai(path="/website/:id/badge-token",method="post")]
+    async fn rotate_badge_token(&self,Path(id): Path<String>,Data(s):Data<&Db>,Data(config):Data<&Arc<NotifierConfig>>,UserId(user_id):UserId)->Result<Json<BadgeOutput>,ApiError>{
+        let website=s.rotate_badge_token(user_id,id).await?;
+
+        Ok(Json(badge_output(config,&website)))
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn parses_periods(){
+        assert_eq!(parse_period(None,"30d").unwrap(),(Duration::days(30),"30d".to_string()));
+        assert_eq!(parse_period(Some("12h"),"30d").unwrap(),(Duration::hours(12),"12h".to_string()));
+        assert_eq!(parse_period(Some("365d"),"30d").unwrap().0,Duration::days(365));
+        assert_eq!(parse_period(Some("8760h"),"30d").unwrap().0,Duration::days(365));
+    }
+
+    #[test]
+    fn rejects_invalid_periods(){
+        for period in ["","d","0d","-1d","366d","8761h","30","30m","1.5d","30dd","9223372036854775807d","99999999999999h","3é","é"]{
+            assert!(matches!(parse_period(Some(period),"30d"),Err(ApiError::BadRequest(_))),"{:?} was accepted",period);
+        }
+    }
+}
@@ -0,0 +1,520 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use poem::web::Data;
+use poem_openapi::{payload::Json, OpenApi};
+use crate::{auth_middleware::UserId, error::ApiError, routes::{v1::{notification::validate_channel, status_page::{page_write_error, validate}, website::validate_tags}, ApiTags}};
+use api_types::{request_input::{ChannelConfigInput, ComponentConfigInput, MonitorConfigInput, StatusPageConfigInput, StatusPageInput, WebsiteConfigInput}, request_output::{ConfigChangeOutput, ConfigPlanOutput, FieldChangeOutput}};
+use db::{db::{Db, ScopedFutureExt}, error::DbError, models::{notification_channel::{NotificationChannel, CHANNEL_WEBHOOK}, status_page::{NewStatusPage, StatusPage, StatusPageComponent}, website::{normalize_url, Website, WebsiteFilter}}};
+
+/// Longest key a document can give a resource.
+const MAX_KEY_LEN:usize=128;
+
+/// Stands in for secrets in plans.
+const HIDDEN:&str="(hidden)";
+
+/// The user's resources, with what the document can describe about them.
+struct Current{
+    websites:Vec<Website>,
+    tags:HashMap<String,BTreeMap<String,String>>,
+    dependencies:HashMap<String,Vec<String>>,
+    channels:Vec<NotificationChannel>,
+    pages:Vec<(StatusPage,Vec<StatusPageComponent>)>
+}
+
+async fn load_current(s:&Db,user_id:&str)->Result<Current,ApiError>{
+    let websites=s.get_websites(user_id.to_string(),&WebsiteFilter::default()).await?;
+    let ids:Vec<String>=websites.iter().map(|w| w.id.clone()).collect();
+    let mut tags:HashMap<String,BTreeMap<String,String>>=HashMap::new();
+    for tag in s.get_website_tags(&ids).await?{
+        tags.entry(tag.website_id).or_default().insert(tag.key,tag.value);
+    }
+    let mut dependencies:HashMap<String,Vec<String>>=HashMap::new();
+    for dependency in s.get_websites_dependencies(&ids).await?{
+        dependencies.entry(dependency.website_id).or_default().push(dependency.depends_on_id);
+    }
+
+    Ok(Current{
+        websites,
+        tags,
+        dependencies,
+        channels:s.get_notification_channels(user_id.to_string()).await?,
+        pages:s.get_status_pages(user_id.to_string()).await?
+    })
+}
+
+/// Says which resource a validation error is about.
+fn in_resource(resource:&str,key:&str,e:ApiError)->ApiError{
+    match e{
+        ApiError::BadRequest(detail)=>ApiError::BadRequest(format!("{} `{}`: {}", resource, key, detail)),
+        e=>e
+    }
+}
+
+fn validate_key(resource:&str,key:&str)->Result<(),ApiError>{
+    if key.trim().is_empty() || key.len()>MAX_KEY_LEN{
+        return Err(ApiError::BadRequest(format!("{} keys must be 1-{} characters", resource, MAX_KEY_LEN)));
+    }
+    Ok(())
+}
+
+/// A website that (transitively) depends on itself, if any.
+fn find_dependency_cycle(websites:&BTreeMap<String,WebsiteConfigInput>)->Option<&str>{
+    /// `true` while a key's dependencies are being visited, `false` once they're known to be fine.
+    fn visit<'a>(key:&'a str,websites:&'a BTreeMap<String,WebsiteConfigInput>,visiting:&mut HashMap<&'a str,bool>)->Option<&'a str>{
+        match visiting.get(key){
+            Some(true)=>return Some(key),
+            Some(false)=>return None,
+            None=>{}
+        }
+        visiting.insert(key,true);
+        for dependency in &websites[key].depends_on{
+            if let Some(cycle)=visit(dependency,websites,visiting){
+                return Some(cycle);
+            }
+        }
+        visiting.insert(key,false);
+        None
+    }
+
+    let mut visiting=HashMap::new();
+    websites.keys().find_map(|key| visit(key,websites,&mut visiting))
+}
+
+/// Rejects documents that can't be applied, before anything is changed. Normalizes urls in place.
+async fn validate_document(s:&Db,user_id:&str,doc:&mut MonitorConfigInput)->Result<(),ApiError>{
+    for (key,website) in doc.websites.iter_mut(){
+        validate_key("website",key)?;
+        website.url=normalize_url(&website.url).map_err(|e| ApiError::BadRequest(format!("website `{}`: {}", key, e)))?;
+    }
+
+    let mut urls=HashMap::new();
+    for (key,website) in &doc.websites{
+        if let Some(other)=urls.insert(&website.url,key){
+            return Err(ApiError::BadRequest(format!("websites `{}` and `{}` have the same url", other, key)));
+        }
+        validate_tags(&website.tags).map_err(|e| in_resource("website",key,e))?;
+        for dependency in &website.depends_on{
+            if !doc.websites.contains_key(dependency){
+                return Err(ApiError::BadRequest(format!("website `{}` depends on `{}`, which isn't in the document", key, dependency)));
+            }
+        }
+    }
+    if let Some(key)=find_dependency_cycle(&doc.websites){
+        return Err(ApiError::BadRequest(format!("website `{}` depends on itself", key)));
+    }
+
+    for (key,channel) in &doc.channels{
+        validate_key("channel",key)?;
+        validate_channel(&channel.kind,&channel.target).map_err(|e| in_resource("channel",key,e))?;
+    }
+
+    let mut slugs=HashMap::new();
+    for (key,page) in &doc.status_pages{
+        validate_key("status page",key)?;
+        let input=StatusPageInput{ slug:page.slug.clone(),title:page.title.clone(),logo_url:page.logo_url.clone(),components:Vec::new() };
+        validate(input).map_err(|e| in_resource("status page",key,e))?;
+        if let Some(other)=slugs.insert(&page.slug,key){
+            return Err(ApiError::BadRequest(format!("status pages `{}` and `{}` have the same slug", other, key)));
+        }
+        for component in &page.components{
+            if !doc.websites.contains_key(&component.website){
+                return Err(ApiError::BadRequest(format!("status page `{}` shows `{}`, which isn't in the document", key, component.website)));
+            }
+        }
+    }
+
+    // well-formed, now check what it points at exists
+    let groups:HashSet<String>=s.get_website_groups(user_id.to_string()).await?.into_iter().map(|g| g.id).collect();
+    let policies:HashSet<String>=s.get_escalation_policies(user_id.to_string()).await?.into_iter().map(|(p,_)| p.id).collect();
+    for (key,website) in &doc.websites{
+        if website.group_id.as_ref().is_some_and(|g| !groups.contains(g)){
+            return Err(ApiError::Unprocessable(format!("website `{}`: unknown group", key)));
+        }
+        if website.escalation_policy_id.as_ref().is_some_and(|p| !policies.contains(p)){
+            return Err(ApiError::Unprocessable(format!("website `{}`: unknown escalation policy", key)));
+        }
+    }
+    Ok(())
+}
+
+/// Pairs document keys with the existing resources they manage, by index. A resource already
+/// named by the key wins; otherwise one no other key claims and that `same` considers equal is
+/// adopted, so resources made before the document, or under a renamed key, keep their history.
+fn match_keys<D,T>(desired:&BTreeMap<String,D>,existing:&[T],key_of:impl Fn(&T)->Option<&String>,same:impl Fn(&D,&T)->bool)->HashMap<String,usize>{
+    let mut matched:HashMap<String,usize>=existing.iter().enumerate()
+        .filter_map(|(i,t)| key_of(t).filter(|k| desired.contains_key(*k)).map(|k| (k.clone(),i)))
+        .collect();
+    let mut taken:HashSet<usize>=matched.values().copied().collect();
+    for (key,d) in desired{
+        if matched.contains_key(key){
+            continue;
+        }
+        if let Some(i)=(0..existing.len()).find(|i| !taken.contains(i) && same(d,&existing[*i])){
+            taken.insert(i);
+            matched.insert(key.clone(),i);
+        }
+    }
+    matched
+}
+
+fn diff(fields:&mut Vec<FieldChangeOutput>,field:&str,from:Option<String>,to:Option<String>){
+    if from!=to{
+        fields.push(FieldChangeOutput{ field:field.to_string(),from,to });
+    }
+}
+
+fn flag(value:bool)->Option<String>{
+    value.then(|| "true".to_string())
+}
+
+fn tags_text(tags:&BTreeMap<String,String>)->Option<String>{
+    (!tags.is_empty()).then(|| tags.iter().map(|(k,v)| format!("{}:{}", k, v)).collect::<Vec<_>>().join(","))
+}
+
+fn list_text(items:&[String])->Option<String>{
+    (!items.is_empty()).then(|| items.join(", "))
+}
+
+fn change(action:&str,resource:&str,key:&str,id:Option<String>,fields:Vec<FieldChangeOutput>)->ConfigChangeOutput{
+    ConfigChangeOutput{ action:action.to_string(),resource:resource.to_string(),key:key.to_string(),id,fields }
+}
+
+/// What a document is to the account.
+#[derive(Clone,Copy,PartialEq)]
+pub enum Mode{
+    /// The whole truth: resources take on its keys and managed ones it leaves out are deleted.
+    Apply,
+    /// Additions: nothing is deleted and no keys are recorded, resources are matched by
+    /// url, slug or target as well as by keys recorded earlier.
+    Import
+}
+
+/// Works out what differs between the account and the document and, unless `dry_run`, changes it
+/// in one transaction, so a failure part way leaves the account as it was.
+pub async fn converge(s:&Db,user_id:String,doc:MonitorConfigInput,mode:Mode,dry_run:bool)->Result<ConfigPlanOutput,ApiError>{
+    if dry_run{
+        return converge_in(s,user_id,doc,mode,false).await;
+    }
+    s.transaction(|tx| async move{ converge_in(tx,user_id,doc,mode,true).await }.scope_boxed()).await
+}
+
+/// Creates and updates go in key order, channels then websites then status pages, and deletes last.
+async fn converge_in(s:&Db,user_id:String,mut doc:MonitorConfigInput,mode:Mode,apply:bool)->Result<ConfigPlanOutput,ApiError>{
+    validate_document(s,&user_id,&mut doc).await?;
+    let manage=mode==Mode::Apply;
+    let current=load_current(s,&user_id).await?;
+    let mut changes=Vec::new();
+
+    let channel_matches=match_keys(&doc.channels,&current.channels,|c| c.external_key.as_ref(),|d,c| d.kind==c.kind && d.target==c.target);
+    for (key,channel) in &doc.channels{
+        let mut fields=Vec::new();
+        let Some(existing)=channel_matches.get(key).map(|i| &current.channels[*i]) else{
+            diff(&mut fields,"kind",None,Some(channel.kind.clone()));
+            diff(&mut fields,"target",None,Some(channel.target.clone()));
+            let mut id=None;
+            if apply{
+                let created=s.create_notification_channel(user_id.clone(),channel.kind.clone(),channel.target.clone(),channel.secret.clone()).await?;
+                if manage{
+                    s.set_notification_channel_external_key(user_id.clone(),created.id.clone(),Some(key.clone())).await?;
+                }
+                // like creating it directly, a generated secret is shown this once
+                let secret=if channel.secret.is_some(){ Some(HIDDEN.to_string()) }else{ created.secret };
+                diff(&mut fields,"secret",None,secret);
+                id=Some(created.id);
+            }else if channel.secret.is_some() || channel.kind==CHANNEL_WEBHOOK{
+                diff(&mut fields,"secret",None,Some(HIDDEN.to_string()));
+            }
+            changes.push(change("create","channel",key,id,fields));
+            continue;
+        };
+
+        if manage{
+            diff(&mut fields,"key",existing.external_key.clone(),Some(key.clone()));
+        }
+        diff(&mut fields,"kind",Some(existing.kind.clone()),Some(channel.kind.clone()));
+        diff(&mut fields,"target",Some(existing.target.clone()),Some(channel.target.clone()));
+        if channel.secret.is_some() && channel.secret!=existing.secret{
+            diff(&mut fields,"secret",existing.secret.as_ref().map(|_| HIDDEN.to_string()),Some(format!("{} (new)", HIDDEN)));
+        }
+        if fields.is_empty(){
+            continue;
+        }
+        if apply{
+            if fields.iter().any(|f| f.field!="key"){
+                s.update_notification_channel(user_id.clone(),existing.id.clone(),channel.kind.clone(),channel.target.clone(),channel.secret.clone()).await?;
+            }
+            if manage && existing.external_key.as_ref()!=Some(key){
+                s.set_notification_channel_external_key(user_id.clone(),existing.id.clone(),Some(key.clone())).await?;
+            }
+        }
+        changes.push(change("update","channel",key,Some(existing.id.clone()),fields));
+    }
+
+    let website_matches=match_keys(&doc.websites,&current.websites,|w| w.external_key.as_ref(),|d,w| d.url==w.url);
+    // dependencies and components are compared by key; ones the document doesn't manage show as ids
+    let key_of_id:HashMap<&str,&str>=website_matches.iter().map(|(k,i)| (current.websites[*i].id.as_str(),k.as_str())).collect();
+    let as_key=|id:&String| key_of_id.get(id.as_str()).map(|k| k.to_string()).unwrap_or_else(|| id.clone());
+    let mut website_ids:HashMap<&str,String>=website_matches.iter().map(|(k,i)| (k.as_str(),current.websites[*i].id.clone())).collect();
+    // set once every website exists, since they can point at websites created after them
+    let mut pending_dependencies=Vec::new();
+    // websites in the document may trade urls, which only has to hold once they all changed
+    if apply{
+        s.set_website_url_check_deferred(true).await?;
+    }
+
+    for (key,website) in &doc.websites{
+        let existing=website_matches.get(key).map(|i| &current.websites[*i]);
+        let mut fields=Vec::new();
+        if let Some(existing)=existing.filter(|_| manage){
+            diff(&mut fields,"key",existing.external_key.clone(),Some(key.clone()));
+        }
+        diff(&mut fields,"url",existing.map(|e| e.url.clone()),Some(website.url.clone()));
+        diff(&mut fields,"paused",existing.and_then(|e| flag(e.paused)),flag(website.paused));
+        diff(&mut fields,"group_id",existing.and_then(|e| e.group_id.clone()),website.group_id.clone());
+        diff(&mut fields,"tags",existing.and_then(|e| current.tags.get(&e.id)).and_then(tags_text),tags_text(&website.tags));
+        diff(&mut fields,"escalation_policy_id",existing.and_then(|e| e.escalation_policy_id.clone()),website.escalation_policy_id.clone());
+        let mut current_dependencies:Vec<String>=existing.and_then(|e| current.dependencies.get(&e.id)).map(|d| d.iter().map(as_key).collect()).unwrap_or_default();
+        current_dependencies.sort();
+        let mut dependencies=website.depends_on.clone();
+        dependencies.sort();
+        dependencies.dedup();
+        diff(&mut fields,"depends_on",list_text(&current_dependencies),list_text(&dependencies));
+        if fields.is_empty(){
+            continue;
+        }
+
+        let changed=|field:&str| fields.iter().any(|f| f.field==field);
+        if apply{
+            let url_taken=|e:DbError| match e{
+                DbError::Conflict(_)=>ApiError::Conflict(format!("website `{}`: you already monitor {}", key, website.url)),
+                e=>ApiError::from(e)
+            };
+            let id=match existing{
+                Some(existing)=>{
+                    if changed("url"){
+                        s.update_website_url(user_id.clone(),existing.id.clone(),website.url.clone()).await.map_err(url_taken)?;
+                    }
+                    existing.id.clone()
+                }
+                None=>s.create_website(user_id.clone(),website.url.clone()).await.map_err(url_taken)?.id
+            };
+            if manage && (existing.is_none() || changed("key")){
+                s.set_website_external_key(user_id.clone(),id.clone(),Some(key.clone())).await?;
+            }
+            if changed("paused"){
+                if website.paused{
+                    s.pause_websites(user_id.clone(),vec![id.clone()]).await?;
+                }else{
+                    s.resume_websites(user_id.clone(),vec![id.clone()]).await?;
+                }
+            }
+            if changed("group_id"){
+                s.set_website_group(user_id.clone(),id.clone(),website.group_id.clone()).await?;
+            }
+            if changed("tags"){
+                s.set_website_tags(user_id.clone(),id.clone(),website.tags.clone().into_iter().collect()).await?;
+            }
+            if changed("escalation_policy_id"){
+                s.set_website_escalation_policy(user_id.clone(),id.clone(),website.escalation_policy_id.clone()).await?;
+            }
+            website_ids.insert(key,id);
+        }
+        if changed("depends_on"){
+            pending_dependencies.push((key,dependencies));
+        }
+        let action=if existing.is_some(){ "update" }else{ "create" };
+        changes.push(change(action,"website",key,website_ids.get(key.as_str()).cloned(),fields));
+    }
+    if apply{
+        s.set_website_url_check_deferred(false).await.map_err(|e| match e{
+            DbError::Conflict(_)=>ApiError::Conflict("the document monitors a url twice, or one you monitor outside it".to_string()),
+            e=>ApiError::from(e)
+        })?;
+        for (key,dependencies) in pending_dependencies{
+            let ids=dependencies.iter().map(|d| website_ids[d.as_str()].clone()).collect();
+            s.set_website_dependencies(user_id.clone(),website_ids[key.as_str()].clone(),ids).await.map_err(|e| match e{
+                DbError::Conflict(detail)=>ApiError::BadRequest(format!("website `{}`: {}", key, detail)),
+                e=>ApiError::from(e)
+            })?;
+        }
+    }
+
+    let page_matches=match_keys(&doc.status_pages,&current.pages,|(p,_)| p.external_key.as_ref(),|d,(p,_)| d.slug==p.slug);
+    for (key,page) in &doc.status_pages{
+        let existing=page_matches.get(key).map(|i| &current.pages[*i]);
+        let mut fields=Vec::new();
+        if let Some((existing,_))=existing.filter(|_| manage){
+            diff(&mut fields,"key",existing.external_key.clone(),Some(key.clone()));
+        }
+        diff(&mut fields,"slug",existing.map(|(p,_)| p.slug.clone()),Some(page.slug.clone()));
+        diff(&mut fields,"title",existing.map(|(p,_)| p.title.clone()),Some(page.title.clone()));
+        diff(&mut fields,"logo_url",existing.and_then(|(p,_)| p.logo_url.clone()),page.logo_url.clone());
+        let current_components:Vec<String>=existing.map(|(_,components)| components.iter().map(|c| format!("{} ({})", as_key(&c.website_id), c.name)).collect()).unwrap_or_default();
+        let components:Vec<String>=page.components.iter().map(|c| format!("{} ({})", c.website, c.name)).collect();
+        diff(&mut fields,"components",list_text(&current_components),list_text(&components));
+        if fields.is_empty(){
+            continue;
+        }
+
+        let mut id=existing.map(|(p,_)| p.id.clone());
+        if apply{
+            let new_page=NewStatusPage{
+                slug:page.slug.clone(),
+                title:page.title.clone(),
+                logo_url:page.logo_url.clone(),
+                components:page.components.iter().map(|c| (website_ids[c.website.as_str()].clone(),c.name.clone())).collect()
+            };
+            let page_id=match existing{
+                Some((existing,_))=>{
+                    if fields.iter().any(|f| f.field!="key"){
+                        s.update_status_page(user_id.clone(),existing.id.clone(),new_page).await.map_err(page_write_error)?;
+                    }
+                    existing.id.clone()
+                }
+                None=>s.create_status_page(user_id.clone(),new_page).await.map_err(page_write_error)?.0.id
+            };
+            if manage && (existing.is_none() || fields.iter().any(|f| f.field=="key")){
+                s.set_status_page_external_key(user_id.clone(),page_id.clone(),Some(key.clone())).await?;
+            }
+            id=Some(page_id);
+        }
+        let action=if existing.is_some(){ "update" }else{ "create" };
+        changes.push(change(action,"status_page",key,id,fields));
+    }
+
+    if !manage{
+        return Ok(ConfigPlanOutput{ changes });
+    }
+
+    // managed resources the document no longer lists; pages first as they show websites
+    let page_kept:HashSet<usize>=page_matches.into_values().collect();
+    for (i,(page,_)) in current.pages.iter().enumerate(){
+        let Some(key)=page.external_key.as_ref().filter(|_| !page_kept.contains(&i)) else{ continue };
+        if apply{
+            s.delete_status_page(user_id.clone(),page.id.clone()).await?;
+        }
+        changes.push(change("delete","status_page",key,Some(page.id.clone()),vec![FieldChangeOutput{ field:"slug".to_string(),from:Some(page.slug.clone()),to:None }]));
+    }
+    let website_kept:HashSet<usize>=website_matches.into_values().collect();
+    for (i,website) in current.websites.iter().enumerate(){
+        let Some(key)=website.external_key.as_ref().filter(|_| !website_kept.contains(&i)) else{ continue };
+        if apply{
+            s.delete_website(user_id.clone(),website.id.clone()).await?;
+        }
+        changes.push(change("delete","website",key,Some(website.id.clone()),vec![FieldChangeOutput{ field:"url".to_string(),from:Some(website.url.clone()),to:None }]));
+    }
+    let channel_kept:HashSet<usize>=channel_matches.into_values().collect();
+    for (i,channel) in current.channels.iter().enumerate(){
+        let Some(key)=channel.external_key.as_ref().filter(|_| !channel_kept.contains(&i)) else{ continue };
+        if apply{
+            s.delete_notification_channel(user_id.clone(),channel.id.clone()).await.map_err(|e| match e{
+                DbError::Conflict(detail)=>ApiError::Conflict(format!("channel `{}`: {}", key, detail)),
+                e=>ApiError::from(e)
+            })?;
+        }
+        changes.push(change("delete","channel",key,Some(channel.id.clone()),vec![FieldChangeOutput{ field:"kind".to_string(),from:Some(channel.kind.clone()),to:None }]));
+    }
+
+    Ok(ConfigPlanOutput{ changes })
+}
+
+/// The account as a document: every website, channel and status page, under its key or, for
+/// resources no document manages, its id. Secrets are left out, so importing keeps existing ones.
+pub async fn export_document(s:&Db,user_id:&str)->Result<MonitorConfigInput,ApiError>{
+    let current=load_current(s,user_id).await?;
+    let key_of_id:HashMap<&str,&str>=current.websites.iter().map(|w| (w.id.as_str(),w.external_key.as_deref().unwrap_or(&w.id))).collect();
+    let as_key=|id:&String| key_of_id.get(id.as_str()).map(|k| k.to_string()).unwrap_or_else(|| id.clone());
+
+    let mut doc=MonitorConfigInput::default();
+    for website in &current.websites{
+        doc.websites.insert(as_key(&website.id),WebsiteConfigInput{
+            url:website.url.clone(),
+            paused:website.paused,
+            group_id:website.group_id.clone(),
+            tags:current.tags.get(&website.id).cloned().unwrap_or_default(),
+            depends_on:current.dependencies.get(&website.id).map(|d| d.iter().map(as_key).collect()).unwrap_or_default(),
+            escalation_policy_id:website.escalation_policy_id.clone()
+        });
+    }
+    for channel in &current.channels{
+        let key=channel.external_key.clone().unwrap_or_else(|| channel.id.clone());
+        doc.channels.insert(key,ChannelConfigInput{ kind:channel.kind.clone(),target:channel.target.clone(),secret:None });
+    }
+    for (page,components) in &current.pages{
+        let key=page.external_key.clone().unwrap_or_else(|| page.id.clone());
+        doc.status_pages.insert(key,StatusPageConfigInput{
+            slug:page.slug.clone(),
+            title:page.title.clone(),
+            logo_url:page.logo_url.clone(),
+            components:components.iter().map(|c| ComponentConfigInput{ website:as_key(&c.website_id),name:c.name.clone() }).collect()
+        });
+    }
+    Ok(doc)
+}
+
+pub struct ConfigApi;
+
+#[OpenApi(tag="ApiTags::Config")]
+impl ConfigApi{
+    /// What applying the document would change, without changing anything.
+    #[oai(path="/config/plan",method="post")]
+    async fn plan_config(&self,Json(data):Json<MonitorConfigInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<ConfigPlanOutput>,ApiError>{
+        Ok(Json(converge(s,user_id,data,Mode::Apply,true).await?))
+    }
+
+    /// Creates, updates and deletes until the account matches the document, and returns what changed.
+    /// All or nothing: if a change fails, none of them are kept.
+    #[oai(path="/config/apply",method="post")]
+    async fn apply_config(&self,Json(data):Json<MonitorConfigInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<ConfigPlanOutput>,ApiError>{
+        Ok(Json(converge(s,user_id,data,Mode::Apply,false).await?))
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// (recorded key, url)
+    fn existing(resources:&[(Option<&str>,&str)])->Vec<(Option<String>,String)>{
+        resources.iter().map(|(k,url)| (k.map(str::to_string),url.to_string())).collect()
+    }
+
+    fn desired(keys:&[(&str,&str)])->BTreeMap<String,String>{
+        keys.iter().map(|(k,url)| (k.to_string(),url.to_string())).collect()
+    }
+
+    fn matches(desired:&BTreeMap<String,String>,existing:&[(Option<String>,String)])->HashMap<String,usize>{
+        match_keys(desired,existing,|(k,_)| k.as_ref(),|d,(_,url)| d==url)
+    }
+
+    #[test]
+    fn recorded_keys_win_over_equal_resources(){
+        let current=existing(&[(None,"https://a.example.com"),(Some("a"),"https://old.example.com")]);
+
+        let matched=matches(&desired(&[("a","https://a.example.com")]),&current);
+        assert_eq!(matched,HashMap::from([("a".to_string(),1)]));
+    }
+
+    #[test]
+    fn adopts_equal_resources_nobody_claims(){
+        let current=existing(&[(None,"https://a.example.com"),(Some("renamed"),"https://b.example.com")]);
+
+        let matched=matches(&desired(&[("a","https://a.example.com"),("b","https://b.example.com")]),&current);
+        assert_eq!(matched,HashMap::from([("a".to_string(),0),("b".to_string(),1)]));
+    }
+
+    #[test]
+    fn adopts_each_resource_once(){
+        let current=existing(&[(None,"https://a.example.com"),(Some("kept"),"https://a.example.com")]);
+
+        // `kept` holds its own; `x` and `y` share the one unclaimed resource, first key first
+        let matched=matches(&desired(&[("kept","https://a.example.com"),("x","https://a.example.com"),("y","https://a.example.com")]),&current);
+        assert_eq!(matched,HashMap::from([("kept".to_string(),1),("x".to_string(),0)]));
+    }
+
+    #[test]
+    fn leaves_new_keys_unmatched(){
+        let current=existing(&[(Some("a"),"https://a.example.com")]);
+
+        assert!(matches(&desired(&[("b","https://b.example.com")]),&current).is_empty());
+    }
+}
@@ -0,0 +1,55 @@
+use poem::web::Data;
+use poem_openapi::{param::Path, payload::Json, OpenApi};
+use crate::{auth_middleware::UserId, error::ApiError, routes::{ApiTags, NoContent}};
+use api_types::{request_input::CreateEscalationPolicyInput, request_output::EscalationPolicyOutput};
+use db::{db::Db, error::DbError, models::escalation::NewEscalationLevel};
+
+pub struct EscalationApi;
+
+#[OpenApi(tag="ApiTags::Escalation")]
+impl EscalationApi{
+    #[oai(path="/escalation-policy",method="post")]
+    async fn create_escalation_policy(&self,Json(data):Json<CreateEscalationPolicyInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<EscalationPolicyOutput>,ApiError>{
+        if data.levels.is_empty(){
+            return Err(ApiError::BadRequest("a policy needs at least one level".to_string()));
+        }
+        for level in &data.levels{
+            if level.delay_minutes<0{
+                return Err(ApiError::BadRequest("delay_minutes can't be negative".to_string()));
+            }
+            if level.channel_id.is_some()==level.schedule_id.is_some(){
+                return Err(ApiError::BadRequest("each level needs exactly one of channel_id or schedule_id".to_string()));
+            }
+        }
+
+        let levels=data.levels.into_iter().map(|l| NewEscalationLevel{
+            delay_minutes:l.delay_minutes,
+            channel_id:l.channel_id,
+            schedule_id:l.schedule_id
+        }).collect();
+
+        let policy=s.create_escalation_policy(user_id, data.name, levels).await.map_err(|e| match e{
+            DbError::NotFound=>ApiError::Unprocessable("unknown channel or schedule".to_string()),
+            e=>ApiError::from(e)
+        })?;
+
+        Ok(Json(policy.into()))
+    }
+
+    #[oai(path="/escalation-policies",method="get")]
+    async fn get_escalation_policies(&self,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<Vec<EscalationPolicyOutput>>,ApiError>{
+        let policies=s.get_escalation_policies(user_id).await?;
+
+        Ok(Json(policies.into_iter().map(EscalationPolicyOutput::from).collect()))
+    }
+
+    #[oai(path="/escalation-policy/:id",method="delete")]
+    async fn delete_escalation_policy(&self,Path(id): Path<String>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<NoContent,ApiError>{
+        let deleted=s.delete_escalation_policy(user_id, id).await?;
+
+        if deleted==0{
+            return Err(ApiError::NotFound("not found".to_string()));
+        }
+        Ok(NoContent::NoContent)
+    }
+}
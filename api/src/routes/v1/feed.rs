@@ -0,0 +1,344 @@
+use std::{collections::HashMap, sync::Arc};
+use chrono::{Days, Duration, NaiveDateTime, Utc};
+use poem::web::Data;
+use poem_openapi::{param::Path, payload::{Json, PlainText}, ApiResponse, OpenApi};
+use crate::{auth_middleware::UserId, error::ApiError, routes::ApiTags};
+use api_types::request_output::FeedTokenOutput;
+use db::{db::{Db, Page}, models::{incident::Incident, incident_event::{IncidentEvent, EVENT_ACKNOWLEDGED, EVENT_OPENED, EVENT_RESOLVED}, maintenance_window::MaintenanceWindow, website::WebsiteFilter}};
+use notifier::config::NotifierConfig;
+
+/// Incidents in a feed, newest first.
+const FEED_INCIDENTS: i64 = 50;
+/// Days of incident history on a status page feed, same as the page itself.
+const PAGE_HISTORY_DAYS: u64 = 90;
+/// Past occurrences kept in calendars so recent maintenance doesn't vanish right away.
+const CALENDAR_PAST_DAYS: i64 = 30;
+/// How far ahead recurring windows are expanded.
+const CALENDAR_FUTURE_DAYS: i64 = 180;
+/// Cap per window, a window recurring every minute would otherwise flood the calendar.
+const CALENDAR_OCCURRENCES: usize = 200;
+
+/// One incident as an Atom entry. `name` is what the incident is about,
+/// `updates` its timeline as (time, text).
+struct FeedEntry{
+    incident:Incident,
+    name:String,
+    updates:Vec<(NaiveDateTime,String)>
+}
+
+/// One maintenance occurrence as a calendar event.
+struct CalendarEvent{
+    uid:String,
+    summary:String,
+    description:String,
+    starts_at:NaiveDateTime,
+    ends_at:NaiveDateTime
+}
+
+fn escape_xml(s:&str)->String{
+    s.replace('&',"&amp;").replace('<',"&lt;").replace('>',"&gt;").replace('"',"&quot;").replace('\'',"&#39;")
+}
+
+fn rfc3339(t:NaiveDateTime)->String{
+    t.and_utc().to_rfc3339()
+}
+
+fn render_atom(title:&str,self_url:&str,alternate_url:Option<&str>,entries:&[FeedEntry])->String{
+    let updated=entries.iter()
+        .map(|e| e.updates.last().map(|(t,_)| *t).unwrap_or(e.incident.opened_at))
+        .max()
+        .unwrap_or_else(|| Utc::now().naive_utc());
+
+    let mut xml=format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\">\
+         <id>{self_url}</id><title>{title}</title><updated>{updated}</updated>\
+         <link rel=\"self\" href=\"{self_url}\"/>{alternate}<author><name>{title}</name></author>",
+        self_url=escape_xml(self_url),
+        title=escape_xml(title),
+        updated=rfc3339(updated),
+        alternate=alternate_url.map(|u| format!("<link rel=\"alternate\" href=\"{}\"/>", escape_xml(u))).unwrap_or_default()
+    );
+    for entry in entries{
+        let incident=&entry.incident;
+        let headline=match incident.resolved_at{
+            Some(_)=>format!("{} was down (resolved)", entry.name),
+            None=>format!("{} is down", entry.name)
+        };
+        let content:String=entry.updates.iter()
+            .map(|(t,text)| format!("<p><strong>{}</strong> {}</p>", rfc3339(*t), escape_xml(text)))
+            .collect();
+        xml.push_str(&format!(
+            "<entry><id>urn:uuid:{id}</id><title>{headline}</title><published>{published}</published><updated>{updated}</updated>\
+             <content type=\"html\">{content}</content></entry>",
+            id=escape_xml(&incident.id),
+            headline=escape_xml(&headline),
+            published=rfc3339(incident.opened_at),
+            updated=rfc3339(entry.updates.last().map(|(t,_)| *t).unwrap_or(incident.opened_at)),
+            content=escape_xml(&content)
+        ));
+    }
+    xml.push_str("</feed>\n");
+    xml
+}
+
+fn escape_ical(s:&str)->String{
+    s.replace('\\',"\\\\").replace(';',"\\;").replace(',',"\\,").replace('\n',"\\n")
+}
+
+fn ical_time(t:NaiveDateTime)->String{
+    t.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Folds a content line at 75 octets as RFC 5545 requires, without splitting a character.
+fn fold_ical_line(line:&str,out:&mut String){
+    let mut width=0;
+    for c in line.chars(){
+        if width+c.len_utf8()>75{
+            out.push_str("\r\n ");
+            width=1;
+        }
+        out.push(c);
+        width+=c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn render_ical(name:&str,events:&[CalendarEvent])->String{
+    let stamp=ical_time(Utc::now().naive_utc());
+    let mut lines=vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//betteruptime//maintenance//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!("X-WR-CALNAME:{}", escape_ical(name))
+    ];
+    for event in events{
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}", event.uid),
+            format!("DTSTAMP:{}", stamp),
+            format!("DTSTART:{}", ical_time(event.starts_at)),
+            format!("DTEND:{}", ical_time(event.ends_at)),
+            format!("SUMMARY:{}", escape_ical(&event.summary)),
+            format!("DESCRIPTION:{}", escape_ical(&event.description)),
+            "TRANSP:OPAQUE".to_string(),
+            "END:VEVENT".to_string()
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut out=String::new();
+    for line in lines{
+        fold_ical_line(&line,&mut out);
+    }
+    out
+}
+
+#[derive(ApiResponse)]
+pub enum AtomResponse{
+    #[oai(status=200,content_type="application/atom+xml; charset=utf-8")]
+    Feed(PlainText<String>)
+}
+
+#[derive(ApiResponse)]
+pub enum IcalResponse{
+    #[oai(status=200,content_type="text/calendar; charset=utf-8")]
+    Calendar(PlainText<String>)
+}
+
+fn atom_response(body:String)->AtomResponse{
+    AtomResponse::Feed(PlainText(body))
+}
+
+fn ical_response(body:String)->IcalResponse{
+    IcalResponse::Calendar(PlainText(body))
+}
+
+/// Builds feed entries; `describe` turns an event into public text, `None` hides it.
+async fn feed_entries(s:&Db,incidents:Vec<Incident>,names:&HashMap<String,String>,describe:impl Fn(&IncidentEvent)->Option<String>)->Result<Vec<FeedEntry>,ApiError>{
+    let ids:Vec<String>=incidents.iter().map(|i| i.id.clone()).collect();
+    let events=s.get_incident_events(&ids).await?;
+
+    Ok(incidents.into_iter().map(|incident| {
+        let updates=events.iter()
+            .filter(|e| e.incident_id==incident.id)
+            .filter_map(|e| describe(e).map(|text| (e.created_at,text)))
+            .collect();
+        FeedEntry{
+            name:names.get(&incident.website_id).cloned().unwrap_or_else(|| incident.website_id.clone()),
+            incident,
+            updates
+        }
+    }).collect())
+}
+
+/// Website ids each window covers, keyed by window id.
+async fn window_coverage(s:&Db,windows:&[MaintenanceWindow])->Result<HashMap<String,Vec<String>>,ApiError>{
+    let mut coverage=HashMap::new();
+    for window in windows{
+        let covered=s.get_maintenance_window_websites(window).await?;
+        coverage.insert(window.id.clone(),covered);
+    }
+    Ok(coverage)
+}
+
+/// Occurrences of the windows between a month back and half a year ahead; `describe`
+/// names what a window covers.
+fn calendar_events(windows:&[MaintenanceWindow],describe:impl Fn(&MaintenanceWindow)->String)->Vec<CalendarEvent>{
+    let now=Utc::now().naive_utc();
+    let horizon=now+Duration::days(CALENDAR_FUTURE_DAYS);
+
+    let mut events=Vec::new();
+    for window in windows{
+        let occurrences:Vec<_>=window.occurrences_after(now-Duration::days(CALENDAR_PAST_DAYS),CALENDAR_OCCURRENCES)
+            .into_iter()
+            .filter(|(start,_)| *start<horizon)
+            .collect();
+        if occurrences.is_empty(){
+            continue;
+        }
+        let description=describe(window);
+        events.extend(occurrences.into_iter().map(|(starts_at,ends_at)| CalendarEvent{
+            uid:format!("{}-{}@betteruptime", window.id, starts_at.and_utc().timestamp()),
+            summary:window.title.clone(),
+            description:description.clone(),
+            starts_at,
+            ends_at
+        }));
+    }
+    events.sort_by_key(|e| e.starts_at);
+    events
+}
+
+fn public_update(event:&IncidentEvent)->Option<String>{
+    match event.kind.as_str(){
+        EVENT_OPENED=>Some("Outage detected.".to_string()),
+        EVENT_ACKNOWLEDGED=>Some("We are looking into it.".to_string()),
+        EVENT_RESOLVED=>Some("Resolved.".to_string()),
+        // escalations and who acted stay internal
+        _=>None
+    }
+}
+
+pub struct FeedApi;
+
+#[OpenApi(tag="ApiTags::Feeds")]
+impl FeedApi{
+    /// Unauthenticated Atom feed of a status page's incidents. Updates are the public
+    /// milestones only, internal messages never leave the team feed.
+    #[oai(path="/status-page/:slug/incidents.atom",method="get")]
+    async fn status_page_incident_feed(&self,Path(slug): Path<String>,Data(s):Data<&Db>,Data(config):Data<&Arc<NotifierConfig>>)->Result<AtomResponse,ApiError>{
+        let (page,components)=s.get_status_page_by_slug(slug).await?;
+        let since=(Utc::now().date_naive()-Days::new(PAGE_HISTORY_DAYS)).and_hms_opt(0,0,0).expect("midnight exists");
+
+        let names:HashMap<String,String>=components.iter().map(|c| (c.website_id.clone(),c.name.clone())).collect();
+        let mut incidents=s.get_website_incidents(names.keys().cloned().collect(), since).await?;
+        incidents.truncate(FEED_INCIDENTS as usize);
+        let entries=feed_entries(s,incidents,&names,public_update).await?;
+
+        let page_url=format!("{}/status-page/{}", config.versioned_api_url(), page.slug);
+        Ok(atom_response(render_atom(&page.title,&format!("{}/incidents.atom", page_url),Some(&page_url),&entries)))
+    }
+
+    /// Unauthenticated iCalendar feed of the maintenance affecting a status page's components.
+    #[oai(path="/status-page/:slug/maintenance.ics",method="get")]
+    async fn status_page_maintenance_calendar(&self,Path(slug): Path<String>,Data(s):Data<&Db>)->Result<IcalResponse,ApiError>{
+        let (page,components)=s.get_status_page_by_slug(slug).await?;
+
+        let website_ids:Vec<String>=components.iter().map(|c| c.website_id.clone()).collect();
+        let windows=s.get_websites_maintenance_windows(&website_ids).await?;
+        let coverage=window_coverage(s,&windows).await?;
+        let events=calendar_events(&windows,|window| {
+            let covered=&coverage[&window.id];
+            let affected:Vec<&str>=components.iter().filter(|c| covered.contains(&c.website_id)).map(|c| c.name.as_str()).collect();
+            format!("Affects: {}", affected.join(", "))
+        });
+
+        Ok(ical_response(render_ical(&format!("{} maintenance", page.title),&events)))
+    }
+
+    /// Issues a new secret feed url pair for the signed-in user, revoking the previous one.
+    #[oai(path="/user/feed-token",method="post")]
+    async fn create_feed_token(&self,Data(s):Data<&Db>,Data(config):Data<&Arc<NotifierConfig>>,UserId(user_id):UserId)->Result<Json<FeedTokenOutput>,ApiError>{
+        let token=s.rotate_feed_token(user_id).await?;
+
+        Ok(Json(FeedTokenOutput{
+            incidents_url:format!("{}/feed/{}/incidents.atom", config.versioned_api_url(), token),
+            maintenance_url:format!("{}/feed/{}/maintenance.ics", config.versioned_api_url(), token),
+            token
+        }))
+    }
+
+    /// Atom feed of every incident on the team's websites with the full timeline.
+    #[oai(path="/feed/:token/incidents.atom",method="get")]
+    async fn team_incident_feed(&self,Path(token): Path<String>,Data(s):Data<&Db>,Data(config):Data<&Arc<NotifierConfig>>)->Result<AtomResponse,ApiError>{
+        let user_id=s.get_user_id_by_feed_token(token.clone()).await?;
+
+        let websites=s.get_websites(user_id.clone(),&WebsiteFilter::default()).await?;
+        let names:HashMap<String,String>=websites.into_iter().map(|w| (w.id,w.url)).collect();
+        let incidents=s.get_incidents(user_id, &Page{ limit:Some(FEED_INCIDENTS),after:None }).await?;
+        let entries=feed_entries(s,incidents,&names,|e| Some(e.message.clone())).await?;
+
+        Ok(atom_response(render_atom("Incidents",&format!("{}/feed/{}/incidents.atom", config.versioned_api_url(), token),None,&entries)))
+    }
+
+    /// iCalendar feed of every maintenance window the team scheduled.
+    #[oai(path="/feed/:token/maintenance.ics",method="get")]
+    async fn team_maintenance_calendar(&self,Path(token): Path<String>,Data(s):Data<&Db>)->Result<IcalResponse,ApiError>{
+        let user_id=s.get_user_id_by_feed_token(token).await?;
+
+        let websites=s.get_websites(user_id.clone(),&WebsiteFilter::default()).await?;
+        let names:HashMap<String,String>=websites.into_iter().map(|w| (w.id,w.url)).collect();
+        let windows=s.get_maintenance_windows(user_id, None, None).await?;
+        let coverage=window_coverage(s,&windows).await?;
+        let events=calendar_events(&windows,|window| {
+            let affected:Vec<&str>=coverage[&window.id].iter().filter_map(|id| names.get(id)).map(String::as_str).collect();
+            format!("Affects: {}", affected.join(", "))
+        });
+
+        Ok(ical_response(render_ical("Maintenance",&events)))
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn folded(line:&str)->String{
+        let mut out=String::new();
+        fold_ical_line(line,&mut out);
+        out
+    }
+
+    #[test]
+    fn leaves_short_lines_whole(){
+        assert_eq!(folded("SUMMARY:Database upgrade"),"SUMMARY:Database upgrade\r\n");
+        assert_eq!(folded(&"x".repeat(75)),format!("{}\r\n","x".repeat(75)));
+    }
+
+    #[test]
+    fn folds_at_75_octets(){
+        let line=format!("DESCRIPTION:{}","a".repeat(200));
+        let out=folded(&line);
+
+        assert!(out.split("\r\n").all(|l| l.len()<=75));
+        assert!(out.split("\r\n").skip(1).filter(|l| !l.is_empty()).all(|l| l.starts_with(' ')));
+        // unfolding, as a reader does, gives the line back
+        assert_eq!(out.trim_end_matches("\r\n").replace("\r\n ",""),line);
+    }
+
+    #[test]
+    fn never_splits_a_character(){
+        // 3-octet characters don't line up with the 75 octet limit
+        let line=format!("SUMMARY:{}","€".repeat(60));
+        let out=folded(&line);
+
+        assert!(out.split("\r\n").all(|l| l.len()<=75));
+        assert_eq!(out.trim_end_matches("\r\n").replace("\r\n ",""),line);
+    }
+
+    #[test]
+    fn escapes_text_values(){
+        assert_eq!(escape_ical("Affects: API, Web; db\\main\nsee status"),r"Affects: API\, Web\; db\\main\nsee status");
+    }
+}
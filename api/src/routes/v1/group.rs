@@ -0,0 +1,72 @@
+use poem::web::Data;
+use poem_openapi::{param::Path, payload::Json, OpenApi};
+use crate::{auth_middleware::UserId, error::ApiError, routes::{ApiTags, NoContent}};
+use api_types::{request_input::WebsiteGroupInput, request_output::WebsiteGroupOutput};
+use db::{db::Db, error::DbError, models::website_group::{MonitorState, WebsiteGroup}};
+
+fn group_output(group:WebsiteGroup,state:MonitorState,website_count:usize)->WebsiteGroupOutput{
+    WebsiteGroupOutput{
+        id:group.id,
+        name:group.name,
+        parent_id:group.parent_id,
+        status:state.as_str().to_string(),
+        website_count
+    }
+}
+
+async fn find_group_state(s:&Db,user_id:String,id:&str)->Result<WebsiteGroupOutput,ApiError>{
+    s.get_group_states(user_id).await?
+        .into_iter()
+        .find(|(group,_,_)| group.id==id)
+        .map(|(group,state,count)| group_output(group,state,count))
+        .ok_or_else(|| ApiError::NotFound("not found".to_string()))
+}
+
+pub struct GroupApi;
+
+#[OpenApi(tag="ApiTags::Groups")]
+impl GroupApi{
+    #[oai(path="/website-group",method="post")]
+    async fn create_website_group(&self,Json(data):Json<WebsiteGroupInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<WebsiteGroupOutput>,ApiError>{
+        let group=s.create_website_group(user_id, data.name, data.parent_id).await.map_err(|e| match e{
+            DbError::NotFound=>ApiError::Unprocessable("unknown parent group".to_string()),
+            e=>ApiError::from(e)
+        })?;
+
+        Ok(Json(group_output(group,MonitorState::Unknown,0)))
+    }
+
+    /// Every group with the worst status of the websites in it, one tile per service.
+    #[oai(path="/website-groups",method="get")]
+    async fn get_website_groups(&self,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<Vec<WebsiteGroupOutput>>,ApiError>{
+        let groups=s.get_group_states(user_id).await?;
+
+        Ok(Json(groups.into_iter().map(|(group,state,count)| group_output(group,state,count)).collect()))
+    }
+
+    #[oai(path="/website-group/:id",method="get")]
+    async fn get_website_group(&self,Path(id): Path<String>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<WebsiteGroupOutput>,ApiError>{
+        Ok(Json(find_group_state(s,user_id,&id).await?))
+    }
+
+    #[oai(path="/website-group/:id",method="put")]
+    async fn update_website_group(&self,Path(id): Path<String>,Json(data):Json<WebsiteGroupInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<WebsiteGroupOutput>,ApiError>{
+        let updated=s.update_website_group(user_id.clone(), id.clone(), data.name, data.parent_id).await?;
+        if updated.is_none(){
+            return Err(ApiError::BadRequest("a group can't be moved into its own subgroup".to_string()));
+        }
+
+        Ok(Json(find_group_state(s,user_id,&id).await?))
+    }
+
+    /// Deletes the group and its subgroups; their websites are kept, ungrouped.
+    #[oai(path="/website-group/:id",method="delete")]
+    async fn delete_website_group(&self,Path(id): Path<String>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<NoContent,ApiError>{
+        let deleted=s.delete_website_group(user_id, id).await?;
+        if deleted==0{
+            return Err(ApiError::NotFound("not found".to_string()));
+        }
+
+        Ok(NoContent::NoContent)
+    }
+}
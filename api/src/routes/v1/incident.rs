@@ -0,0 +1,95 @@
+use std::sync::Arc;
+use chrono::Utc;
+use poem::web::Data;
+use poem_openapi::{param::{Path, Query}, payload::{Html, Json}, OpenApi};
+use crate::{auth_middleware::UserId, error::ApiError, routes::{v1::page, ApiTags}};
+use api_types::request_output::{IncidentEventOutput, IncidentOutput, NotificationDeliveryOutput};
+use db::db::Db;
+use notifier::{action_token::{verify, IncidentAction, TokenError}, config::NotifierConfig};
+
+/// How many incidents the listing returns without a `limit`.
+const INCIDENT_HISTORY: i64 = 100;
+
+fn verify_action_token(config:&NotifierConfig,token:&str)->Result<notifier::action_token::ActionClaims,ApiError>{
+    let secret=config.action_secret.as_deref().ok_or_else(|| ApiError::NotFound("not found".to_string()))?;
+    verify(secret, token, Utc::now()).map_err(|e| match e{
+        TokenError::Expired=>ApiError::Gone("this link has expired".to_string()),
+        TokenError::Malformed | TokenError::BadSignature=>ApiError::NotFound("invalid link".to_string())
+    })
+}
+
+pub struct IncidentApi;
+
+#[OpenApi(tag="ApiTags::Incidents")]
+impl IncidentApi{
+    /// Newest first. Pass the last id as `after` for the next page.
+    #[oai(path="/incidents",method="get")]
+    async fn get_incidents(&self,Query(limit):Query<Option<i64>>,Query(after):Query<Option<String>>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<Vec<IncidentOutput>>,ApiError>{
+        let incidents=s.get_incidents(user_id, &page(limit,after,Some(INCIDENT_HISTORY))?).await?;
+
+        Ok(Json(incidents.into_iter().map(IncidentOutput::from).collect()))
+    }
+
+    #[oai(path="/incident/:id/acknowledge",method="post")]
+    async fn acknowledge_incident(&self,Path(id): Path<String>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<IncidentOutput>,ApiError>{
+        let incident=s.acknowledge_incident(user_id, id, "the api").await?;
+
+        Ok(Json(incident.into()))
+    }
+
+    #[oai(path="/incident/:id/resolve",method="post")]
+    async fn resolve_incident(&self,Path(id): Path<String>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<IncidentOutput>,ApiError>{
+        let incident=s.resolve_incident(user_id, id, "the api").await?;
+
+        Ok(Json(incident.into()))
+    }
+
+    #[oai(path="/incident/:id/timeline",method="get")]
+    async fn get_incident_timeline(&self,Path(id): Path<String>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<Vec<IncidentEventOutput>>,ApiError>{
+        let events=s.get_incident_timeline(user_id, id).await?;
+
+        Ok(Json(events.into_iter().map(IncidentEventOutput::from).collect()))
+    }
+
+    /// Landing page of a one-click link. It only asks for confirmation, so link
+    /// previews and mail scanners fetching the URL can't change the incident.
+    #[oai(path="/incident/action/:token",method="get")]
+    async fn incident_action_page(&self,Path(token): Path<String>,Data(config):Data<&Arc<NotifierConfig>>)->Result<Html<String>,ApiError>{
+        let claims=verify_action_token(config,&token)?;
+        let label=match claims.action{
+            IncidentAction::Acknowledge=>"Acknowledge",
+            IncidentAction::Resolve=>"Resolve"
+        };
+
+        Ok(Html(format!(
+            "<!doctype html><html><head><meta name=\"viewport\" content=\"width=device-width\"><title>{label} incident</title></head>\
+             <body><form method=\"post\"><p>{label} incident <code>{id}</code>?</p><button type=\"submit\">{label}</button></form></body></html>",
+            label=label,
+            id=claims.incident_id
+        )))
+    }
+
+    #[oai(path="/incident/action/:token",method="post")]
+    async fn apply_incident_action(&self,Path(token): Path<String>,Data(s):Data<&Db>,Data(config):Data<&Arc<NotifierConfig>>)->Result<Html<String>,ApiError>{
+        let claims=verify_action_token(config,&token)?;
+
+        let incident=match claims.action{
+            IncidentAction::Acknowledge=>s.acknowledge_incident_as(claims.incident_id, claims.user_id, "link").await,
+            IncidentAction::Resolve=>s.resolve_incident_as(claims.incident_id, claims.user_id, "link").await
+        }?;
+
+        Ok(Html(format!(
+            "<!doctype html><html><head><meta name=\"viewport\" content=\"width=device-width\"><title>Incident {status}</title></head>\
+             <body><p>Incident <code>{id}</code> is {status}.</p></body></html>",
+            id=incident.id,
+            status=incident.status
+        )))
+    }
+
+    #[oai(path="/incident/:id/deliveries",method="get")]
+    async fn get_incident_deliveries(&self,Path(id): Path<String>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<Vec<NotificationDeliveryOutput>>,ApiError>{
+        let deliveries=s.get_incident_deliveries(user_id, id).await?;
+
+        Ok(Json(deliveries.into_iter().map(NotificationDeliveryOutput::from).collect()))
+    }
+}
@@ -0,0 +1,168 @@
+use std::{collections::HashSet, sync::Arc, time::{Duration, Instant}};
+use futures::{future::BoxFuture, stream::BoxStream, FutureExt, SinkExt, StreamExt};
+use poem::web::{sse::Event, websocket::{Message, WebSocket, WebSocketStream, WebSocketUpgraded}, Data};
+use poem_openapi::{payload::EventStream, OpenApi};
+use redisstream::{x_read_live, LiveEvent};
+use serde_json::json;
+use tokio::sync::broadcast;
+use crate::{auth_middleware::UserId, error::ApiError, routes::ApiTags};
+use api_types::request_output::LiveEventOutput;
+use db::{db::Db, error::DbError, models::website::WebsiteFilter};
+
+/// Events buffered per connection. A client further behind than this misses
+/// events and is told to resync instead of slowing down everyone else.
+const LIVE_BUFFER: usize = 1024;
+/// How often a connection re-reads which websites the user owns, so new ones show up.
+const OWNERSHIP_REFRESH: Duration = Duration::from_secs(30);
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+pub type LiveSender = broadcast::Sender<Arc<LiveEvent>>;
+
+/// Boxed so the socket endpoint's return type can be named.
+type SocketHandler = Box<dyn FnOnce(WebSocketStream)->BoxFuture<'static,()>+Send+Sync>;
+
+/// Relays the live redis stream to every connection of this api instance.
+pub fn spawn_live_relay()->LiveSender{
+    let (sender,_)=broadcast::channel(LIVE_BUFFER);
+    let relay=sender.clone();
+    tokio::spawn(async move {
+        let mut last_id="$".to_string();
+        loop{
+            match x_read_live(&last_id, 200, 5000).await{
+                Ok(Some(batch))=>{
+                    last_id=batch.last_id;
+                    for event in batch.events{
+                        // no receivers just means nobody is watching right now
+                        let _=relay.send(Arc::new(event));
+                    }
+                }
+                Ok(None)=>{}
+                Err(_)=>tokio::time::sleep(Duration::from_secs(1)).await
+            }
+        }
+    });
+    sender
+}
+
+enum Outgoing{
+    Event(Arc<LiveEvent>),
+    /// The connection fell behind and this many events were dropped; refetch state.
+    Resync(u64)
+}
+
+impl Outgoing{
+    fn kind(&self)->&str{
+        match self{
+            Outgoing::Event(event)=>&event.kind,
+            Outgoing::Resync(_)=>"resync"
+        }
+    }
+
+    fn data(&self)->serde_json::Value{
+        match self{
+            Outgoing::Event(event)=>json!(event.as_ref()),
+            Outgoing::Resync(missed)=>json!({ "missed": missed })
+        }
+    }
+
+    fn output(&self)->LiveEventOutput{
+        LiveEventOutput{ event:self.kind().to_string(),data:self.data() }
+    }
+}
+
+/// One client's view of the live feed: only the websites the user owns.
+struct LiveSubscription{
+    db:Db,
+    user_id:String,
+    receiver:broadcast::Receiver<Arc<LiveEvent>>,
+    owned:HashSet<String>,
+    refreshed_at:Instant
+}
+
+impl LiveSubscription{
+    async fn new(db:&Db,user_id:String,sender:&LiveSender)->Result<Self,ApiError>{
+        let mut subscription=LiveSubscription{
+            db:db.clone(),
+            user_id,
+            receiver:sender.subscribe(),
+            owned:HashSet::new(),
+            refreshed_at:Instant::now()
+        };
+        subscription.refresh().await?;
+        Ok(subscription)
+    }
+
+    async fn refresh(&mut self)->Result<(),DbError>{
+        let websites=self.db.get_websites(self.user_id.clone(),&WebsiteFilter::default()).await?;
+        self.owned=websites.into_iter().map(|w| w.id).collect();
+        self.refreshed_at=Instant::now();
+        Ok(())
+    }
+
+    /// Next event for this user, `None` once the relay is gone.
+    async fn next(&mut self)->Option<Outgoing>{
+        loop{
+            match self.receiver.recv().await{
+                Ok(event)=>{
+                    if self.refreshed_at.elapsed()>=OWNERSHIP_REFRESH{
+                        // keep the previous list if the db is briefly unavailable
+                        let _=self.refresh().await;
+                    }
+                    if self.owned.contains(&event.website_id){
+                        return Some(Outgoing::Event(event));
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(missed))=>return Some(Outgoing::Resync(missed)),
+                Err(broadcast::error::RecvError::Closed)=>return None
+            }
+        }
+    }
+}
+
+pub struct LiveApi;
+
+#[OpenApi(tag="ApiTags::Live")]
+impl LiveApi{
+    /// Server-sent events of tick results (`tick`) and status transitions (`status`)
+    /// for the caller's websites. A `resync` event means the client was too slow and missed some.
+    #[oai(path="/live",method="get")]
+    async fn live_events(&self,Data(s):Data<&Db>,Data(sender):Data<&LiveSender>,UserId(user_id):UserId)->Result<EventStream<BoxStream<'static,LiveEventOutput>>,ApiError>{
+        let subscription=LiveSubscription::new(s,user_id,sender).await?;
+        let events=futures::stream::unfold(subscription,|mut subscription| async move {
+            let outgoing=subscription.next().await?;
+            Some((outgoing.output(),subscription))
+        });
+
+        Ok(EventStream::new(events.boxed())
+            .keep_alive(KEEP_ALIVE)
+            .to_event(|e| Event::message(e.data.to_string()).event_type(e.event)))
+    }
+
+    /// Same feed as `/live` over a WebSocket, each message `{"event": ..., "data": ...}`.
+    #[oai(path="/live/ws",method="get")]
+    async fn live_socket(&self,ws:WebSocket,Data(s):Data<&Db>,Data(sender):Data<&LiveSender>,UserId(user_id):UserId)->Result<WebSocketUpgraded<SocketHandler>,ApiError>{
+        let mut subscription=LiveSubscription::new(s,user_id,sender).await?;
+
+        let handler:SocketHandler=Box::new(move |socket| async move {
+            let (mut sink,mut incoming)=socket.split();
+            loop{
+                tokio::select!{
+                    outgoing=subscription.next()=>{
+                        let Some(outgoing)=outgoing else { break };
+                        let text=json!(outgoing.output()).to_string();
+                        // a slow client blocks here and falls behind, which turns into a resync
+                        if sink.send(Message::Text(text)).await.is_err(){
+                            break;
+                        }
+                    }
+                    received=incoming.next()=>match received{
+                        Some(Ok(Message::Close(_)))|Some(Err(_))|None=>break,
+                        _=>{}
+                    }
+                }
+            }
+        }.boxed());
+
+        Ok(ws.on_upgrade(handler))
+    }
+}
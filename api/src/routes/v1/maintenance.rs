@@ -0,0 +1,70 @@
+use poem::web::Data;
+use poem_openapi::{param::{Path, Query}, payload::Json, OpenApi};
+use crate::{auth_middleware::UserId, error::ApiError, routes::{ApiTags, NoContent}};
+use api_types::{request_input::MaintenanceWindowInput, request_output::MaintenanceWindowOutput};
+use db::{db::Db, models::maintenance_window::{parse_recurrence, NewMaintenanceWindow}};
+
+fn validate(data:MaintenanceWindowInput)->Result<NewMaintenanceWindow,ApiError>{
+    if data.website_id.is_some()==data.group_id.is_some(){
+        return Err(ApiError::BadRequest("a window needs exactly one of website_id or group_id".to_string()));
+    }
+    if data.duration_minutes<=0{
+        return Err(ApiError::BadRequest("duration_minutes must be positive".to_string()));
+    }
+    if let Some(recurrence)=&data.recurrence{
+        parse_recurrence(recurrence).map_err(|e| ApiError::BadRequest(format!("invalid recurrence: {}", e)))?;
+    }
+    if data.until.is_some_and(|until| until<=data.starts_at){
+        return Err(ApiError::BadRequest("until must be after starts_at".to_string()));
+    }
+
+    Ok(NewMaintenanceWindow{
+        website_id:data.website_id,
+        group_id:data.group_id,
+        title:data.title,
+        starts_at:data.starts_at.naive_utc(),
+        duration_minutes:data.duration_minutes,
+        recurrence:data.recurrence,
+        until:data.until.map(|u| u.naive_utc())
+    })
+}
+
+pub struct MaintenanceApi;
+
+#[OpenApi(tag="ApiTags::Maintenance")]
+impl MaintenanceApi{
+    #[oai(path="/maintenance-window",method="post")]
+    async fn create_maintenance_window(&self,Json(data):Json<MaintenanceWindowInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<MaintenanceWindowOutput>,ApiError>{
+        let window=validate(data)?;
+
+        let window=s.create_maintenance_window(user_id, window).await?;
+
+        Ok(Json(window.into()))
+    }
+
+    #[oai(path="/maintenance-windows",method="get")]
+    async fn get_maintenance_windows(&self,Query(website_id):Query<Option<String>>,Query(group_id):Query<Option<String>>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<Vec<MaintenanceWindowOutput>>,ApiError>{
+        let windows=s.get_maintenance_windows(user_id, website_id, group_id).await?;
+
+        Ok(Json(windows.into_iter().map(MaintenanceWindowOutput::from).collect()))
+    }
+
+    #[oai(path="/maintenance-window/:id",method="put")]
+    async fn update_maintenance_window(&self,Path(id): Path<String>,Json(data):Json<MaintenanceWindowInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<MaintenanceWindowOutput>,ApiError>{
+        let window=validate(data)?;
+
+        let window=s.update_maintenance_window(user_id, id, window).await?;
+
+        Ok(Json(window.into()))
+    }
+
+    #[oai(path="/maintenance-window/:id",method="delete")]
+    async fn delete_maintenance_window(&self,Path(id): Path<String>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<NoContent,ApiError>{
+        let deleted=s.delete_maintenance_window(user_id, id).await?;
+        if deleted==0{
+            return Err(ApiError::NotFound("not found".to_string()));
+        }
+
+        Ok(NoContent::NoContent)
+    }
+}
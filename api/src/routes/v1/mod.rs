@@ -0,0 +1,60 @@
+//! The `/v1` api. Paths, inputs and outputs here only change in backwards compatible
+//! ways; anything else goes into a new version next to this one.
+
+use poem::Route;
+use poem_openapi::OpenApiService;
+use db::db::Page;
+
+use crate::error::ApiError;
+
+use self::{badge::BadgeApi, config::ConfigApi, escalation::EscalationApi, feed::FeedApi, group::GroupApi, incident::IncidentApi, live::LiveApi, maintenance::MaintenanceApi, notification::NotificationApi, on_call::OnCallApi, slack::SlackApi, status_page::StatusPageApi, subscription::SubscriptionApi, transfer::TransferApi, user::UserApi, website::WebsiteApi};
+
+pub mod website;
+pub mod user;
+pub mod notification;
+pub mod incident;
+pub mod escalation;
+pub mod on_call;
+pub mod slack;
+pub mod maintenance;
+pub mod group;
+pub mod status_page;
+pub mod subscription;
+pub mod feed;
+pub mod badge;
+pub mod live;
+pub mod config;
+pub mod transfer;
+
+pub const PREFIX: &str = "/v1";
+
+/// Most items a page of a listing holds.
+const MAX_PAGE_SIZE:i64=500;
+
+/// The `limit` and `after` query of a paginated listing. The next page starts after the last
+/// id of this one; a page shorter than `limit` is the last.
+pub fn page(limit:Option<i64>,after:Option<String>,default_limit:Option<i64>)->Result<Page,ApiError>{
+    let limit=limit.or(default_limit);
+    if limit.is_some_and(|l| !(1..=MAX_PAGE_SIZE).contains(&l)){
+        return Err(ApiError::BadRequest(format!("limit must be between 1 and {}", MAX_PAGE_SIZE)));
+    }
+    Ok(Page{ limit,after })
+}
+
+pub type Api = (WebsiteApi,UserApi,GroupApi,IncidentApi,NotificationApi,EscalationApi,OnCallApi,MaintenanceApi,SlackApi,StatusPageApi,SubscriptionApi,FeedApi,BadgeApi,LiveApi,ConfigApi,TransferApi);
+
+/// Every v1 endpoint, documented as served from `api_url` + [`PREFIX`].
+pub fn service(api_url:&str)->OpenApiService<Api,()>{
+    let api:Api=(WebsiteApi,UserApi,GroupApi,IncidentApi,NotificationApi,EscalationApi,OnCallApi,MaintenanceApi,SlackApi,StatusPageApi,SubscriptionApi,FeedApi,BadgeApi,LiveApi,ConfigApi,TransferApi);
+    OpenApiService::new(api,"betteruptime","1")
+        .server(format!("{}{}", api_url, PREFIX))
+}
+
+/// The endpoints plus their OpenAPI document at `/openapi.json` and Swagger UI at `/docs`.
+pub fn routes(api_url:&str)->Route{
+    let api=service(api_url);
+    Route::new()
+        .at("/openapi.json",api.spec_endpoint())
+        .nest("/docs",api.swagger_ui())
+        .nest("/",api)
+}
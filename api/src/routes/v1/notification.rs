@@ -0,0 +1,79 @@
+use poem::web::Data;
+use poem_openapi::{param::{Path, Query}, payload::Json, OpenApi};
+use crate::{auth_middleware::UserId, error::ApiError, routes::{v1::page, ApiTags, NoContent}};
+use api_types::{request_input::CreateNotificationChannelInput, request_output::{NotificationChannelOutput, NotificationDeliveryOutput}};
+use db::{db::Db, error::DbError, models::notification_channel::{KEY_CHANNEL_KINDS, URL_CHANNEL_KINDS}};
+
+/// How many attempts the channel history returns without a `limit`.
+const CHANNEL_DELIVERY_HISTORY: i64 = 100;
+
+/// Checks the target fits the kind of channel.
+pub fn validate_channel(kind:&str,target:&str)->Result<(),ApiError>{
+    if URL_CHANNEL_KINDS.contains(&kind){
+        if !(target.starts_with("https://") || target.starts_with("http://")){
+            return Err(ApiError::BadRequest("channel target must be an http(s) url".to_string()));
+        }
+    }else if KEY_CHANNEL_KINDS.contains(&kind){
+        if target.trim().is_empty(){
+            return Err(ApiError::BadRequest("channel target must be an integration key".to_string()));
+        }
+    }else{
+        return Err(ApiError::BadRequest(format!("unsupported channel kind `{}`",kind)));
+    }
+    Ok(())
+}
+
+pub struct NotificationApi;
+
+#[OpenApi(tag="ApiTags::Notifications")]
+impl NotificationApi{
+    #[oai(path="/notification-channel",method="post")]
+    async fn create_notification_channel(&self,Json(data):Json<CreateNotificationChannelInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<NotificationChannelOutput>,ApiError>{
+        validate_channel(&data.kind,&data.target)?;
+
+        let channel=s.create_notification_channel(user_id, data.kind, data.target, data.secret).await?;
+
+        // the secret is only ever shown once, so the receiver can be configured with it
+        let response=NotificationChannelOutput{
+            id:channel.id,
+            kind:channel.kind,
+            target:channel.target,
+            secret:channel.secret
+        };
+        Ok(Json(response))
+    }
+
+    #[oai(path="/notification-channels",method="get")]
+    async fn get_notification_channels(&self,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<Vec<NotificationChannelOutput>>,ApiError>{
+        let channels=s.get_notification_channels(user_id).await?;
+
+        let response=channels.into_iter().map(|c| NotificationChannelOutput{
+            id:c.id,
+            kind:c.kind,
+            target:c.target,
+            secret:None
+        }).collect();
+        Ok(Json(response))
+    }
+
+    #[oai(path="/notification-channel/:id",method="delete")]
+    async fn delete_notification_channel(&self,Path(id): Path<String>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<NoContent,ApiError>{
+        let deleted=s.delete_notification_channel(user_id, id).await.map_err(|e| match e{
+            DbError::Conflict(detail)=>ApiError::Conflict(detail),
+            e=>ApiError::from(e)
+        })?;
+
+        if deleted==0{
+            return Err(ApiError::NotFound("not found".to_string()));
+        }
+        Ok(NoContent::NoContent)
+    }
+
+    /// Newest first. Pass the last id as `after` for the next page.
+    #[oai(path="/notification-channel/:id/deliveries",method="get")]
+    async fn get_channel_deliveries(&self,Path(id): Path<String>,Query(limit):Query<Option<i64>>,Query(after):Query<Option<String>>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<Vec<NotificationDeliveryOutput>>,ApiError>{
+        let deliveries=s.get_channel_deliveries(user_id, id, &page(limit,after,Some(CHANNEL_DELIVERY_HISTORY))?).await?;
+
+        Ok(Json(deliveries.into_iter().map(NotificationDeliveryOutput::from).collect()))
+    }
+}
@@ -0,0 +1,80 @@
+use poem::web::Data;
+use poem_openapi::{param::Path, payload::Json, OpenApi};
+use crate::{auth_middleware::UserId, error::ApiError, routes::ApiTags};
+use api_types::{request_input::{CreateOnCallOverrideInput, CreateOnCallScheduleInput}, request_output::{OnCallOutput, OnCallOverrideOutput, OnCallScheduleOutput}};
+use db::{db::Db, models::on_call::{ROTATION_DAILY, ROTATION_WEEKLY}};
+
+/// Whoever is on call gets every one of their channels notified, so schedules only take the
+/// caller and the team members who accepted their invitation.
+async fn check_team_users(s:&Db,user_id:&str,on_call_user_ids:&[String])->Result<(),ApiError>{
+    let team=s.get_team_member_ids(user_id.to_string()).await?;
+    match on_call_user_ids.iter().find(|id| *id!=user_id && !team.contains(id)){
+        Some(id)=>Err(ApiError::Unprocessable(format!("user `{}` is not on your team", id))),
+        None=>Ok(())
+    }
+}
+
+pub struct OnCallApi;
+
+#[OpenApi(tag="ApiTags::OnCall")]
+impl OnCallApi{
+    #[oai(path="/on-call-schedule",method="post")]
+    async fn create_on_call_schedule(&self,Json(data):Json<CreateOnCallScheduleInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<OnCallScheduleOutput>,ApiError>{
+        if data.rotation!=ROTATION_DAILY && data.rotation!=ROTATION_WEEKLY{
+            return Err(ApiError::BadRequest("rotation must be `daily` or `weekly`".to_string()));
+        }
+        if data.participants.is_empty(){
+            return Err(ApiError::BadRequest("a schedule needs at least one participant".to_string()));
+        }
+        check_team_users(s,&user_id,&data.participants).await?;
+
+        let schedule=s.create_on_call_schedule(user_id, data.name, data.rotation, data.rotation_start.naive_utc(), data.participants).await?;
+
+        Ok(Json(schedule.into()))
+    }
+
+    #[oai(path="/on-call-schedules",method="get")]
+    async fn get_on_call_schedules(&self,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<Vec<OnCallScheduleOutput>>,ApiError>{
+        let schedules=s.get_on_call_schedules(user_id).await?;
+
+        Ok(Json(schedules.into_iter().map(OnCallScheduleOutput::from).collect()))
+    }
+
+    #[oai(path="/on-call-schedule/:id/on-call",method="get")]
+    async fn get_current_on_call(&self,Path(id): Path<String>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<OnCallOutput>,ApiError>{
+        let schedule=s.get_on_call_schedule(user_id, id).await?;
+        let on_call=s.get_current_on_call(schedule.id.clone()).await?;
+        let overrides=s.get_on_call_overrides(schedule.id.clone()).await?;
+
+        let response=OnCallOutput{
+            schedule_id:schedule.id,
+            user_id:on_call,
+            overrides:overrides.into_iter().map(|o| OnCallOverrideOutput{
+                id:o.id,
+                user_id:o.user_id,
+                starts_at:o.starts_at.and_utc().to_rfc3339(),
+                ends_at:o.ends_at.and_utc().to_rfc3339()
+            }).collect()
+        };
+        Ok(Json(response))
+    }
+
+    #[oai(path="/on-call-schedule/:id/override",method="post")]
+    async fn create_on_call_override(&self,Path(id): Path<String>,Json(data):Json<CreateOnCallOverrideInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<OnCallOverrideOutput>,ApiError>{
+        if data.ends_at<=data.starts_at{
+            return Err(ApiError::BadRequest("override must end after it starts".to_string()));
+        }
+        check_team_users(s,&user_id,std::slice::from_ref(&data.user_id)).await?;
+
+        let schedule=s.get_on_call_schedule(user_id, id).await?;
+        let o=s.create_on_call_override(schedule.id, data.user_id, data.starts_at.naive_utc(), data.ends_at.naive_utc()).await?;
+
+        let response=OnCallOverrideOutput{
+            id:o.id,
+            user_id:o.user_id,
+            starts_at:o.starts_at.and_utc().to_rfc3339(),
+            ends_at:o.ends_at.and_utc().to_rfc3339()
+        };
+        Ok(Json(response))
+    }
+}
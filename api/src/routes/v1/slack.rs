@@ -0,0 +1,268 @@
+use std::sync::Arc;
+use chrono::{Duration, Utc};
+use poem::{web::Data, Request};
+use poem_openapi::{payload::Json, OpenApi};
+use serde::Deserialize;
+use reqwest::Url;
+use serde_json::{json, Value};
+use crate::{auth_middleware::UserId, error::ApiError, routes::ApiTags};
+use api_types::request_output::SlackLinkCodeOutput;
+use db::{db::Db, error::DbError, models::incident::Incident};
+use notifier::{action_token::{generate_link_code, verify_link_code, TokenError}, config::NotifierConfig, ssrf::GuardedClient, slack::{verify_request, SignatureError, ACTION_ACKNOWLEDGE, ACTION_RESOLVE}};
+
+/// How long a code from `/user/slack-link-code` can be redeemed with `/uptime link`.
+const LINK_CODE_TTL_MINUTES: i64 = 15;
+/// Websites listed by `/uptime status` before the reply asks for a narrower query.
+const STATUS_MATCHES: usize = 5;
+/// Where Slack's `response_url`s point; replies go nowhere else.
+const RESPONSE_URL_HOST: &str = "hooks.slack.com";
+
+#[derive(Deserialize)]
+struct SlashCommand{
+    team_id:String,
+    user_id:String,
+    command:String,
+    #[serde(default)]
+    text:String
+}
+
+#[derive(Deserialize)]
+struct InteractionForm{
+    payload:String
+}
+
+#[derive(Deserialize)]
+struct Interaction{
+    #[serde(rename="type")]
+    kind:String,
+    team:SlackId,
+    user:SlackId,
+    #[serde(default)]
+    actions:Vec<InteractionAction>,
+    response_url:Option<String>
+}
+
+#[derive(Deserialize)]
+struct SlackId{
+    id:String
+}
+
+#[derive(Deserialize)]
+struct InteractionAction{
+    action_id:String,
+    #[serde(default)]
+    value:String
+}
+
+fn ephemeral(text:String)->Json<Value>{
+    Json(json!({ "response_type": "ephemeral", "text": text }))
+}
+
+/// Rejects anything not signed by our Slack app.
+fn verify_slack_request(req:&Request,body:&[u8],config:&NotifierConfig)->Result<(),ApiError>{
+    let secret=config.slack_signing_secret.as_deref().ok_or_else(|| ApiError::NotFound("not found".to_string()))?;
+    verify_request(secret, req.header("x-slack-request-timestamp"), req.header("x-slack-signature"), body, Utc::now()).map_err(|e| match e{
+        SignatureError::Missing | SignatureError::Mismatch=>ApiError::Unauthorized("invalid slack signature".to_string()),
+        SignatureError::Stale=>ApiError::Unauthorized("stale slack request".to_string())
+    })
+}
+
+/// Splits `/uptime <sub> <arg>` text into the subcommand and its argument.
+fn subcommand(text:&str)->(&str,&str){
+    let text=text.trim();
+    text.split_once(char::is_whitespace).map(|(a,b)| (a,b.trim())).unwrap_or((text,""))
+}
+
+/// The interaction's `response_url`, if it's an https url on Slack's webhook host.
+fn response_url(url:&str)->Option<Url>{
+    Url::parse(url).ok().filter(|u| u.scheme()=="https" && u.host_str()==Some(RESPONSE_URL_HOST) && u.port().is_none())
+}
+
+fn not_linked(command:&str)->String{
+    format!("Your Slack account isn't linked yet. Create a link code in the dashboard, then run `{} link <code>`.", command)
+}
+
+fn incident_reply(incident:&Incident)->String{
+    format!("Incident `{}` on website `{}` is {}.", incident.id, incident.website_id, incident.status)
+}
+
+pub struct SlackApi;
+
+#[OpenApi(tag="ApiTags::Slack")]
+impl SlackApi{
+    #[oai(path="/user/slack-link-code",method="post")]
+    async fn create_slack_link_code(&self,Data(config):Data<&Arc<NotifierConfig>>,UserId(user_id):UserId)->Result<Json<SlackLinkCodeOutput>,ApiError>{
+        let secret=config.action_secret.as_deref().ok_or_else(|| ApiError::NotFound("not found".to_string()))?;
+        let expires_at=Utc::now()+Duration::minutes(LINK_CODE_TTL_MINUTES);
+
+        Ok(Json(SlackLinkCodeOutput{
+            code:generate_link_code(secret, &user_id, expires_at),
+            expires_at:expires_at.to_rfc3339()
+        }))
+    }
+
+    /// `/uptime status <site>`, `/uptime ack <incident>`, `/uptime resolve <incident>` and `/uptime link <code>`.
+    #[oai(path="/slack/command",method="post")]
+    async fn slack_command(&self,req:&Request,body:Vec<u8>,Data(s):Data<&Db>,Data(config):Data<&Arc<NotifierConfig>>)->Result<Json<Value>,ApiError>{
+        verify_slack_request(req,&body,config)?;
+        let command:SlashCommand=serde_urlencoded::from_bytes(&body).map_err(|_| ApiError::BadRequest("malformed request".to_string()))?;
+
+        let (sub,arg)=subcommand(&command.text);
+
+        if sub=="link"{
+            let Some(secret)=config.action_secret.as_deref() else{
+                return Ok(ephemeral("Account linking isn't enabled on this server.".to_string()));
+            };
+            let reply=match verify_link_code(secret, arg, Utc::now()){
+                Ok(user_id)=>{
+                    s.link_slack_user(command.team_id, command.user_id, user_id).await?;
+                    "Your Slack account is now linked.".to_string()
+                }
+                Err(TokenError::Expired)=>"That link code has expired, create a new one in the dashboard.".to_string(),
+                Err(TokenError::Malformed | TokenError::BadSignature)=>"That link code isn't valid.".to_string()
+            };
+            return Ok(ephemeral(reply));
+        }
+
+        let Some(user_id)=s.get_slack_user(command.team_id, command.user_id).await? else{
+            return Ok(ephemeral(not_linked(&command.command)));
+        };
+
+        let reply=match (sub,arg){
+            ("status",query) if !query.is_empty()=>{
+                let websites=s.find_websites(user_id, query.to_string()).await?;
+                if websites.is_empty(){
+                    format!("No website matches `{}`.", query)
+                }else{
+                    let mut lines=Vec::new();
+                    for website in websites.iter().take(STATUS_MATCHES){
+                        let line=match s.get_open_incident(website.id.clone()).await?{
+                            Some(incident)=>format!("*{}* is down since {} (incident `{}`, {})", website.url, incident.opened_at.and_utc().to_rfc3339(), incident.id, incident.status),
+                            None=>{
+                                let ticks=s.get_latest_region_ticks(website.id.clone()).await?;
+                                let regions:Vec<String>=ticks.iter().map(|t| format!("{} {} ms", t.region_id, t.response_time_ms)).collect();
+                                if regions.is_empty(){
+                                    format!("*{}* has not been checked yet", website.url)
+                                }else{
+                                    format!("*{}* is up ({})", website.url, regions.join(", "))
+                                }
+                            }
+                        };
+                        lines.push(line);
+                    }
+                    if websites.len()>STATUS_MATCHES{
+                        lines.push(format!("…and {} more, narrow down the query.", websites.len()-STATUS_MATCHES));
+                    }
+                    lines.join("\n")
+                }
+            }
+            ("ack" | "acknowledge",id) if !id.is_empty()=>match s.acknowledge_incident(user_id, id.to_string(), "slack").await{
+                Ok(incident)=>incident_reply(&incident),
+                Err(DbError::NotFound)=>format!("No incident `{}` you can acknowledge.", id),
+                Err(e)=>return Err(e.into())
+            },
+            ("resolve",id) if !id.is_empty()=>match s.resolve_incident(user_id, id.to_string(), "slack").await{
+                Ok(incident)=>incident_reply(&incident),
+                Err(DbError::NotFound)=>format!("No incident `{}` you can resolve.", id),
+                Err(e)=>return Err(e.into())
+            },
+            _=>format!(
+                "Usage:\n`{c} status <website>`\n`{c} ack <incident>`\n`{c} resolve <incident>`\n`{c} link <code>`",
+                c=command.command
+            )
+        };
+
+        Ok(ephemeral(reply))
+    }
+
+    /// Button clicks on alerts posted by the Slack channel.
+    #[oai(path="/slack/interaction",method="post")]
+    async fn slack_interaction(&self,req:&Request,body:Vec<u8>,Data(s):Data<&Db>,Data(config):Data<&Arc<NotifierConfig>>,Data(http):Data<&GuardedClient>)->Result<(),ApiError>{
+        verify_slack_request(req,&body,config)?;
+        let form:InteractionForm=serde_urlencoded::from_bytes(&body).map_err(|_| ApiError::BadRequest("malformed request".to_string()))?;
+        let interaction:Interaction=serde_json::from_str(&form.payload).map_err(|_| ApiError::BadRequest("malformed request".to_string()))?;
+        if interaction.kind!="block_actions"{
+            return Ok(());
+        }
+        let response_url=match interaction.response_url.as_deref(){
+            Some(url)=>Some(response_url(url).ok_or_else(|| ApiError::BadRequest(format!("response_url must be an https url on {}", RESPONSE_URL_HOST)))?),
+            None=>None
+        };
+
+        let reply=match s.get_slack_user(interaction.team.id, interaction.user.id).await?{
+            None=>not_linked("/uptime"),
+            Some(user_id)=>{
+                let mut replies=Vec::new();
+                for action in &interaction.actions{
+                    let result=match action.action_id.as_str(){
+                        ACTION_ACKNOWLEDGE=>s.acknowledge_incident(user_id.clone(), action.value.clone(), "slack").await,
+                        ACTION_RESOLVE=>s.resolve_incident(user_id.clone(), action.value.clone(), "slack").await,
+                        // link buttons report clicks too
+                        _=>continue
+                    };
+                    replies.push(match result{
+                        Ok(incident)=>incident_reply(&incident),
+                        Err(DbError::NotFound)=>format!("You can't act on incident `{}`.", action.value),
+                        Err(e)=>return Err(e.into())
+                    });
+                }
+                replies.join("\n")
+            }
+        };
+
+        // slack ignores the response body of block actions, replies go through response_url
+        if let (Some(url),false)=(response_url,reply.is_empty()){
+            let http=http.clone();
+            tokio::spawn(async move{
+                let request=http.http().post(url).json(&json!({ "response_type": "ephemeral", "replace_original": false, "text": reply }));
+                let _=http.send(request).await;
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    /// A slash command as Slack posts it, from Slack's request signing docs.
+    const RECORDED_COMMAND:&str="token=xyzz0WbapA4vBCDEFasx0q6G&team_id=T1DC2JH3J&team_domain=testteamnow&channel_id=G8PSS9T3V&channel_name=foobar&user_id=U2CERLKJA&user_name=roadrunner&command=%2Fuptime&text=ack+%20inc-42+&response_url=https%3A%2F%2Fhooks.slack.com%2Fcommands%2FT1DC2JH3J%2F397700885554%2F96rGlfmibIGlgcZRskXaIFfN&trigger_id=398738663015.47445629121.803a0bc887a14d10d2c447fce8b6703c";
+
+    #[test]
+    fn parses_recorded_command(){
+        let command:SlashCommand=serde_urlencoded::from_bytes(RECORDED_COMMAND.as_bytes()).unwrap();
+        assert_eq!(command.team_id,"T1DC2JH3J");
+        assert_eq!(command.user_id,"U2CERLKJA");
+        assert_eq!(command.command,"/uptime");
+        assert_eq!(subcommand(&command.text),("ack","inc-42"));
+    }
+
+    #[test]
+    fn splits_subcommands(){
+        assert_eq!(subcommand("status api.example.com"),("status","api.example.com"));
+        assert_eq!(subcommand("  help "),("help",""));
+        assert_eq!(subcommand(""),("",""));
+    }
+
+    #[test]
+    fn parses_recorded_interaction(){
+        let payload=r#"{"type":"block_actions","team":{"id":"T1DC2JH3J","domain":"testteamnow"},"user":{"id":"U2CERLKJA","username":"roadrunner"},"response_url":"https://hooks.slack.com/actions/T1DC2JH3J/1/abc","actions":[{"action_id":"incident_acknowledge","block_id":"b","value":"inc-42","type":"button","action_ts":"1531420618.1"}]}"#;
+        let body=serde_urlencoded::to_string([("payload",payload)]).unwrap();
+        let form:InteractionForm=serde_urlencoded::from_bytes(body.as_bytes()).unwrap();
+        let interaction:Interaction=serde_json::from_str(&form.payload).unwrap();
+        assert_eq!(interaction.kind,"block_actions");
+        assert_eq!(interaction.user.id,"U2CERLKJA");
+        assert_eq!(interaction.actions[0].action_id,ACTION_ACKNOWLEDGE);
+        assert_eq!(interaction.actions[0].value,"inc-42");
+    }
+
+    #[test]
+    fn only_replies_to_slack(){
+        assert!(response_url("https://hooks.slack.com/actions/T1DC2JH3J/1/abc").is_some());
+        for url in ["http://hooks.slack.com/actions/T/1/abc","https://hooks.slack.com:8443/actions/T/1/abc","https://hooks.slack.com.example.com/x","https://example.com/?hooks.slack.com","https://user@169.254.169.254/","not a url"]{
+            assert!(response_url(url).is_none(),"{}",url);
+        }
+    }
+}
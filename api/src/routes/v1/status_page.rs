@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+use chrono::{Days, NaiveDate, Utc};
+use poem::{web::Data, IntoResponse, Request, Response};
+use poem_openapi::{param::Path, payload::{Html, Json}, registry::{MetaMediaType, MetaResponse, MetaResponses, Registry}, types::Type, ApiResponse, OpenApi};
+use crate::{auth_middleware::UserId, error::ApiError, routes::{ApiTags, NoContent}};
+use api_types::{request_input::StatusPageInput, request_output::{PublicComponentOutput, PublicIncidentOutput, PublicStatusPageOutput, StatusPageOutput, UptimeDayOutput}};
+use db::{db::Db, error::DbError, models::{status_page::NewStatusPage, website_group::MonitorState, website_tick::DailyUptime}};
+
+/// Days of history behind the uptime bars.
+const HISTORY_DAYS: u64 = 90;
+
+pub fn page_write_error(e:DbError)->ApiError{
+    match e{
+        DbError::NotFound=>ApiError::NotFound("unknown website or status page".to_string()),
+        DbError::Conflict(_)=>ApiError::Conflict("this slug is already taken".to_string()),
+        e=>ApiError::from(e)
+    }
+}
+
+pub fn validate(data:StatusPageInput)->Result<NewStatusPage,ApiError>{
+    let slug_ok=(3..=64).contains(&data.slug.len())
+        && data.slug.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c=='-');
+    if !slug_ok{
+        return Err(ApiError::BadRequest("slug must be 3-64 characters of a-z, 0-9 and -".to_string()));
+    }
+    if data.logo_url.as_deref().is_some_and(|u| !u.starts_with("https://") && !u.starts_with("http://")){
+        return Err(ApiError::BadRequest("logo_url must be an http(s) url".to_string()));
+    }
+
+    Ok(NewStatusPage{
+        slug:data.slug,
+        title:data.title,
+        logo_url:data.logo_url,
+        components:data.components.into_iter().map(|c| (c.website_id,c.name)).collect()
+    })
+}
+
+/// The public page in whichever form the client asked for. One 200 response with two
+/// media types, which the derive can't express.
+pub enum PublicStatusPage{
+    Html(String),
+    Json(PublicStatusPageOutput)
+}
+
+impl IntoResponse for PublicStatusPage{
+    fn into_response(self)->Response{
+        match self{
+            PublicStatusPage::Html(html)=>Html(html).into_response(),
+            PublicStatusPage::Json(page)=>Json(page).into_response()
+        }
+    }
+}
+
+impl ApiResponse for PublicStatusPage{
+    fn meta()->MetaResponses{
+        MetaResponses{
+            responses:vec![MetaResponse{
+                description:"The status page, rendered when `text/html` is accepted",
+                status:Some(200),
+                status_range:None,
+                content:vec![
+                    MetaMediaType{ content_type:"application/json; charset=utf-8",schema:PublicStatusPageOutput::schema_ref() },
+                    MetaMediaType{ content_type:"text/html; charset=utf-8",schema:String::schema_ref() }
+                ],
+                headers:vec![]
+            }]
+        }
+    }
+
+    fn register(registry:&mut Registry){
+        PublicStatusPageOutput::register(registry);
+    }
+}
+
+/// One bar per day from `first_day` on, and the uptime over all of them. Days without
+/// counted ticks have no percentage; counts dated outside the bars are left out of both.
+fn uptime_days(first_day:NaiveDate,website_days:&[&DailyUptime])->(Option<f64>,Vec<UptimeDayOutput>){
+    let by_day:HashMap<NaiveDate,&DailyUptime>=website_days.iter().map(|d| (d.day,*d)).collect();
+    let days:Vec<NaiveDate>=first_day.iter_days().take(HISTORY_DAYS as usize).collect();
+    let (up,down)=days.iter().filter_map(|day| by_day.get(day)).fold((0,0),|(up,down),d| (up+d.up_ticks,down+d.down_ticks));
+
+    let bars=days.into_iter().map(|day| UptimeDayOutput{
+        date:day.to_string(),
+        uptime_percentage:by_day.get(&day).and_then(|d| d.percentage())
+    }).collect();
+    ((up+down>0).then(|| up as f64*100.0/(up+down) as f64),bars)
+}
+
+/// Everything the public page shows, computed from the page's components.
+pub async fn public_status_page(s:&Db,slug:String)->Result<PublicStatusPageOutput,ApiError>{
+    let (page,components)=s.get_status_page_by_slug(slug).await?;
+    let today=Utc::now().date_naive();
+    let first_day=today-Days::new(HISTORY_DAYS-1);
+    let since=first_day.and_hms_opt(0,0,0).expect("midnight exists");
+
+    let website_ids:Vec<String>=components.iter().map(|c| c.website_id.clone()).collect();
+    let websites=s.get_websites_by_ids(&website_ids).await?;
+    let states=s.get_monitor_states(&websites).await?;
+    let daily=s.get_daily_uptime(website_ids.clone(), since).await?;
+    let incidents=s.get_website_incidents(website_ids, since).await?;
+
+    let component_outputs:Vec<PublicComponentOutput>=components.iter().map(|c| {
+        let website_days:Vec<&DailyUptime>=daily.iter().filter(|d| d.website_id==c.website_id).collect();
+        let (uptime_percentage,days)=uptime_days(first_day,&website_days);
+        PublicComponentOutput{
+            id:c.id.clone(),
+            name:c.name.clone(),
+            status:states.get(&c.website_id).copied().unwrap_or(MonitorState::Unknown).as_str().to_string(),
+            uptime_percentage,
+            days
+        }
+    }).collect();
+
+    let overall=components.iter()
+        .filter_map(|c| states.get(&c.website_id).copied())
+        .max()
+        .unwrap_or(MonitorState::Unknown);
+
+    Ok(PublicStatusPageOutput{
+        slug:page.slug,
+        title:page.title,
+        logo_url:page.logo_url,
+        status:overall.as_str().to_string(),
+        components:component_outputs,
+        incidents:incidents.into_iter().map(|i| PublicIncidentOutput{
+            component:components.iter().find(|c| c.website_id==i.website_id).map(|c| c.name.clone()).unwrap_or_default(),
+            id:i.id,
+            status:i.status,
+            opened_at:i.opened_at.and_utc().to_rfc3339(),
+            resolved_at:i.resolved_at.map(|r| r.and_utc().to_rfc3339())
+        }).collect()
+    })
+}
+
+pub struct StatusPageApi;
+
+#[OpenApi(tag="ApiTags::StatusPages")]
+impl StatusPageApi{
+    #[oai(path="/status-page",method="post")]
+    async fn create_status_page(&self,Json(data):Json<StatusPageInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<StatusPageOutput>,ApiError>{
+        let page=validate(data)?;
+
+        let page=s.create_status_page(user_id, page).await.map_err(page_write_error)?;
+
+        Ok(Json(page.into()))
+    }
+
+    #[oai(path="/status-page/:id",method="put")]
+    async fn update_status_page(&self,Path(id): Path<String>,Json(data):Json<StatusPageInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<StatusPageOutput>,ApiError>{
+        let page=validate(data)?;
+
+        let page=s.update_status_page(user_id, id, page).await.map_err(page_write_error)?;
+
+        Ok(Json(page.into()))
+    }
+
+    #[oai(path="/status-pages",method="get")]
+    async fn get_status_pages(&self,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<Vec<StatusPageOutput>>,ApiError>{
+        let pages=s.get_status_pages(user_id).await?;
+
+        Ok(Json(pages.into_iter().map(StatusPageOutput::from).collect()))
+    }
+
+    #[oai(path="/status-page/:id",method="delete")]
+    async fn delete_status_page(&self,Path(id): Path<String>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<NoContent,ApiError>{
+        let deleted=s.delete_status_page(user_id, id).await?;
+        if deleted==0{
+            return Err(ApiError::NotFound("not found".to_string()));
+        }
+
+        Ok(NoContent::NoContent)
+    }
+
+    /// Unauthenticated. Browsers asking for `text/html` get the rendered page, everyone else JSON.
+    #[oai(path="/status-page/:slug",method="get")]
+    async fn get_public_status_page(&self,req:&Request,Path(slug): Path<String>,Data(s):Data<&Db>)->Result<PublicStatusPage,ApiError>{
+        let page=public_status_page(s,slug).await?;
+
+        if req.header("accept").is_some_and(|a| a.contains("text/html")){
+            Ok(PublicStatusPage::Html(render_status_page(&page)))
+        }else{
+            Ok(PublicStatusPage::Json(page))
+        }
+    }
+}
+
+fn escape_html(s:&str)->String{
+    s.replace('&',"&amp;").replace('<',"&lt;").replace('>',"&gt;").replace('"',"&quot;").replace('\'',"&#39;")
+}
+
+fn status_color(status:&str)->&'static str{
+    match status{
+        "up"=>"#2eb67d",
+        "down"=>"#e01e5a",
+        "paused"=>"#8d8d8d",
+        _=>"#c4c4c4"
+    }
+}
+
+fn bar_color(uptime:Option<f64>)->&'static str{
+    match uptime{
+        None=>"#e0e0e0",
+        Some(u) if u>=99.9=>"#2eb67d",
+        Some(u) if u>=99.0=>"#ecb22e",
+        Some(_)=>"#e01e5a"
+    }
+}
+
+fn render_status_page(page:&PublicStatusPageOutput)->String{
+    let headline=match page.status.as_str(){
+        "up"=>"All systems operational",
+        "down"=>"Some systems are down",
+        _=>"Status unknown"
+    };
+    let logo=page.logo_url.as_deref()
+        .map(|url| format!("<img src=\"{}\" alt=\"\" height=\"40\">", escape_html(url)))
+        .unwrap_or_default();
+
+    let mut components=String::new();
+    for c in &page.components{
+        let bars:String=c.days.iter().map(|d| format!(
+            "<span class=\"bar\" style=\"background:{}\" title=\"{} {}\"></span>",
+            bar_color(d.uptime_percentage),
+            d.date,
+            d.uptime_percentage.map(|u| format!("{:.2}%", u)).unwrap_or_else(|| "no data".to_string())
+        )).collect();
+        components.push_str(&format!(
+            "<section><h2>{name} <small style=\"color:{color}\">{status}</small></h2><div class=\"bars\">{bars}</div><p>{uptime} uptime over the last {days} days</p></section>",
+            name=escape_html(&c.name),
+            color=status_color(&c.status),
+            status=c.status,
+            bars=bars,
+            uptime=c.uptime_percentage.map(|u| format!("{:.2}%", u)).unwrap_or_else(|| "—".to_string()),
+            days=HISTORY_DAYS
+        ));
+    }
+
+    let incidents=if page.incidents.is_empty(){
+        "<p>No incidents in the last 90 days.</p>".to_string()
+    }else{
+        page.incidents.iter().map(|i| format!(
+            "<li><strong>{component}</strong> {status} — opened {opened}{resolved}</li>",
+            component=escape_html(&i.component),
+            status=i.status,
+            opened=i.opened_at,
+            resolved=i.resolved_at.as_deref().map(|r| format!(", resolved {}", r)).unwrap_or_default()
+        )).collect::<Vec<_>>().join("")
+    };
+
+    format!(
+        "<!doctype html><html><head><meta charset=\"utf-8\"><meta name=\"viewport\" content=\"width=device-width\"><title>{title}</title>\
+         <link rel=\"alternate\" type=\"application/atom+xml\" href=\"{slug}/incidents.atom\" title=\"Incidents\">\
+         <style>body{{font-family:sans-serif;max-width:52rem;margin:2rem auto;padding:0 1rem}}.bars{{display:flex;gap:2px}}.bar{{flex:1;height:2rem;border-radius:2px}}</style></head>\
+         <body><header>{logo}<h1>{title}</h1><p style=\"color:{color}\">{headline}</p></header>{components}<h2>Incidents</h2><ul>{incidents}</ul>\
+         <footer><a href=\"{slug}/incidents.atom\">Incident feed</a> · <a href=\"{slug}/maintenance.ics\">Maintenance calendar</a></footer></body></html>",
+        title=escape_html(&page.title),
+        slug=page.slug,
+        logo=logo,
+        color=status_color(&page.status),
+        headline=headline,
+        components=components,
+        incidents=incidents
+    )
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    fn day(date:&str)->NaiveDate{
+        date.parse().unwrap()
+    }
+
+    fn counts(date:&str,up_ticks:i64,down_ticks:i64)->DailyUptime{
+        DailyUptime{ website_id:"website-1".to_string(),day:day(date),up_ticks,down_ticks }
+    }
+
+    #[test]
+    fn has_a_bar_for_every_day_without_data(){
+        let (uptime,days)=uptime_days(day("2026-01-01"),&[]);
+        assert_eq!(uptime,None);
+        assert_eq!(days.len(),90);
+        assert_eq!(days[0].date,"2026-01-01");
+        assert_eq!(days[89].date,"2026-03-31");
+        assert!(days.iter().all(|d| d.uptime_percentage.is_none()));
+    }
+
+    #[test]
+    fn counts_partial_days_by_their_ticks(){
+        // today has only been checked for a few minutes, a day in maintenance has no counted ticks
+        let today=counts("2026-03-31",3,1);
+        let maintenance=counts("2026-02-01",0,0);
+        let full=counts("2026-01-15",1440,0);
+        let (uptime,days)=uptime_days(day("2026-01-01"),&[&full,&maintenance,&today]);
+
+        assert_eq!(days[14].uptime_percentage,Some(100.0));
+        assert_eq!(days[31].uptime_percentage,None);
+        assert_eq!(days[89].uptime_percentage,Some(75.0));
+        assert_eq!(days.iter().filter(|d| d.uptime_percentage.is_some()).count(),2);
+        assert_eq!(uptime,Some(1443.0*100.0/1444.0));
+    }
+
+    #[test]
+    fn leaves_out_days_beyond_the_window(){
+        let before=counts("2025-12-31",0,10);
+        let first=counts("2026-01-01",9,1);
+        let last=counts("2026-03-31",10,0);
+        let after=counts("2026-04-01",0,10);
+        let (uptime,days)=uptime_days(day("2026-01-01"),&[&before,&first,&last,&after]);
+
+        assert_eq!(days.first().map(|d| (d.date.as_str(),d.uptime_percentage)),Some(("2026-01-01",Some(90.0))));
+        assert_eq!(days.last().map(|d| (d.date.as_str(),d.uptime_percentage)),Some(("2026-03-31",Some(100.0))));
+        assert_eq!(uptime,Some(95.0));
+    }
+}
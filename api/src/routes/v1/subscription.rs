@@ -0,0 +1,123 @@
+use std::sync::Arc;
+use poem::web::Data;
+use poem_openapi::{param::Path, payload::{Html, Json}, OpenApi};
+use crate::{error::ApiError, routes::ApiTags};
+use api_types::{request_input::SubscribeInput, request_output::SubscriptionOutput};
+use db::{db::Db, models::status_page_subscriber::{StatusPageSubscriber, SUBSCRIBER_EMAIL, SUBSCRIBER_WEBHOOK}};
+use notifier::{config::NotifierConfig, email::Mailer, ssrf::GuardedClient, subscriber::{send_confirmation, send_webhook_confirmation}};
+
+/// Pending subscribers get another confirmation request at most this often per address or url.
+const CONFIRMATION_RESEND_MINUTES:i64=10;
+
+fn valid_email(email:&str)->bool{
+    match email.split_once('@'){
+        Some((local,domain))=>!local.is_empty()
+            && domain.contains('.')
+            && !domain.contains('@')
+            && !email.chars().any(|c| c.is_whitespace() || c.is_control()),
+        None=>false
+    }
+}
+
+fn subscription_output(subscriber:StatusPageSubscriber,created:bool)->SubscriptionOutput{
+    SubscriptionOutput{
+        id:subscriber.id,
+        confirmed:subscriber.confirmed_at.is_some(),
+        secret:subscriber.secret.filter(|_| created),
+        kind:subscriber.kind,
+        component_ids:subscriber.component_ids
+    }
+}
+
+fn page(title:&str,body:&str)->Html<String>{
+    Html(format!(
+        "<!doctype html><html><head><meta name=\"viewport\" content=\"width=device-width\"><title>{title}</title></head><body>{body}</body></html>",
+        title=title,
+        body=body
+    ))
+}
+
+pub struct SubscriptionApi;
+
+#[OpenApi(tag="ApiTags::Subscriptions")]
+impl SubscriptionApi{
+    /// Unauthenticated, so every subscription is double opt-in: nothing but the confirmation
+    /// request is sent until the emailed link is opened, or for webhooks until the receiver
+    /// POSTs to the `confirm_url` of the signed `subscription.confirm` request.
+    #[oai(path="/status-page/:slug/subscribe",method="post")]
+    async fn subscribe_to_status_page(&self,Path(slug): Path<String>,Json(data):Json<SubscribeInput>,Data(s):Data<&Db>,Data(config):Data<&Arc<NotifierConfig>>,Data(mailer):Data<&Option<Arc<Mailer>>>,Data(http):Data<&GuardedClient>)->Result<Json<SubscriptionOutput>,ApiError>{
+        let (kind,target)=match (data.email,data.webhook_url){
+            (Some(email),None)=>{
+                let email=email.trim().to_string();
+                if !valid_email(&email){
+                    return Err(ApiError::BadRequest("email is not a valid address".to_string()));
+                }
+                (SUBSCRIBER_EMAIL,email)
+            }
+            (None,Some(url))=>{
+                let parsed=reqwest::Url::parse(&url)
+                    .ok()
+                    .filter(|u| u.scheme()=="https" || u.scheme()=="http")
+                    .ok_or_else(|| ApiError::BadRequest("webhook_url must be an http(s) url".to_string()))?;
+                http.check_url(&parsed).map_err(|e| ApiError::BadRequest(format!("webhook_url is not allowed: {}", e)))?;
+                (SUBSCRIBER_WEBHOOK,url)
+            }
+            _=>return Err(ApiError::BadRequest("give either email or webhook_url".to_string()))
+        };
+        if kind==SUBSCRIBER_EMAIL && mailer.is_none(){
+            return Err(ApiError::Unavailable("email subscriptions are not available".to_string()));
+        }
+
+        let (page,subscriber,created)={
+            let (page,components)=s.get_status_page_by_slug(slug).await?;
+            if let Some(unknown)=data.component_ids.iter().find(|id| !components.iter().any(|c| &c.id==*id)){
+                return Err(ApiError::BadRequest(format!("component {} is not on this status page", unknown)));
+            }
+            let (subscriber,created)=s.create_status_page_subscriber(page.id.clone(), kind, target, data.component_ids).await
+                ?;
+            (page,subscriber,created)
+        };
+
+        // pending subscribers may have lost the first request, but anyone can call this
+        if subscriber.confirmed_at.is_none() && s.claim_subscriber_confirmation(&subscriber, chrono::Duration::minutes(CONFIRMATION_RESEND_MINUTES)).await?{
+            match mailer.as_ref().filter(|_| kind==SUBSCRIBER_EMAIL){
+                Some(mailer)=>send_confirmation(mailer, config, &page, &subscriber).await
+                    .map_err(|_| ApiError::BadGateway("could not send the confirmation email".to_string()))?,
+                None=>send_webhook_confirmation(http, config, &page, &subscriber).await
+                    .map_err(|e| ApiError::BadGateway(format!("could not deliver the confirmation request: {}", e)))?
+            }
+        }
+
+        Ok(Json(subscription_output(subscriber,created)))
+    }
+
+    #[oai(path="/subscription/:token/confirm",method="get")]
+    async fn confirm_subscription_page(&self,Path(token): Path<String>,Data(s):Data<&Db>)->Result<Html<String>,ApiError>{
+        s.get_status_page_subscriber_by_token(token).await?;
+
+        Ok(page("Confirm subscription","<form method=\"post\"><p>Start receiving status updates?</p><button type=\"submit\">Confirm</button></form>"))
+    }
+
+    #[oai(path="/subscription/:token/confirm",method="post")]
+    async fn confirm_subscription(&self,Path(token): Path<String>,Data(s):Data<&Db>)->Result<Html<String>,ApiError>{
+        s.confirm_status_page_subscriber(token).await?;
+
+        Ok(page("Subscription confirmed","<p>You are subscribed. Every update includes a link to unsubscribe.</p>"))
+    }
+
+    #[oai(path="/subscription/:token/unsubscribe",method="get")]
+    async fn unsubscribe_page(&self,Path(token): Path<String>,Data(s):Data<&Db>)->Result<Html<String>,ApiError>{
+        s.get_status_page_subscriber_by_token(token).await?;
+
+        Ok(page("Unsubscribe","<form method=\"post\"><p>Stop receiving status updates?</p><button type=\"submit\">Unsubscribe</button></form>"))
+    }
+
+    /// Also the RFC 8058 one-click target of the `List-Unsubscribe` header, so it must not need anything but the token.
+    #[oai(path="/subscription/:token/unsubscribe",method="post")]
+    async fn unsubscribe(&self,Path(token): Path<String>,Data(s):Data<&Db>)->Result<Html<String>,ApiError>{
+        s.delete_status_page_subscriber(token).await?;
+
+        // unsubscribing twice is not an error worth showing
+        Ok(page("Unsubscribed","<p>You will not receive further updates.</p>"))
+    }
+}
@@ -0,0 +1,348 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use poem::web::Data;
+use poem_openapi::{param::Query, payload::{Json, PlainText}, OpenApi};
+use serde::{de::DeserializeOwned, Deserialize};
+use crate::{auth_middleware::UserId, error::ApiError, routes::{v1::config::{converge, export_document, Mode}, ApiTags}};
+use api_types::{request_input::{MonitorConfigInput, WebsiteConfigInput}, request_output::{ImportOutput, SkippedImportOutput}};
+use db::{db::Db, models::website::{normalize_url, WebsiteFilter}};
+
+/// UptimeRobot monitor types that check a url: HTTP(s) and keyword.
+const UPTIMEROBOT_URL_TYPES:[i64;2]=[1,2];
+
+/// Better Stack monitor types that check a url.
+const BETTERSTACK_URL_TYPES:[&str;4]=["status","expected_status_code","keyword","keyword_absence"];
+
+/// A monitor read from another service's export.
+struct Entry{
+    /// Unique within the import, e.g. `uptimerobot-777`.
+    source:String,
+    url:String,
+    name:Option<String>,
+    paused:bool
+}
+
+/// A paginated api response saved as one page or as an array of pages.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Pages<T>{
+    Many(Vec<T>),
+    One(T)
+}
+
+impl<T> Pages<T>{
+    fn into_vec(self)->Vec<T>{
+        match self{
+            Pages::Many(pages)=>pages,
+            Pages::One(page)=>vec![page]
+        }
+    }
+}
+
+/// A `getMonitors` response of the UptimeRobot api.
+#[derive(Deserialize)]
+struct UptimeRobotPage{
+    monitors:Vec<UptimeRobotMonitor>
+}
+
+#[derive(Deserialize)]
+struct UptimeRobotMonitor{
+    id:serde_json::Value,
+    friendly_name:Option<String>,
+    #[serde(default)]
+    url:String,
+    #[serde(rename="type")]
+    kind:i64,
+    /// 0 is paused.
+    #[serde(default)]
+    status:i64
+}
+
+/// A `GET /api/v2/monitors` response of the Better Stack api.
+#[derive(Deserialize)]
+struct BetterStackPage{
+    data:Vec<BetterStackMonitor>
+}
+
+#[derive(Deserialize)]
+struct BetterStackMonitor{
+    id:serde_json::Value,
+    attributes:BetterStackAttributes
+}
+
+#[derive(Deserialize)]
+struct BetterStackAttributes{
+    #[serde(default)]
+    url:String,
+    pronounceable_name:Option<String>,
+    monitor_type:String,
+    #[serde(default)]
+    paused:bool
+}
+
+fn parse_pages<T:DeserializeOwned>(format:&str,body:serde_json::Value)->Result<Vec<T>,ApiError>{
+    serde_json::from_value::<Pages<T>>(body)
+        .map(Pages::into_vec)
+        .map_err(|_| ApiError::BadRequest(format!("expected a monitor listing from {}, or an array of them", format)))
+}
+
+/// Ids are numbers in some api versions and strings in others.
+fn id_text(id:&serde_json::Value)->String{
+    match id{
+        serde_json::Value::String(id)=>id.clone(),
+        id=>id.to_string()
+    }
+}
+
+fn skip(source:&str,url:Option<&str>,reason:&str)->SkippedImportOutput{
+    SkippedImportOutput{ source:source.to_string(),url:url.map(str::to_string),reason:reason.to_string() }
+}
+
+fn uptimerobot_entries(data:serde_json::Value)->Result<(Vec<Entry>,Vec<SkippedImportOutput>),ApiError>{
+    let mut entries=Vec::new();
+    let mut skipped=Vec::new();
+    for monitor in parse_pages::<UptimeRobotPage>("UptimeRobot",data)?.into_iter().flat_map(|p| p.monitors){
+        let source=format!("uptimerobot-{}", id_text(&monitor.id));
+        if !UPTIMEROBOT_URL_TYPES.contains(&monitor.kind){
+            skipped.push(skip(&source,Some(&monitor.url).filter(|u| !u.is_empty()).map(String::as_str),"only HTTP and keyword monitors can be imported"));
+            continue;
+        }
+        entries.push(Entry{ source,url:monitor.url,name:monitor.friendly_name,paused:monitor.status==0 });
+    }
+    Ok((entries,skipped))
+}
+
+fn betterstack_entries(data:serde_json::Value)->Result<(Vec<Entry>,Vec<SkippedImportOutput>),ApiError>{
+    let mut entries=Vec::new();
+    let mut skipped=Vec::new();
+    for monitor in parse_pages::<BetterStackPage>("Better Stack",data)?.into_iter().flat_map(|p| p.data){
+        let source=format!("betterstack-{}", id_text(&monitor.id));
+        let attributes=monitor.attributes;
+        if !BETTERSTACK_URL_TYPES.contains(&attributes.monitor_type.as_str()){
+            skipped.push(skip(&source,Some(&attributes.url).filter(|u| !u.is_empty()).map(String::as_str),&format!("{} monitors can't be imported", attributes.monitor_type)));
+            continue;
+        }
+        entries.push(Entry{ source,url:attributes.url,name:attributes.pronounceable_name,paused:attributes.paused });
+    }
+    Ok((entries,skipped))
+}
+
+fn parse_csv(body:&str)->Result<Vec<Entry>,ApiError>{
+    let mut reader=csv::ReaderBuilder::new().has_headers(false).flexible(true).trim(csv::Trim::All).from_reader(body.as_bytes());
+    let mut records=reader.records().peekable();
+    let mut url_column=0;
+    let mut name_column=Some(1);
+    if let Some(Ok(first))=records.peek(){
+        let position=|name:&str| first.iter().position(|h| h.eq_ignore_ascii_case(name));
+        if let Some(column)=position("url"){
+            url_column=column;
+            name_column=position("name");
+            records.next();
+        }
+    }
+
+    let mut entries=Vec::new();
+    for record in records{
+        let record=record.map_err(|e| ApiError::BadRequest(format!("invalid CSV: {}", e)))?;
+        let line=record.position().map(|p| p.line()).unwrap_or_default();
+        entries.push(Entry{
+            source:format!("csv-{}", line),
+            url:record.get(url_column).unwrap_or_default().to_string(),
+            name:name_column.and_then(|c| record.get(c)).map(str::to_string),
+            paused:false
+        });
+    }
+    Ok(entries)
+}
+
+/// Creates a website per entry, tagged with where it came from and its name there. Entries
+/// whose url is invalid, repeated or already monitored are skipped, so importing the same
+/// export again changes nothing.
+async fn import_entries(s:&Db,user_id:String,origin:&str,entries:Vec<Entry>,mut skipped:Vec<SkippedImportOutput>,dry_run:bool)->Result<ImportOutput,ApiError>{
+    let monitored:HashSet<String>=s.get_websites(user_id.clone(),&WebsiteFilter::default()).await?.into_iter().map(|w| w.url).collect();
+    let mut sources:HashMap<String,String>=HashMap::new();
+    let mut doc=MonitorConfigInput::default();
+    for entry in entries{
+        if entry.url.trim().is_empty(){
+            skipped.push(skip(&entry.source,None,"no url"));
+            continue;
+        }
+        let url=match normalize_url(&entry.url){
+            Ok(url)=>url,
+            Err(e)=>{
+                skipped.push(skip(&entry.source,Some(&entry.url),&e));
+                continue;
+            }
+        };
+        if monitored.contains(&url){
+            skipped.push(skip(&entry.source,Some(&url),"already monitored"));
+            continue;
+        }
+        if let Some(first)=sources.get(&url){
+            skipped.push(skip(&entry.source,Some(&url),&format!("same url as {}", first)));
+            continue;
+        }
+        if doc.websites.contains_key(&entry.source){
+            skipped.push(skip(&entry.source,Some(&url),"listed twice"));
+            continue;
+        }
+
+        let mut tags=BTreeMap::from([("source".to_string(),origin.to_string())]);
+        // tag values can't hold commas
+        if let Some(name)=entry.name.map(|n| n.replace(',',";").trim().to_string()).filter(|n| !n.is_empty()){
+            tags.insert("name".to_string(),name);
+        }
+        sources.insert(url.clone(),entry.source.clone());
+        doc.websites.insert(entry.source,WebsiteConfigInput{
+            url,
+            paused:entry.paused,
+            group_id:None,
+            tags,
+            depends_on:Vec::new(),
+            escalation_policy_id:None
+        });
+    }
+
+    let plan=converge(s,user_id,doc,Mode::Import,dry_run).await?;
+    Ok(ImportOutput{ changes:plan.changes,skipped })
+}
+
+pub struct TransferApi;
+
+#[OpenApi(tag="ApiTags::Transfer")]
+impl TransferApi{
+    /// Every website, channel and status page as a monitors-as-code document, for `/import`
+    /// or `/config/apply`. Group and escalation policy ids only exist in this account.
+    #[oai(path="/export",method="get")]
+    async fn export(&self,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<MonitorConfigInput>,ApiError>{
+        Ok(Json(export_document(s,&user_id).await?))
+    }
+
+    /// Creates and updates resources to match an `/export`, matching existing ones by key, url,
+    /// slug or target. Unlike `/config/apply` nothing is deleted and no keys are recorded.
+    #[oai(path="/import",method="post")]
+    async fn import(&self,Json(data):Json<MonitorConfigInput>,Query(dry_run):Query<Option<bool>>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<ImportOutput>,ApiError>{
+        let plan=converge(s,user_id,data,Mode::Import,dry_run.unwrap_or(false)).await?;
+        Ok(Json(ImportOutput{ changes:plan.changes,skipped:Vec::new() }))
+    }
+
+    /// Imports `getMonitors` responses of the UptimeRobot api, one or an array of pages.
+    /// HTTP and keyword monitors become websites (keywords aren't checked), others are skipped.
+    #[oai(path="/import/uptimerobot",method="post")]
+    async fn import_uptimerobot(&self,Json(data):Json<serde_json::Value>,Query(dry_run):Query<Option<bool>>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<ImportOutput>,ApiError>{
+        let (entries,skipped)=uptimerobot_entries(data)?;
+        Ok(Json(import_entries(s,user_id,"uptimerobot",entries,skipped,dry_run.unwrap_or(false)).await?))
+    }
+
+    /// Imports `GET /api/v2/monitors` responses of the Better Stack api, one or an array of pages.
+    /// Status, status code and keyword monitors become websites (keywords aren't checked), others are skipped.
+    #[oai(path="/import/betterstack",method="post")]
+    async fn import_betterstack(&self,Json(data):Json<serde_json::Value>,Query(dry_run):Query<Option<bool>>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<ImportOutput>,ApiError>{
+        let (entries,skipped)=betterstack_entries(data)?;
+        Ok(Json(import_entries(s,user_id,"betterstack",entries,skipped,dry_run.unwrap_or(false)).await?))
+    }
+
+    /// Imports a CSV with a url per row. With a header row the `url` and, if present, `name`
+    /// columns are read; without, the first column is the url and the second the name.
+    #[oai(path="/import/csv",method="post")]
+    async fn import_csv(&self,data:PlainText<String>,Query(dry_run):Query<Option<bool>>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<ImportOutput>,ApiError>{
+        let entries=parse_csv(&data.0)?;
+        Ok(Json(import_entries(s,user_id,"csv",entries,Vec::new(),dry_run.unwrap_or(false)).await?))
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use serde_json::json;
+
+    use super::*;
+
+    /// (source, url, name, paused)
+    fn summary(entries:&[Entry])->Vec<(&str,&str,Option<&str>,bool)>{
+        entries.iter().map(|e| (e.source.as_str(),e.url.as_str(),e.name.as_deref(),e.paused)).collect()
+    }
+
+    #[test]
+    fn reads_uptimerobot_pages(){
+        let page=json!({
+            "stat":"ok",
+            "pagination":{"offset":0,"limit":50,"total":3},
+            "monitors":[
+                {"id":777,"friendly_name":"API","url":"https://api.example.com/health","type":1,"status":2,"interval":300},
+                {"id":"778","friendly_name":"Docs","url":"https://docs.example.com","type":2,"status":0,"keyword_value":"Welcome"},
+                {"id":779,"friendly_name":"Mail","url":"mail.example.com","type":4,"status":2,"port":25}
+            ]
+        });
+
+        let (entries,skipped)=uptimerobot_entries(page.clone()).unwrap();
+        assert_eq!(summary(&entries),[
+            ("uptimerobot-777","https://api.example.com/health",Some("API"),false),
+            ("uptimerobot-778","https://docs.example.com",Some("Docs"),true)
+        ]);
+        assert_eq!(skipped.len(),1);
+        assert_eq!(skipped[0].source,"uptimerobot-779");
+
+        // an array of pages reads the same as its pages one after another
+        let (entries,_)=uptimerobot_entries(json!([page.clone(),page])).unwrap();
+        assert_eq!(entries.len(),4);
+    }
+
+    #[test]
+    fn reads_betterstack_pages(){
+        let page=json!({
+            "data":[
+                {"id":"2345","type":"monitor","attributes":{"url":"https://example.com","pronounceable_name":"Homepage","monitor_type":"status","paused":false}},
+                {"id":"2346","type":"monitor","attributes":{"url":"https://example.com/login","pronounceable_name":"Login","monitor_type":"keyword","paused":true}},
+                {"id":"2347","type":"monitor","attributes":{"url":"example.com","pronounceable_name":"Ping","monitor_type":"ping","paused":false}}
+            ],
+            "pagination":{"first":"https://uptime.betterstack.com/api/v2/monitors?page=1","next":null}
+        });
+
+        let (entries,skipped)=betterstack_entries(page).unwrap();
+        assert_eq!(summary(&entries),[
+            ("betterstack-2345","https://example.com",Some("Homepage"),false),
+            ("betterstack-2346","https://example.com/login",Some("Login"),true)
+        ]);
+        assert_eq!(skipped.len(),1);
+        assert_eq!(skipped[0].reason,"ping monitors can't be imported");
+    }
+
+    #[test]
+    fn rejects_other_documents(){
+        assert!(matches!(uptimerobot_entries(json!({"data":[]})),Err(ApiError::BadRequest(_))));
+        assert!(matches!(betterstack_entries(json!({"monitors":[]})),Err(ApiError::BadRequest(_))));
+        assert!(matches!(uptimerobot_entries(json!("https://example.com")),Err(ApiError::BadRequest(_))));
+    }
+
+    #[test]
+    fn ids_read_the_same_as_numbers_or_strings(){
+        assert_eq!(id_text(&json!(777)),"777");
+        assert_eq!(id_text(&json!("777")),"777");
+    }
+
+    #[test]
+    fn reads_csv_with_a_header(){
+        let entries=parse_csv("name,url\nHomepage,https://example.com\n\"API, v2\",https://api.example.com\n").unwrap();
+
+        assert_eq!(summary(&entries),[
+            ("csv-2","https://example.com",Some("Homepage"),false),
+            ("csv-3","https://api.example.com",Some("API, v2"),false)
+        ]);
+    }
+
+    #[test]
+    fn reads_csv_without_a_header(){
+        let entries=parse_csv("https://example.com, Homepage\nhttps://api.example.com\n").unwrap();
+
+        assert_eq!(summary(&entries),[
+            ("csv-1","https://example.com",Some("Homepage"),false),
+            ("csv-2","https://api.example.com",None,false)
+        ]);
+    }
+
+    #[test]
+    fn header_without_a_name_column_reads_no_names(){
+        let entries=parse_csv("URL,interval\nhttps://example.com,60\n").unwrap();
+
+        assert_eq!(summary(&entries),[("csv-2","https://example.com",None,false)]);
+    }
+}
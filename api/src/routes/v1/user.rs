@@ -0,0 +1,123 @@
+use poem::web::Data;
+use poem_openapi::{param::Path, payload::Json, OpenApi};
+use crate::{auth_middleware::UserId, error::ApiError, routes::{ApiTags, NoContent}};
+use api_types::{request_input::{CreateApiKeyInput, CreateUserInput, InviteTeamMemberInput}, request_output::{ApiKeyOutput, CreateUserOutput, SigninOutput, TeamMemberOutput}};
+use db::{db::Db, error::DbError};
+
+use serde::{Serialize, Deserialize};
+use jsonwebtoken::{encode, Header, EncodingKey};
+
+/// Our claims struct, it needs to derive `Serialize` and/or `Deserialize`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String,
+    exp: usize,
+}
+
+pub struct UserApi;
+
+#[OpenApi(tag="ApiTags::Users")]
+impl UserApi{
+    #[oai(path="/user/signup",method="post")]
+    async fn sign_up(&self,Json(data):Json<CreateUserInput>,Data(s):Data<&Db>)->Result<Json<CreateUserOutput>,ApiError>{
+        let id=s.sign_up(data.username, data.password).await.map_err(|e| match e{
+            DbError::Conflict(_)=>ApiError::Conflict("this username is already taken".to_string()),
+            e=>ApiError::from(e)
+        })?;
+
+        let response=CreateUserOutput{
+         id
+        };
+        Ok(Json(response))
+    }
+
+    #[oai(path="/user/signin",method="post")]
+    async fn sign_in(&self,Json(data):Json<CreateUserInput>,Data(s):Data<&Db>)->Result<Json<SigninOutput>,ApiError>{
+        // unknown usernames and wrong passwords both come back as InvalidCredentials, a 401
+        let user_id=s.sign_in(data.username, data.password).await?;
+
+        let my_claims= Claims {
+            exp:111111111111111,        
+            sub: user_id,
+        };
+        let token = encode(&Header::default(), &my_claims, &EncodingKey::from_secret("secret".as_ref())).map_err(|_| ApiError::Internal)?;
+
+        let response=SigninOutput{jwt:token};
+        Ok(Json(response))
+    }
+
+    /// A key for scripts, used like the jwt. It is only shown in this response.
+    #[oai(path="/user/api-key",method="post")]
+    async fn create_api_key(&self,Json(data):Json<CreateApiKeyInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<ApiKeyOutput>,ApiError>{
+        let name=data.name.trim().to_string();
+        if name.is_empty(){
+            return Err(ApiError::BadRequest("name must not be empty".to_string()));
+        }
+        let (api_key,key)=s.create_api_key(user_id, name).await?;
+
+        Ok(Json(ApiKeyOutput{ key:Some(key),..api_key.into() }))
+    }
+
+    #[oai(path="/user/api-keys",method="get")]
+    async fn get_api_keys(&self,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<Vec<ApiKeyOutput>>,ApiError>{
+        let api_keys=s.get_api_keys(user_id).await?;
+
+        Ok(Json(api_keys.into_iter().map(ApiKeyOutput::from).collect()))
+    }
+
+    /// Revokes the key, requests using it fail from then on.
+    #[oai(path="/user/api-key/:id",method="delete")]
+    async fn delete_api_key(&self,Path(id): Path<String>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<NoContent,ApiError>{
+        s.delete_api_key(user_id, id).await?;
+
+        Ok(NoContent::NoContent)
+    }
+
+    /// Invites a user into your team. Once they accept they can be put on your on-call schedules.
+    #[oai(path="/user/team-member",method="post")]
+    async fn invite_team_member(&self,Json(data):Json<InviteTeamMemberInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<TeamMemberOutput>,ApiError>{
+        let username=data.username.trim().to_string();
+        let member=s.invite_team_member(user_id, username.clone()).await.map_err(|e| match e{
+            DbError::NotFound=>ApiError::Unprocessable(format!("there is no user called `{}`", username)),
+            DbError::Conflict(detail)=>ApiError::Conflict(detail),
+            e=>ApiError::from(e)
+        })?;
+
+        Ok(Json(member.into()))
+    }
+
+    /// Everyone you invited, accepted or not.
+    #[oai(path="/user/team-members",method="get")]
+    async fn get_team_members(&self,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<Vec<TeamMemberOutput>>,ApiError>{
+        let members=s.get_team_members(user_id).await?;
+
+        Ok(Json(members.into_iter().map(TeamMemberOutput::from).collect()))
+    }
+
+    /// Invitations into other users' teams, accepted or not.
+    #[oai(path="/user/team-invitations",method="get")]
+    async fn get_team_invitations(&self,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<Vec<TeamMemberOutput>>,ApiError>{
+        let invitations=s.get_team_invitations(user_id).await?;
+
+        Ok(Json(invitations.into_iter().map(TeamMemberOutput::from).collect()))
+    }
+
+    #[oai(path="/user/team-invitation/:id/accept",method="post")]
+    async fn accept_team_invitation(&self,Path(id): Path<String>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<TeamMemberOutput>,ApiError>{
+        let member=s.accept_team_invitation(user_id, id).await?;
+
+        Ok(Json(member.into()))
+    }
+
+    /// Removes a member from your team, or leaves a team you were invited into. Members still
+    /// on one of the team's on-call schedules have to be taken off it first.
+    #[oai(path="/user/team-member/:id",method="delete")]
+    async fn delete_team_member(&self,Path(id): Path<String>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<NoContent,ApiError>{
+        s.delete_team_member(user_id, id).await.map_err(|e| match e{
+            DbError::Conflict(detail)=>ApiError::Conflict(detail),
+            e=>ApiError::from(e)
+        })?;
+
+        Ok(NoContent::NoContent)
+    }
+}
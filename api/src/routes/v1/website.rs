@@ -0,0 +1,255 @@
+use std::collections::BTreeMap;
+use chrono::{Duration, Utc};
+use poem::web::Data;
+use poem_openapi::{param::{Path, Query}, payload::Json, OpenApi};
+use crate::{auth_middleware::UserId, error::ApiError, routes::{v1::page, ApiTags, NoContent}};
+use api_types::{request_input::{BulkWebsiteInput, CreateWebsiteInput, SetDependenciesInput, SetEscalationPolicyInput, SetTagsInput, SetWebsiteGroupInput, UpdateWebsiteInput}, request_output::{CreateWebsiteOutput, DependenciesOutput, GetWebsiteOutput, TagsOutput, UptimeOutput, WebsiteSummaryOutput}};
+use db::{db::Db, error::DbError, models::{website::{normalize_url, WebsiteFilter}, website_group::MonitorState}};
+
+/// Parses `key:value,key:value` tag filters.
+fn parse_filter(group_id:Option<String>,tag:Option<String>)->Result<WebsiteFilter,ApiError>{
+   let mut tags=Vec::new();
+   for pair in tag.iter().flat_map(|t| t.split(',')).filter(|p| !p.is_empty()){
+       let (key,value)=pair.split_once(':').ok_or_else(|| ApiError::BadRequest(format!("tag filter `{}` must be key:value", pair)))?;
+       tags.push((key.to_string(),value.to_string()));
+   }
+   Ok(WebsiteFilter{ group_id,tags })
+}
+
+/// Keys can't contain `:` or `,` and values can't contain `,`, or they couldn't be filtered on.
+pub fn validate_tags(tags:&BTreeMap<String,String>)->Result<(),ApiError>{
+   for (key,value) in tags{
+       if key.is_empty() || key.contains([':',',']) || value.contains(','){
+           return Err(ApiError::BadRequest(format!("invalid tag `{}:{}`", key, value)));
+       }
+   }
+   Ok(())
+}
+
+/// Ids from a bulk request: the listed ones plus everything the filter matches.
+async fn bulk_ids(s:&Db,user_id:&str,data:BulkWebsiteInput)->Result<Vec<String>,ApiError>{
+   let filter=parse_filter(data.group_id,data.tag)?;
+   let mut ids=data.ids;
+   if !filter.is_empty(){
+       let matched=s.get_websites(user_id.to_string(),&filter).await?;
+       ids.extend(matched.into_iter().map(|w| w.id));
+   }
+   ids.sort();
+   ids.dedup();
+   Ok(ids)
+}
+
+fn uptime_since(days:Option<i64>)->Result<chrono::DateTime<Utc>,ApiError>{
+   let days=days.unwrap_or(30);
+   if !(1..=365).contains(&days){
+       return Err(ApiError::BadRequest("days must be between 1 and 365".to_string()));
+   }
+   Ok(Utc::now()-Duration::days(days))
+}
+
+async fn uptime_output(s:&Db,website_id:String,since:chrono::DateTime<Utc>)->Result<UptimeOutput,ApiError>{
+   let uptime=s.get_uptime(website_id.clone(),since.naive_utc()).await?;
+
+   Ok(UptimeOutput{
+       website_id,
+       since:since.to_rfc3339(),
+       uptime_percentage:uptime.percentage(),
+       up_ticks:uptime.up_ticks,
+       down_ticks:uptime.down_ticks,
+       maintenance_ticks:uptime.maintenance_ticks
+   })
+}
+
+pub struct WebsiteApi;
+
+#[OpenApi(tag="ApiTags::Websites")]
+impl WebsiteApi{
+    #[oai(path="/status/:website_id",method="get")]
+    async fn get_website(&self,Path(website_id): Path<String>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<GetWebsiteOutput>,ApiError>{
+        let website=s.get_website(user_id,website_id).await?;
+        let response=GetWebsiteOutput::from(website);
+        Ok(Json(response))
+    }
+
+    #[oai(path="/website",method="post")]
+    async fn create_website(&self,Json(data):Json<CreateWebsiteInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<CreateWebsiteOutput>,ApiError> {
+        let url=normalize_url(&data.url).map_err(ApiError::BadRequest)?;
+        let website=s.create_website(user_id,url).await.map_err(|e| match e{
+            DbError::Conflict(_)=>ApiError::Conflict("you already monitor this url".to_string()),
+            e=>ApiError::from(e)
+        })?;
+
+        let response=CreateWebsiteOutput { id: website.id };
+        Ok(Json(response))
+    }
+
+    /// Changes the monitored url; ticks, incidents and settings stay with the website.
+    #[oai(path="/website/:id",method="put")]
+    async fn update_website(&self,Path(id): Path<String>,Json(data):Json<UpdateWebsiteInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<GetWebsiteOutput>,ApiError>{
+        let url=normalize_url(&data.url).map_err(ApiError::BadRequest)?;
+        let website=s.update_website_url(user_id,id,url).await.map_err(|e| match e{
+            DbError::Conflict(_)=>ApiError::Conflict("you already monitor this url".to_string()),
+            e=>ApiError::from(e)
+        })?;
+
+        Ok(Json(GetWebsiteOutput::from(website)))
+    }
+
+    /// Deletes the website along with its ticks and incidents.
+    #[oai(path="/website/:id",method="delete")]
+    async fn delete_website(&self,Path(id): Path<String>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<NoContent,ApiError>{
+        let deleted=s.delete_website(user_id,id).await?;
+        if deleted==0{
+            return Err(ApiError::NotFound("not found".to_string()));
+        }
+
+        Ok(NoContent::NoContent)
+    }
+
+    /// Oldest first, every website unless a `limit` is given. Pass the last id as `after` for the next page.
+    #[oai(path="/websites",method="get")]
+    async fn get_websites(
+        &self,
+        Query(group_id):Query<Option<String>>,
+        /// Comma separated `key:value` pairs, all of which must match.
+        Query(tag):Query<Option<String>>,
+        Query(limit):Query<Option<i64>>,
+        Query(after):Query<Option<String>>,
+        Data(s):Data<&Db>,
+        UserId(user_id):UserId
+    )->Result<Json<Vec<WebsiteSummaryOutput>>,ApiError>{
+        let filter=parse_filter(group_id,tag)?;
+
+        let websites=s.get_websites_page(user_id,&filter,&page(limit,after,None)?).await?;
+        let ids:Vec<String>=websites.iter().map(|w| w.id.clone()).collect();
+        let mut tags:BTreeMap<String,BTreeMap<String,String>>=BTreeMap::new();
+        for tag in s.get_website_tags(&ids).await?{
+            tags.entry(tag.website_id).or_default().insert(tag.key,tag.value);
+        }
+        let states=s.get_monitor_states(&websites).await?;
+
+        Ok(Json(websites.into_iter().map(|w| WebsiteSummaryOutput{
+            status:states.get(&w.id).copied().unwrap_or(MonitorState::Unknown).as_str().to_string(),
+            tags:tags.remove(&w.id).unwrap_or_default(),
+            id:w.id,
+            url:w.url,
+            paused:w.paused,
+            group_id:w.group_id
+        }).collect()))
+    }
+
+    #[oai(path="/website/:id/escalation-policy",method="put")]
+    async fn set_escalation_policy(&self,Path(id): Path<String>,Json(data):Json<SetEscalationPolicyInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<NoContent,ApiError>{
+        s.set_website_escalation_policy(user_id,id,data.policy_id).await?;
+
+        Ok(NoContent::NoContent)
+    }
+
+    /// Uptime over the last `days` (30 by default), not counting maintenance windows.
+    #[oai(path="/website/:id/uptime",method="get")]
+    async fn get_website_uptime(&self,Path(id): Path<String>,Query(days):Query<Option<i64>>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<UptimeOutput>,ApiError>{
+        let since=uptime_since(days)?;
+
+        let website=s.get_website(user_id,id).await?;
+
+        Ok(Json(uptime_output(s,website.id,since).await?))
+    }
+
+    /// Uptime of every website matching the `group_id` / `tag` filter.
+    #[oai(path="/websites/uptime",method="get")]
+    async fn get_uptime_report(
+        &self,
+        /// 30 by default, at most 365.
+        Query(days):Query<Option<i64>>,
+        Query(group_id):Query<Option<String>>,
+        /// Comma separated `key:value` pairs, all of which must match.
+        Query(tag):Query<Option<String>>,
+        Data(s):Data<&Db>,
+        UserId(user_id):UserId
+    )->Result<Json<Vec<UptimeOutput>>,ApiError>{
+        let since=uptime_since(days)?;
+        let filter=parse_filter(group_id,tag)?;
+
+        let websites=s.get_websites(user_id,&filter).await?;
+        let mut report=Vec::with_capacity(websites.len());
+        for website in websites{
+            report.push(uptime_output(s,website.id,since).await?);
+        }
+
+        Ok(Json(report))
+    }
+
+    /// Stops checking a website until it is resumed. Pausing a paused website is a no-op.
+    #[oai(path="/website/:id/pause",method="post")]
+    async fn pause_website(&self,Path(id): Path<String>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<GetWebsiteOutput>,ApiError>{
+        s.pause_websites(user_id.clone(),vec![id.clone()]).await?;
+        let website=s.get_website(user_id,id).await?;
+
+        Ok(Json(website.into()))
+    }
+
+    #[oai(path="/website/:id/resume",method="post")]
+    async fn resume_website(&self,Path(id): Path<String>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<GetWebsiteOutput>,ApiError>{
+        s.resume_websites(user_id.clone(),vec![id.clone()]).await?;
+        let website=s.get_website(user_id,id).await?;
+
+        Ok(Json(website.into()))
+    }
+
+    /// Returns the websites whose state changed.
+    #[oai(path="/websites/pause",method="post")]
+    async fn pause_websites(&self,Json(data):Json<BulkWebsiteInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<Vec<GetWebsiteOutput>>,ApiError>{
+        let ids=bulk_ids(s,&user_id,data).await?;
+        let paused=s.pause_websites(user_id,ids).await?;
+
+        Ok(Json(paused.into_iter().map(GetWebsiteOutput::from).collect()))
+    }
+
+    #[oai(path="/websites/resume",method="post")]
+    async fn resume_websites(&self,Json(data):Json<BulkWebsiteInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<Vec<GetWebsiteOutput>>,ApiError>{
+        let ids=bulk_ids(s,&user_id,data).await?;
+        let resumed=s.resume_websites(user_id,ids).await?;
+
+        Ok(Json(resumed.into_iter().map(GetWebsiteOutput::from).collect()))
+    }
+
+    #[oai(path="/website/:id/dependencies",method="get")]
+    async fn get_dependencies(&self,Path(id): Path<String>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<DependenciesOutput>,ApiError>{
+        let website=s.get_website(user_id,id).await?;
+        let depends_on=s.get_website_dependencies(website.id.clone()).await?;
+
+        Ok(Json(DependenciesOutput{ website_id:website.id,depends_on }))
+    }
+
+    /// Replaces the websites this one depends on. While any of them has an open incident,
+    /// this website's failures are folded into that incident instead of alerting on their own.
+    #[oai(path="/website/:id/dependencies",method="put")]
+    async fn set_dependencies(&self,Path(id): Path<String>,Json(data):Json<SetDependenciesInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<DependenciesOutput>,ApiError>{
+        let depends_on=s.set_website_dependencies(user_id,id.clone(),data.depends_on).await.map_err(|e| match e{
+            DbError::Conflict(detail)=>ApiError::BadRequest(detail),
+            e=>ApiError::from(e)
+        })?;
+
+        Ok(Json(DependenciesOutput{ website_id:id,depends_on }))
+    }
+
+    #[oai(path="/website/:id/group",method="put")]
+    async fn set_website_group(&self,Path(id): Path<String>,Json(data):Json<SetWebsiteGroupInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<GetWebsiteOutput>,ApiError>{
+        let website=s.set_website_group(user_id,id,data.group_id).await?;
+
+        Ok(Json(website.into()))
+    }
+
+    /// Replaces the website's tags. Keys can't contain `:` or `,`, values can't contain `,`.
+    #[oai(path="/website/:id/tags",method="put")]
+    async fn set_website_tags(&self,Path(id): Path<String>,Json(data):Json<SetTagsInput>,Data(s):Data<&Db>,UserId(user_id):UserId)->Result<Json<TagsOutput>,ApiError>{
+        validate_tags(&data.tags)?;
+
+        let tags=s.set_website_tags(user_id,id.clone(),data.tags.into_iter().collect()).await?;
+
+        Ok(Json(TagsOutput{
+            website_id:id,
+            tags:tags.into_iter().map(|t| (t.key,t.value)).collect()
+        }))
+    }
+}
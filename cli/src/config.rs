@@ -0,0 +1,70 @@
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+
+/// Profile used when neither `--profile` nor `default_profile` picks one.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// `~/.config/bu/config.toml`:
+///
+/// ```toml
+/// default_profile = "prod"
+///
+/// [profiles.prod]
+/// api_url = "https://api.example.com"
+/// token = "eyJ..."
+/// ```
+#[derive(Serialize, Deserialize, Default)]
+pub struct Config {
+    pub default_profile: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// One api and the account to use it as.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Profile {
+    /// Where the api is served, without the version.
+    pub api_url: String,
+    /// Set by `bu login`, removed by `bu logout`.
+    pub token: Option<String>,
+}
+
+impl Config {
+    pub fn default_path() -> anyhow::Result<PathBuf> {
+        let dir = dirs::config_dir().ok_or_else(|| anyhow!("no config directory, pass --config"))?;
+        Ok(dir.join("bu").join("config.toml"))
+    }
+
+    /// A missing file is an empty config.
+    pub fn load(path: &PathBuf) -> anyhow::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text).with_context(|| format!("reading {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("reading {}", path.display())),
+        }
+    }
+
+    /// Only readable by the owner, it holds tokens.
+    pub fn save(&self, path: &PathBuf) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+        }
+        fs::write(path, toml::to_string_pretty(self)?).with_context(|| format!("writing {}", path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(())
+    }
+
+    /// `--profile`, else the configured default, else [`DEFAULT_PROFILE`].
+    pub fn profile_name(&self, requested: Option<&str>) -> String {
+        requested
+            .map(str::to_string)
+            .or_else(|| self.default_profile.clone())
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+    }
+}
@@ -0,0 +1,59 @@
+use clap::{Subcommand, ValueEnum};
+
+use crate::{
+    output::{print_one, print_rows},
+    Context,
+};
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Status {
+    Open,
+    Acknowledged,
+    Resolved,
+}
+
+impl Status {
+    fn as_str(self) -> &'static str {
+        match self {
+            Status::Open => "open",
+            Status::Acknowledged => "acknowledged",
+            Status::Resolved => "resolved",
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum IncidentCommand {
+    /// Lists incidents, newest first.
+    List {
+        /// Only incidents in these states; repeat for several.
+        #[arg(long, value_enum)]
+        status: Vec<Status>,
+        /// Only incidents of this website.
+        #[arg(long)]
+        website: Option<String>,
+    },
+    /// Stops escalating an incident.
+    #[command(visible_alias = "ack")]
+    Acknowledge { id: String },
+    Resolve { id: String },
+    /// What happened during an incident.
+    Timeline { id: String },
+}
+
+pub async fn run(ctx: &Context, cmd: IncidentCommand) -> anyhow::Result<()> {
+    let client = ctx.client()?;
+    match cmd {
+        IncidentCommand::List { status, website } => {
+            let mut incidents = client.get_incidents().await?;
+            incidents.retain(|i| {
+                (status.is_empty() || status.iter().any(|s| s.as_str() == i.status))
+                    && website.as_ref().is_none_or(|w| *w == i.website_id)
+            });
+            print_rows(ctx.format, &incidents)
+        }
+        IncidentCommand::Acknowledge { id } => print_one(ctx.format, &client.acknowledge_incident(&id).await?),
+        IncidentCommand::Resolve { id } => print_one(ctx.format, &client.resolve_incident(&id).await?),
+        IncidentCommand::Timeline { id } => print_rows(ctx.format, &client.get_incident_timeline(&id).await?),
+    }
+}
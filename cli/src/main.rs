@@ -0,0 +1,189 @@
+//! `bu`, the betteruptime command line.
+//!
+//! Sign in once with `bu login --api-url https://api.example.com --username alice`, then e.g.
+//! `bu websites list -o csv` or `bu tail`. In CI, `BU_API_URL` and `BU_TOKEN` replace the login.
+
+use std::{
+    io::{self, BufRead, IsTerminal, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use anyhow::{anyhow, bail};
+use betteruptime_client::Client;
+use clap::{Args, Parser, Subcommand};
+
+mod config;
+mod incidents;
+mod monitors;
+mod output;
+mod profile;
+mod report;
+mod tail;
+mod websites;
+
+use config::{Config, Profile};
+use output::Format;
+
+#[derive(Parser)]
+#[command(name = "bu", version, about = "Manage betteruptime monitors from the terminal")]
+struct Cli {
+    /// Profile from the config file to use.
+    #[arg(long, short, global = true, env = "BU_PROFILE")]
+    profile: Option<String>,
+    /// Config file, `~/.config/bu/config.toml` by default.
+    #[arg(long, global = true, env = "BU_CONFIG")]
+    config: Option<PathBuf>,
+    /// Api to talk to instead of the profile's, without the version.
+    #[arg(long, global = true, env = "BU_API_URL")]
+    api_url: Option<String>,
+    /// Token to use instead of the profile's.
+    #[arg(long, global = true, env = "BU_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+    #[arg(long, short, global = true, value_enum, default_value = "table")]
+    output: Format,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Signs in and keeps the token in the profile.
+    Login(profile::LoginArgs),
+    /// Forgets the profile's token.
+    Logout,
+    #[command(subcommand)]
+    Profile(profile::ProfileCommand),
+    #[command(subcommand, visible_alias = "website")]
+    Websites(websites::WebsiteCommand),
+    #[command(subcommand, visible_alias = "incident")]
+    Incidents(incidents::IncidentCommand),
+    /// Follows check results and status changes as they happen.
+    Tail(tail::TailArgs),
+    #[command(subcommand)]
+    Report(report::ReportCommand),
+    /// Shows what applying a monitors-as-code file would change.
+    Plan(monitors::PlanArgs),
+    /// Makes the account match a monitors-as-code file.
+    Apply(monitors::ApplyArgs),
+    /// Writes every website, channel and status page as a monitors-as-code file.
+    Export(monitors::ExportArgs),
+    /// Adds monitors from `bu export`, UptimeRobot, Better Stack or a CSV of urls.
+    Import(monitors::ImportArgs),
+}
+
+/// Selects websites by group and tags, like the api's filters.
+#[derive(Args, Default)]
+pub struct WebsiteFilter {
+    /// Only websites in this group or its subgroups.
+    #[arg(long)]
+    group: Option<String>,
+    /// `key:value`; repeat to require several.
+    #[arg(long = "tag")]
+    tags: Vec<String>,
+}
+
+impl WebsiteFilter {
+    fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    /// The api's comma separated form.
+    fn tag(&self) -> Option<String> {
+        (!self.tags.is_empty()).then(|| self.tags.join(","))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.group.is_none() && self.tags.is_empty()
+    }
+}
+
+/// What every command gets: the loaded config, which profile is in use and how to print.
+pub struct Context {
+    pub config_path: PathBuf,
+    pub config: Config,
+    pub profile_name: String,
+    pub format: Format,
+    api_url: Option<String>,
+    token: Option<String>,
+}
+
+impl Context {
+    fn profile(&self) -> Option<&Profile> {
+        self.config.profiles.get(&self.profile_name)
+    }
+
+    pub fn api_url(&self) -> anyhow::Result<String> {
+        self.api_url
+            .clone()
+            .or_else(|| self.profile().map(|p| p.api_url.clone()))
+            .ok_or_else(|| anyhow!("no api url for profile `{}`, pass --api-url or run `bu login`", self.profile_name))
+    }
+
+    /// A client signed in as the profile's account.
+    pub fn client(&self) -> anyhow::Result<Client> {
+        let token = self
+            .token
+            .clone()
+            .or_else(|| self.profile().and_then(|p| p.token.clone()))
+            .ok_or_else(|| anyhow!("not signed in to profile `{}`, run `bu login`", self.profile_name))?;
+        Ok(Client::new(&self.api_url()?).with_token(token))
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        self.config.save(&self.config_path)
+    }
+}
+
+/// Asks on a terminal; scripts have to pass `--yes`.
+pub fn confirm(question: &str) -> anyhow::Result<bool> {
+    if !io::stdin().is_terminal() {
+        bail!("{} Pass --yes to confirm", question);
+    }
+    eprint!("{} [y/N] ", question);
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+async fn run(cli: Cli) -> anyhow::Result<()> {
+    let config_path = match cli.config {
+        Some(path) => path,
+        None => Config::default_path()?,
+    };
+    let config = Config::load(&config_path)?;
+    let mut ctx = Context {
+        profile_name: config.profile_name(cli.profile.as_deref()),
+        config_path,
+        config,
+        format: cli.output,
+        api_url: cli.api_url,
+        token: cli.token,
+    };
+
+    match cli.command {
+        Command::Login(args) => profile::login(&mut ctx, args).await,
+        Command::Logout => profile::logout(&mut ctx),
+        Command::Profile(cmd) => profile::run(&mut ctx, cmd),
+        Command::Websites(cmd) => websites::run(&ctx, cmd).await,
+        Command::Incidents(cmd) => incidents::run(&ctx, cmd).await,
+        Command::Tail(args) => tail::run(&ctx, args).await,
+        Command::Report(cmd) => report::run(&ctx, cmd).await,
+        Command::Plan(args) => monitors::plan(&ctx, args).await,
+        Command::Apply(args) => monitors::apply(&ctx, args).await,
+        Command::Export(args) => monitors::export(&ctx, args).await,
+        Command::Import(args) => monitors::import(&ctx, args).await,
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {:#}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use std::{
+    fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context as _};
+use betteruptime_client::types::{
+    request_input::MonitorConfigInput,
+    request_output::{ConfigChangeOutput, ConfigPlanOutput, ImportOutput},
+};
+use clap::{Args, ValueEnum};
+
+use crate::{
+    confirm,
+    output::{print_rows, Format, Row},
+    Context,
+};
+
+/// Exit code of `bu plan --detailed-exitcode` when there are changes.
+const EXIT_CHANGES: i32 = 2;
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Syntax {
+    Yaml,
+    Toml,
+    Json,
+}
+
+#[derive(Args)]
+struct DocumentArgs {
+    /// File describing the monitors, or `-` for stdin.
+    file: PathBuf,
+    /// Syntax of the file; by default taken from its extension, yaml for stdin.
+    #[arg(long, value_enum)]
+    syntax: Option<Syntax>,
+}
+
+#[derive(Args)]
+pub struct PlanArgs {
+    #[command(flatten)]
+    document: DocumentArgs,
+    /// Exit with 2 instead of 0 when there are changes, for CI.
+    #[arg(long)]
+    detailed_exitcode: bool,
+}
+
+#[derive(Args)]
+pub struct ApplyArgs {
+    #[command(flatten)]
+    document: DocumentArgs,
+    /// Don't ask for confirmation.
+    #[arg(long, short)]
+    yes: bool,
+}
+
+#[derive(Args)]
+pub struct ExportArgs {
+    /// Where to write the document; stdout by default.
+    #[arg(long, short)]
+    file: Option<PathBuf>,
+    /// Syntax to write; by default taken from the file's extension, yaml for stdout.
+    #[arg(long, value_enum)]
+    syntax: Option<Syntax>,
+}
+
+/// What an import file holds.
+#[derive(Clone, Copy, ValueEnum)]
+enum Source {
+    /// A document from `bu export`, in `--syntax`.
+    Document,
+    /// UptimeRobot `getMonitors` responses: one, or an array of pages.
+    Uptimerobot,
+    /// Better Stack `/api/v2/monitors` responses: one, or an array of pages.
+    Betterstack,
+    /// A url per row; a header row may name the `url` and `name` columns.
+    Csv,
+}
+
+#[derive(Args)]
+pub struct ImportArgs {
+    #[command(flatten)]
+    document: DocumentArgs,
+    #[arg(long, value_enum, default_value = "document")]
+    from: Source,
+    /// Show what would change without changing anything.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+impl Row for ConfigChangeOutput {
+    const HEADERS: &'static [&'static str] = &["action", "resource", "key", "changes"];
+
+    fn cells(&self) -> Vec<String> {
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|f| {
+                let from = f.from.as_deref().unwrap_or("-");
+                let to = f.to.as_deref().unwrap_or("-");
+                match self.action.as_str() {
+                    "create" => format!("{}={}", f.field, to),
+                    "delete" => format!("{}={}", f.field, from),
+                    _ => format!("{}: {} -> {}", f.field, from, to),
+                }
+            })
+            .collect();
+        vec![self.action.clone(), self.resource.clone(), self.key.clone(), fields.join("; ")]
+    }
+}
+
+impl DocumentArgs {
+    fn syntax(&self) -> anyhow::Result<Syntax> {
+        if let Some(syntax) = self.syntax {
+            return Ok(syntax);
+        }
+        if self.file == Path::new("-") {
+            return Ok(Syntax::Yaml);
+        }
+        match self.file.extension().and_then(|e| e.to_str()) {
+            Some("yaml" | "yml") => Ok(Syntax::Yaml),
+            Some("toml") => Ok(Syntax::Toml),
+            Some("json") => Ok(Syntax::Json),
+            _ => bail!("can't tell the syntax of {}, pass --syntax", self.file.display()),
+        }
+    }
+
+    fn read(&self) -> anyhow::Result<String> {
+        if self.file == Path::new("-") {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text).context("reading stdin")?;
+            return Ok(text);
+        }
+        fs::read_to_string(&self.file).with_context(|| format!("reading {}", self.file.display()))
+    }
+
+    fn load(&self) -> anyhow::Result<MonitorConfigInput> {
+        let text = self.read()?;
+        let parsed = match self.syntax()? {
+            Syntax::Yaml => serde_yaml::from_str(&text).map_err(anyhow::Error::from),
+            Syntax::Toml => toml::from_str(&text).map_err(anyhow::Error::from),
+            Syntax::Json => serde_json::from_str(&text).map_err(anyhow::Error::from),
+        };
+        parsed.with_context(|| format!("parsing {}", self.file.display()))
+    }
+}
+
+fn print_plan(format: Format, plan: &ConfigPlanOutput) -> anyhow::Result<()> {
+    if plan.changes.is_empty() {
+        if let Format::Json = format {
+            return print_rows(format, &plan.changes);
+        }
+        eprintln!("no changes, the account matches the document");
+        return Ok(());
+    }
+    print_rows(format, &plan.changes)
+}
+
+pub async fn plan(ctx: &Context, args: PlanArgs) -> anyhow::Result<()> {
+    let document = args.document.load()?;
+    let plan = ctx.client()?.plan_config(&document).await?;
+    print_plan(ctx.format, &plan)?;
+    if args.detailed_exitcode && !plan.changes.is_empty() {
+        std::process::exit(EXIT_CHANGES);
+    }
+    Ok(())
+}
+
+/// Shows the plan and asks before applying, unless `--yes`.
+pub async fn apply(ctx: &Context, args: ApplyArgs) -> anyhow::Result<()> {
+    let document = args.document.load()?;
+    let client = ctx.client()?;
+    if !args.yes {
+        let plan = client.plan_config(&document).await?;
+        if plan.changes.is_empty() {
+            return print_plan(ctx.format, &plan);
+        }
+        print_plan(Format::Table, &plan)?;
+        if !confirm("Apply these changes?")? {
+            bail!("nothing applied");
+        }
+    }
+    let applied = client.apply_config(&document).await?;
+    print_plan(ctx.format, &applied)
+}
+
+pub async fn export(ctx: &Context, args: ExportArgs) -> anyhow::Result<()> {
+    let syntax = match (args.syntax, &args.file) {
+        (Some(syntax), _) => syntax,
+        (None, None) => Syntax::Yaml,
+        (None, Some(file)) => DocumentArgs { file: file.clone(), syntax: None }.syntax()?,
+    };
+    let document = ctx.client()?.export_config().await?;
+    let text = match syntax {
+        Syntax::Yaml => serde_yaml::to_string(&document)?,
+        Syntax::Toml => toml::to_string(&document)?,
+        Syntax::Json => serde_json::to_string_pretty(&document)? + "\n",
+    };
+    match &args.file {
+        Some(file) => fs::write(file, text).with_context(|| format!("writing {}", file.display())),
+        None => Ok(io::stdout().lock().write_all(text.as_bytes())?),
+    }
+}
+
+pub async fn import(ctx: &Context, args: ImportArgs) -> anyhow::Result<()> {
+    let client = ctx.client()?;
+    let imported = match args.from {
+        Source::Document => client.import_config(&args.document.load()?, args.dry_run).await?,
+        Source::Uptimerobot | Source::Betterstack => {
+            let export: serde_json::Value = serde_json::from_str(&args.document.read()?).with_context(|| format!("parsing {}", args.document.file.display()))?;
+            if let Source::Uptimerobot = args.from {
+                client.import_uptimerobot(&export, args.dry_run).await?
+            } else {
+                client.import_betterstack(&export, args.dry_run).await?
+            }
+        }
+        Source::Csv => client.import_csv(&args.document.read()?, args.dry_run).await?,
+    };
+    print_import(ctx.format, &imported)
+}
+
+/// Changes on stdout like a plan; what was skipped goes to stderr, except in json where it's part of the output.
+fn print_import(format: Format, imported: &ImportOutput) -> anyhow::Result<()> {
+    if let Format::Json = format {
+        let mut out = io::stdout().lock();
+        serde_json::to_writer_pretty(&mut out, imported)?;
+        writeln!(out)?;
+        return Ok(());
+    }
+    if imported.changes.is_empty() {
+        eprintln!("no changes");
+    } else {
+        print_rows(format, &imported.changes)?;
+    }
+    if !imported.skipped.is_empty() {
+        eprintln!("skipped {}:", imported.skipped.len());
+        for skipped in &imported.skipped {
+            eprintln!("  {} {}: {}", skipped.source, skipped.url.as_deref().unwrap_or("-"), skipped.reason);
+        }
+    }
+    Ok(())
+}
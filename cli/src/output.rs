@@ -0,0 +1,197 @@
+use std::io::{self, Write};
+
+use betteruptime_client::types::request_output::{GetWebsiteOutput, IncidentEventOutput, IncidentOutput, UptimeOutput, WebsiteSummaryOutput};
+use clap::ValueEnum;
+use serde::Serialize;
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+    /// Aligned columns for reading.
+    Table,
+    /// What the api returned, for scripts.
+    Json,
+    /// One header row, for spreadsheets.
+    Csv,
+}
+
+/// How a record shows up in table and csv output; json output uses its serde form.
+pub trait Row {
+    const HEADERS: &'static [&'static str];
+
+    fn cells(&self) -> Vec<String>;
+}
+
+pub fn print_rows<T: Row + Serialize>(format: Format, rows: &[T]) -> anyhow::Result<()> {
+    write_rows(&mut io::stdout().lock(), format, rows)
+}
+
+fn write_rows<T: Row + Serialize>(out: &mut impl Write, format: Format, rows: &[T]) -> anyhow::Result<()> {
+    match format {
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut *out, rows)?;
+            writeln!(out)?;
+        }
+        Format::Csv => {
+            let mut writer = csv::Writer::from_writer(out);
+            writer.write_record(T::HEADERS)?;
+            for row in rows {
+                writer.write_record(row.cells())?;
+            }
+            writer.flush()?;
+        }
+        Format::Table => write_table(out, T::HEADERS, rows.iter().map(Row::cells).collect())?,
+    }
+    Ok(())
+}
+
+/// Like [`print_rows`], but json output is the object rather than a list of one.
+pub fn print_one<T: Row + Serialize>(format: Format, row: &T) -> anyhow::Result<()> {
+    match format {
+        Format::Json => {
+            let mut out = io::stdout().lock();
+            serde_json::to_writer_pretty(&mut out, row)?;
+            writeln!(out)?;
+            Ok(())
+        }
+        _ => print_rows(format, std::slice::from_ref(row)),
+    }
+}
+
+/// Cells are kept to one line, line breaks and tabs showing as spaces, so columns stay aligned.
+fn write_table(out: &mut impl Write, headers: &[&str], rows: Vec<Vec<String>>) -> io::Result<()> {
+    let rows: Vec<Vec<String>> = rows
+        .into_iter()
+        .map(|row| row.into_iter().map(|cell| cell.replace(['\r', '\n', '\t'], " ")).collect())
+        .collect();
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.len()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let headers: Vec<String> = headers.iter().map(|h| h.to_uppercase()).collect();
+    for row in std::iter::once(&headers).chain(&rows) {
+        let line: Vec<String> = row.iter().zip(&widths).map(|(cell, width)| format!("{:<width$}", cell, width = *width)).collect();
+        writeln!(out, "{}", line.join("  ").trim_end())?;
+    }
+    Ok(())
+}
+
+fn or_dash(value: &Option<String>) -> String {
+    value.clone().unwrap_or_else(|| "-".to_string())
+}
+
+impl Row for WebsiteSummaryOutput {
+    const HEADERS: &'static [&'static str] = &["id", "url", "status", "group", "tags"];
+
+    fn cells(&self) -> Vec<String> {
+        let tags: Vec<String> = self.tags.iter().map(|(k, v)| format!("{}:{}", k, v)).collect();
+        vec![self.id.clone(), self.url.clone(), self.status.clone(), or_dash(&self.group_id), tags.join(",")]
+    }
+}
+
+impl Row for GetWebsiteOutput {
+    const HEADERS: &'static [&'static str] = &["id", "url", "paused", "group"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.id.clone(), self.url.clone(), self.paused.to_string(), or_dash(&self.group_id)]
+    }
+}
+
+impl Row for IncidentOutput {
+    const HEADERS: &'static [&'static str] = &["id", "website", "status", "opened", "acknowledged", "resolved"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.website_id.clone(),
+            self.status.clone(),
+            self.opened_at.clone(),
+            or_dash(&self.acknowledged_at),
+            or_dash(&self.resolved_at),
+        ]
+    }
+}
+
+impl Row for IncidentEventOutput {
+    const HEADERS: &'static [&'static str] = &["at", "kind", "user", "message"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.created_at.clone(), self.kind.clone(), or_dash(&self.user_id), self.message.clone()]
+    }
+}
+
+impl Row for UptimeOutput {
+    const HEADERS: &'static [&'static str] = &["website", "since", "uptime", "up", "down", "maintenance"];
+
+    fn cells(&self) -> Vec<String> {
+        let uptime = self.uptime_percentage.map(|p| format!("{:.3}%", p)).unwrap_or_else(|| "-".to_string());
+        vec![
+            self.website_id.clone(),
+            self.since.clone(),
+            uptime,
+            self.up_ticks.to_string(),
+            self.down_ticks.to_string(),
+            self.maintenance_ticks.to_string(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(user_id: Option<&str>, message: &str) -> IncidentEventOutput {
+        IncidentEventOutput {
+            id: "event-1".to_string(),
+            kind: "comment".to_string(),
+            user_id: user_id.map(str::to_string),
+            message: message.to_string(),
+            created_at: "2026-03-02T10:00:00+00:00".to_string(),
+        }
+    }
+
+    fn written(format: Format, rows: &[IncidentEventOutput]) -> String {
+        let mut out = Vec::new();
+        write_rows(&mut out, format, rows).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn quotes_csv_cells_that_need_it() {
+        let rows = [event(None, "plain"), event(Some("alice"), "a, b"), event(None, "said \"hi\""), event(None, "two\nlines")];
+
+        assert_eq!(
+            written(Format::Csv, &rows),
+            "at,kind,user,message\n\
+             2026-03-02T10:00:00+00:00,comment,-,plain\n\
+             2026-03-02T10:00:00+00:00,comment,alice,\"a, b\"\n\
+             2026-03-02T10:00:00+00:00,comment,-,\"said \"\"hi\"\"\"\n\
+             2026-03-02T10:00:00+00:00,comment,-,\"two\nlines\"\n"
+        );
+    }
+
+    #[test]
+    fn aligns_table_columns() {
+        let rows = [event(Some("alice"), "first"), event(None, "ünïcode wider than the header")];
+
+        assert_eq!(
+            written(Format::Table, &rows),
+            "AT                         KIND     USER   MESSAGE\n\
+             2026-03-02T10:00:00+00:00  comment  alice  first\n\
+             2026-03-02T10:00:00+00:00  comment  -      ünïcode wider than the header\n"
+        );
+    }
+
+    #[test]
+    fn keeps_table_rows_on_one_line() {
+        let rows = [event(Some("bob"), "two\nlines\tand a tab"), event(Some("alice"), "x")];
+
+        assert_eq!(
+            written(Format::Table, &rows),
+            "AT                         KIND     USER   MESSAGE\n\
+             2026-03-02T10:00:00+00:00  comment  bob    two lines and a tab\n\
+             2026-03-02T10:00:00+00:00  comment  alice  x\n"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use std::io::{self, BufRead, Write};
+
+use anyhow::{bail, Context as _};
+use betteruptime_client::Client;
+use clap::{Args, Subcommand};
+
+use crate::{config::Profile, Context};
+
+#[derive(Args)]
+pub struct LoginArgs {
+    #[arg(long)]
+    username: String,
+    /// Asked for on stdin when not given.
+    #[arg(long, env = "BU_PASSWORD", hide_env_values = true)]
+    password: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum ProfileCommand {
+    /// Lists the configured profiles.
+    List,
+    /// Makes a profile the default.
+    Use { name: String },
+    /// Removes a profile and its token.
+    Remove { name: String },
+}
+
+fn read_password() -> anyhow::Result<String> {
+    eprint!("password: ");
+    io::stderr().flush()?;
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line).context("reading the password")?;
+    Ok(line.trim_end_matches(['\n', '\r']).to_string())
+}
+
+pub async fn login(ctx: &mut Context, args: LoginArgs) -> anyhow::Result<()> {
+    let api_url = ctx.api_url()?;
+    let password = match args.password {
+        Some(password) => password,
+        None => read_password()?,
+    };
+    let client = Client::new(&api_url).login(&args.username, &password).await?;
+
+    let name = ctx.profile_name.clone();
+    ctx.config.profiles.insert(name.clone(), Profile { api_url, token: client.token().map(str::to_string) });
+    ctx.config.default_profile.get_or_insert_with(|| name.clone());
+    ctx.save()?;
+    eprintln!("signed in as {} (profile `{}`)", args.username, name);
+    Ok(())
+}
+
+pub fn logout(ctx: &mut Context) -> anyhow::Result<()> {
+    match ctx.config.profiles.get_mut(&ctx.profile_name) {
+        Some(profile) => profile.token = None,
+        None => bail!("no profile `{}`", ctx.profile_name),
+    }
+    ctx.save()
+}
+
+pub fn run(ctx: &mut Context, cmd: ProfileCommand) -> anyhow::Result<()> {
+    match cmd {
+        ProfileCommand::List => {
+            for (name, profile) in &ctx.config.profiles {
+                let marker = if ctx.config.default_profile.as_deref() == Some(name) { "*" } else { " " };
+                let state = if profile.token.is_some() { "signed in" } else { "signed out" };
+                println!("{} {}  {}  {}", marker, name, profile.api_url, state);
+            }
+            Ok(())
+        }
+        ProfileCommand::Use { name } => {
+            if !ctx.config.profiles.contains_key(&name) {
+                bail!("no profile `{}`", name);
+            }
+            ctx.config.default_profile = Some(name);
+            ctx.save()
+        }
+        ProfileCommand::Remove { name } => {
+            if ctx.config.profiles.remove(&name).is_none() {
+                bail!("no profile `{}`", name);
+            }
+            if ctx.config.default_profile.as_deref() == Some(&name) {
+                ctx.config.default_profile = None;
+            }
+            ctx.save()
+        }
+    }
+}
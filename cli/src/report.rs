@@ -0,0 +1,25 @@
+use clap::Subcommand;
+
+use crate::{output::print_rows, Context, WebsiteFilter};
+
+#[derive(Subcommand)]
+pub enum ReportCommand {
+    /// Uptime per website, leaving out maintenance.
+    Uptime {
+        /// How many days back, 1 to 365.
+        #[arg(long, default_value_t = 30)]
+        days: i64,
+        #[command(flatten)]
+        filter: WebsiteFilter,
+    },
+}
+
+pub async fn run(ctx: &Context, cmd: ReportCommand) -> anyhow::Result<()> {
+    let client = ctx.client()?;
+    match cmd {
+        ReportCommand::Uptime { days, filter } => {
+            let report = client.get_uptime_report(Some(days), filter.group(), filter.tag().as_deref()).await?;
+            print_rows(ctx.format, &report)
+        }
+    }
+}
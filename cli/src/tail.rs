@@ -0,0 +1,129 @@
+use std::{collections::HashMap, io, time::Duration};
+
+use betteruptime_client::{types::request_output::LiveEventOutput, Client, ClientError};
+use chrono::DateTime;
+use clap::Args;
+use futures::{pin_mut, StreamExt};
+use serde_json::Value;
+
+use crate::{output::Format, Context};
+
+/// Wait before reconnecting after the stream dropped.
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Args)]
+pub struct TailArgs {
+    /// Only events of this website; repeat for several.
+    #[arg(long = "website")]
+    websites: Vec<String>,
+    /// Only status changes, no individual check results.
+    #[arg(long)]
+    status_only: bool,
+}
+
+/// Prints events as they arrive; json output is one object per line.
+struct Printer {
+    format: Format,
+    csv: csv::Writer<io::Stdout>,
+    urls: HashMap<String, String>,
+}
+
+impl Printer {
+    fn print(&mut self, event: &LiveEventOutput) -> anyhow::Result<()> {
+        if let Format::Json = self.format {
+            println!("{}", serde_json::to_string(event)?);
+            return Ok(());
+        }
+
+        let field = |name: &str| match &event.data[name] {
+            Value::Null => String::new(),
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        let at = event.data["at_ms"]
+            .as_i64()
+            .and_then(DateTime::from_timestamp_millis)
+            .map(|at| at.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default();
+        let website_id = field("website_id");
+        let url = self.urls.get(&website_id).cloned().unwrap_or(website_id);
+        let cells = [at, event.event.clone(), field("status"), url, field("region_id"), field("response_time_ms"), field("incident_id")];
+
+        match self.format {
+            Format::Csv => {
+                self.csv.write_record(&cells)?;
+                self.csv.flush()?;
+            }
+            _ => {
+                let [at, kind, status, url, region, response_time, incident] = cells;
+                let detail = match kind.as_str() {
+                    "tick" => format!("{} {}ms", region, response_time),
+                    _ if !incident.is_empty() => format!("incident {}", incident),
+                    _ => String::new(),
+                };
+                println!("{}  {:<6}  {:<7}  {}  {}", at, kind, status, url, detail);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether the stream should be opened again; errors the api answered with won't go away.
+fn is_transient(e: &ClientError) -> bool {
+    !matches!(e, ClientError::Api(problem) if problem.status < 500)
+}
+
+async fn website_urls(client: &Client) -> Result<HashMap<String, String>, ClientError> {
+    let websites = client.get_websites(None, None).await?;
+    Ok(websites.into_iter().map(|w| (w.id, w.url)).collect())
+}
+
+pub async fn run(ctx: &Context, args: TailArgs) -> anyhow::Result<()> {
+    let client = ctx.client()?;
+    let mut printer = Printer { format: ctx.format, csv: csv::Writer::from_writer(io::stdout()), urls: website_urls(&client).await? };
+    if let Format::Csv = ctx.format {
+        printer.csv.write_record(["at", "event", "status", "website", "region", "response_time_ms", "incident_id"])?;
+    }
+
+    loop {
+        let events = match client.live_events().await {
+            Ok(events) => events,
+            Err(e) if is_transient(&e) => {
+                eprintln!("{}, reconnecting", e);
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        pin_mut!(events);
+        while let Some(event) = events.next().await {
+            let event = match event {
+                Ok(event) => event,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    break;
+                }
+            };
+            if event.event == "resync" {
+                eprintln!("missed {} events", event.data["missed"]);
+                printer.urls = website_urls(&client).await?;
+                continue;
+            }
+            let website_id = event.data["website_id"].as_str().unwrap_or_default();
+            if !args.websites.is_empty() && !args.websites.iter().any(|w| w == website_id) {
+                continue;
+            }
+            if args.status_only && event.event != "status" {
+                continue;
+            }
+            if !printer.urls.contains_key(website_id) {
+                // added since we started; remember unknown ones as themselves so they're only fetched once
+                printer.urls = website_urls(&client).await?;
+                printer.urls.entry(website_id.to_string()).or_insert_with(|| website_id.to_string());
+            }
+            printer.print(&event)?;
+        }
+        eprintln!("stream ended, reconnecting");
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, bail};
+use betteruptime_client::{
+    types::request_input::{BulkWebsiteInput, CreateWebsiteInput, SetTagsInput, SetWebsiteGroupInput, UpdateWebsiteInput},
+    Client,
+};
+use clap::Subcommand;
+
+use crate::{
+    confirm,
+    output::{print_one, print_rows},
+    Context, WebsiteFilter,
+};
+
+#[derive(Subcommand)]
+pub enum WebsiteCommand {
+    /// Lists websites with their current status.
+    List {
+        #[command(flatten)]
+        filter: WebsiteFilter,
+    },
+    Get { id: String },
+    /// Starts monitoring a url.
+    Create {
+        url: String,
+        /// Group to put the website in.
+        #[arg(long)]
+        group: Option<String>,
+        /// `key:value`; repeat for several.
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+    /// Changes what is set; anything not passed is left alone.
+    Update {
+        id: String,
+        #[arg(long)]
+        url: Option<String>,
+        /// Group to move the website to.
+        #[arg(long, conflicts_with = "no_group")]
+        group: Option<String>,
+        /// Takes the website out of its group.
+        #[arg(long)]
+        no_group: bool,
+        /// `key:value`, replacing all tags; repeat for several.
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+        /// Removes all tags.
+        #[arg(long, conflicts_with = "tags")]
+        no_tags: bool,
+    },
+    /// Deletes a website with its check results and incidents.
+    Delete {
+        id: String,
+        /// Don't ask for confirmation.
+        #[arg(long, short)]
+        yes: bool,
+    },
+    /// Stops checking websites, by id or filter.
+    Pause {
+        ids: Vec<String>,
+        #[command(flatten)]
+        filter: WebsiteFilter,
+    },
+    /// Checks paused websites again, by id or filter.
+    Resume {
+        ids: Vec<String>,
+        #[command(flatten)]
+        filter: WebsiteFilter,
+    },
+}
+
+fn parse_tags(tags: &[String]) -> anyhow::Result<BTreeMap<String, String>> {
+    tags.iter()
+        .map(|tag| {
+            let (key, value) = tag.split_once(':').ok_or_else(|| anyhow!("tag `{}` must be key:value", tag))?;
+            Ok((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+fn bulk_input(ids: Vec<String>, filter: &WebsiteFilter) -> anyhow::Result<BulkWebsiteInput> {
+    if ids.is_empty() && filter.is_empty() {
+        bail!("pass website ids, --group or --tag");
+    }
+    Ok(BulkWebsiteInput { ids, group_id: filter.group().map(str::to_string), tag: filter.tag() })
+}
+
+async fn set_group_and_tags(client: &Client, id: &str, group: Option<Option<String>>, tags: Option<BTreeMap<String, String>>) -> anyhow::Result<()> {
+    if let Some(group_id) = group {
+        client.set_website_group(id, &SetWebsiteGroupInput { group_id }).await?;
+    }
+    if let Some(tags) = tags {
+        client.set_website_tags(id, &SetTagsInput { tags }).await?;
+    }
+    Ok(())
+}
+
+pub async fn run(ctx: &Context, cmd: WebsiteCommand) -> anyhow::Result<()> {
+    let client = ctx.client()?;
+    match cmd {
+        WebsiteCommand::List { filter } => {
+            let websites = client.get_websites(filter.group(), filter.tag().as_deref()).await?;
+            print_rows(ctx.format, &websites)
+        }
+        WebsiteCommand::Get { id } => print_one(ctx.format, &client.get_website(&id).await?),
+        WebsiteCommand::Create { url, group, tags } => {
+            let tags = parse_tags(&tags)?;
+            let created = client.create_website(&CreateWebsiteInput { url }).await?;
+            set_group_and_tags(&client, &created.id, group.map(Some), (!tags.is_empty()).then_some(tags)).await?;
+            print_one(ctx.format, &client.get_website(&created.id).await?)
+        }
+        WebsiteCommand::Update { id, url, group, no_group, tags, no_tags } => {
+            let tags = parse_tags(&tags)?;
+            if let Some(url) = url {
+                client.update_website(&id, &UpdateWebsiteInput { url }).await?;
+            }
+            let group = if no_group { Some(None) } else { group.map(Some) };
+            let tags = if no_tags || !tags.is_empty() { Some(tags) } else { None };
+            set_group_and_tags(&client, &id, group, tags).await?;
+            print_one(ctx.format, &client.get_website(&id).await?)
+        }
+        WebsiteCommand::Delete { id, yes } => {
+            let website = client.get_website(&id).await?;
+            if !yes && !confirm(&format!("Delete {} with its check results and incidents?", website.url))? {
+                bail!("not deleted");
+            }
+            client.delete_website(&id).await?;
+            eprintln!("deleted {}", website.url);
+            Ok(())
+        }
+        WebsiteCommand::Pause { ids, filter } => {
+            let paused = client.pause_websites(&bulk_input(ids, &filter)?).await?;
+            print_rows(ctx.format, &paused)
+        }
+        WebsiteCommand::Resume { ids, filter } => {
+            let resumed = client.resume_websites(&bulk_input(ids, &filter)?).await?;
+            print_rows(ctx.format, &resumed)
+        }
+    }
+}
@@ -0,0 +1,620 @@
+use api_types::{request_input::*, request_output::*};
+use futures::{stream, Stream, TryStreamExt};
+use reqwest::{header::{AUTHORIZATION, CONTENT_TYPE}, Method, RequestBuilder, Response};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{ClientError, RetryPolicy};
+
+/// Version of the api this client speaks, see the api's `/v1` routes.
+const API_PREFIX: &str = "/v1";
+
+pub(crate) const NO_BODY: Option<&()> = None;
+
+/// Items per request when walking a listing page by page.
+const PAGE_SIZE: i64 = 100;
+
+fn dry_run_query(dry_run: bool) -> [(&'static str, Option<String>); 1] {
+    [("dry_run", dry_run.then(|| "true".to_string()))]
+}
+
+/// One method per api endpoint, except the Slack app's, which only Slack can sign.
+/// Cheap to clone, clones share the connection pool.
+#[derive(Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+    retry: RetryPolicy,
+}
+
+impl Client {
+    /// `base_url` is where the api is served, without the version, e.g. `https://api.example.com`.
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: format!("{}{}", base_url.trim_end_matches('/'), API_PREFIX),
+            token: None,
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    /// Authenticates every request with a jwt from [`Client::sign_in`].
+    pub fn with_token(self, token: impl Into<String>) -> Self {
+        Self { token: Some(token.into()), ..self }
+    }
+
+    /// Authenticates every request with a key from [`Client::create_api_key`]. Keys don't
+    /// expire, which suits scripts better than signing in.
+    pub fn with_api_key(self, key: impl Into<String>) -> Self {
+        // the api tells keys from jwts by their prefix, both go in the same header
+        self.with_token(key)
+    }
+
+    pub fn with_retry(self, retry: RetryPolicy) -> Self {
+        Self { retry, ..self }
+    }
+
+    /// For timeouts, proxies and the like.
+    pub fn with_http_client(self, http: reqwest::Client) -> Self {
+        Self { http, ..self }
+    }
+
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    /// Signs in and returns a client using the issued token.
+    pub async fn login(self, username: &str, password: &str) -> Result<Self, ClientError> {
+        let input = CreateUserInput { username: username.to_string(), password: password.to_string() };
+        let output = self.sign_in(&input).await?;
+        Ok(self.with_token(output.jwt))
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let builder = self.http.request(method, format!("{}{}", self.base_url, path));
+        match &self.token {
+            // the api takes the bare token, no `Bearer` scheme
+            Some(token) => builder.header(AUTHORIZATION, token),
+            None => builder,
+        }
+    }
+
+    /// Sends the request, retrying per the [`RetryPolicy`]; any other non-2xx becomes a [`ClientError::Api`].
+    pub(crate) async fn send<B: Serialize + ?Sized>(&self, method: Method, path: &str, query: &[(&str, Option<String>)], body: Option<&B>) -> Result<Response, ClientError> {
+        self.send_with(method, path, query, |builder| match body {
+            Some(body) => builder.json(body),
+            None => builder,
+        })
+        .await
+    }
+
+    /// Like [`Client::send`], with `body` adding whatever body the endpoint takes to each attempt.
+    async fn send_with(&self, method: Method, path: &str, query: &[(&str, Option<String>)], body: impl Fn(RequestBuilder) -> RequestBuilder) -> Result<Response, ClientError> {
+        let query: Vec<(&str, &str)> = query.iter().filter_map(|(k, v)| v.as_deref().map(|v| (*k, v))).collect();
+        let mut retry = 0;
+        loop {
+            let builder = body(self.request(method.clone(), path).query(&query));
+            let response = builder.send().await?;
+            if response.status().is_success() {
+                return Ok(response);
+            }
+            retry += 1;
+            match self.retry.retry_after(&method, &response, retry) {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => return Err(ClientError::from_response(response).await),
+            }
+        }
+    }
+
+    async fn json<T: DeserializeOwned, B: Serialize + ?Sized>(&self, method: Method, path: &str, query: &[(&str, Option<String>)], body: Option<&B>) -> Result<T, ClientError> {
+        Ok(self.send(method, path, query, body).await?.json().await?)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, ClientError> {
+        self.json(Method::GET, path, &[], NO_BODY).await
+    }
+
+    /// Every item of a listing that takes `limit` and `after`, fetched a page at a time as the stream is read.
+    fn paginate<'a, T: DeserializeOwned + 'a>(&'a self, path: String, query: Vec<(&'static str, Option<String>)>, id: fn(&T) -> &str) -> impl Stream<Item = Result<T, ClientError>> + 'a {
+        // `None` once the last page was read
+        let start = Some(None::<String>);
+        stream::try_unfold(start, move |after| {
+            let path = path.clone();
+            let mut query = query.clone();
+            async move {
+                let Some(after) = after else {
+                    return Ok::<_, ClientError>(None);
+                };
+                query.push(("limit", Some(PAGE_SIZE.to_string())));
+                query.push(("after", after));
+                let page: Vec<T> = self.json(Method::GET, &path, &query, NO_BODY).await?;
+                let next = match page.last() {
+                    Some(last) if page.len() as i64 == PAGE_SIZE => Some(Some(id(last).to_string())),
+                    _ => None,
+                };
+                Ok(Some((stream::iter(page.into_iter().map(Ok)), next)))
+            }
+        })
+        .try_flatten()
+    }
+
+    async fn text(&self, path: &str, query: &[(&str, Option<String>)]) -> Result<String, ClientError> {
+        Ok(self.send(Method::GET, path, query, NO_BODY).await?.text().await?)
+    }
+
+    /// For endpoints answering 204, or with a page meant for browsers.
+    async fn no_content<B: Serialize + ?Sized>(&self, method: Method, path: &str, body: Option<&B>) -> Result<(), ClientError> {
+        self.send(method, path, &[], body).await?;
+        Ok(())
+    }
+
+    // users
+
+    pub async fn sign_up(&self, input: &CreateUserInput) -> Result<CreateUserOutput, ClientError> {
+        self.json(Method::POST, "/user/signup", &[], Some(input)).await
+    }
+
+    pub async fn sign_in(&self, input: &CreateUserInput) -> Result<SigninOutput, ClientError> {
+        self.json(Method::POST, "/user/signin", &[], Some(input)).await
+    }
+
+    /// The key is only in this response, see [`Client::with_api_key`].
+    pub async fn create_api_key(&self, input: &CreateApiKeyInput) -> Result<ApiKeyOutput, ClientError> {
+        self.json(Method::POST, "/user/api-key", &[], Some(input)).await
+    }
+
+    pub async fn get_api_keys(&self) -> Result<Vec<ApiKeyOutput>, ClientError> {
+        self.get("/user/api-keys").await
+    }
+
+    pub async fn delete_api_key(&self, id: &str) -> Result<(), ClientError> {
+        self.no_content(Method::DELETE, &format!("/user/api-key/{}", id), NO_BODY).await
+    }
+
+    pub async fn invite_team_member(&self, input: &InviteTeamMemberInput) -> Result<TeamMemberOutput, ClientError> {
+        self.json(Method::POST, "/user/team-member", &[], Some(input)).await
+    }
+
+    pub async fn get_team_members(&self) -> Result<Vec<TeamMemberOutput>, ClientError> {
+        self.get("/user/team-members").await
+    }
+
+    pub async fn get_team_invitations(&self) -> Result<Vec<TeamMemberOutput>, ClientError> {
+        self.get("/user/team-invitations").await
+    }
+
+    pub async fn accept_team_invitation(&self, id: &str) -> Result<TeamMemberOutput, ClientError> {
+        self.json(Method::POST, &format!("/user/team-invitation/{}/accept", id), &[], NO_BODY).await
+    }
+
+    pub async fn delete_team_member(&self, id: &str) -> Result<(), ClientError> {
+        self.no_content(Method::DELETE, &format!("/user/team-member/{}", id), NO_BODY).await
+    }
+
+    // websites
+
+    pub async fn get_website(&self, id: &str) -> Result<GetWebsiteOutput, ClientError> {
+        self.get(&format!("/status/{}", id)).await
+    }
+
+    pub async fn create_website(&self, input: &CreateWebsiteInput) -> Result<CreateWebsiteOutput, ClientError> {
+        self.json(Method::POST, "/website", &[], Some(input)).await
+    }
+
+    pub async fn update_website(&self, website_id: &str, input: &UpdateWebsiteInput) -> Result<GetWebsiteOutput, ClientError> {
+        self.json(Method::PUT, &format!("/website/{}", website_id), &[], Some(input)).await
+    }
+
+    /// Also deletes the website's ticks and incidents.
+    pub async fn delete_website(&self, website_id: &str) -> Result<(), ClientError> {
+        self.no_content(Method::DELETE, &format!("/website/{}", website_id), NO_BODY).await
+    }
+
+    /// `tag` is comma separated `key:value` pairs, all of which must match.
+    pub async fn get_websites(&self, group_id: Option<&str>, tag: Option<&str>) -> Result<Vec<WebsiteSummaryOutput>, ClientError> {
+        let query = [("group_id", group_id.map(str::to_string)), ("tag", tag.map(str::to_string))];
+        self.json(Method::GET, "/websites", &query, NO_BODY).await
+    }
+
+    /// Like [`Client::get_websites`], fetching a page at a time as the stream is read.
+    pub fn websites<'a>(&'a self, group_id: Option<&str>, tag: Option<&str>) -> impl Stream<Item = Result<WebsiteSummaryOutput, ClientError>> + 'a {
+        let query = vec![("group_id", group_id.map(str::to_string)), ("tag", tag.map(str::to_string))];
+        self.paginate("/websites".to_string(), query, |w: &WebsiteSummaryOutput| &w.id)
+    }
+
+    pub async fn set_escalation_policy(&self, website_id: &str, input: &SetEscalationPolicyInput) -> Result<(), ClientError> {
+        self.no_content(Method::PUT, &format!("/website/{}/escalation-policy", website_id), Some(input)).await
+    }
+
+    /// Uptime over the last `days`, 30 when `None`.
+    pub async fn get_website_uptime(&self, website_id: &str, days: Option<i64>) -> Result<UptimeOutput, ClientError> {
+        let query = [("days", days.map(|d| d.to_string()))];
+        self.json(Method::GET, &format!("/website/{}/uptime", website_id), &query, NO_BODY).await
+    }
+
+    pub async fn get_uptime_report(&self, days: Option<i64>, group_id: Option<&str>, tag: Option<&str>) -> Result<Vec<UptimeOutput>, ClientError> {
+        let query = [
+            ("days", days.map(|d| d.to_string())),
+            ("group_id", group_id.map(str::to_string)),
+            ("tag", tag.map(str::to_string)),
+        ];
+        self.json(Method::GET, "/websites/uptime", &query, NO_BODY).await
+    }
+
+    pub async fn pause_website(&self, website_id: &str) -> Result<GetWebsiteOutput, ClientError> {
+        self.json(Method::POST, &format!("/website/{}/pause", website_id), &[], NO_BODY).await
+    }
+
+    pub async fn resume_website(&self, website_id: &str) -> Result<GetWebsiteOutput, ClientError> {
+        self.json(Method::POST, &format!("/website/{}/resume", website_id), &[], NO_BODY).await
+    }
+
+    /// Returns the websites whose state changed.
+    pub async fn pause_websites(&self, input: &BulkWebsiteInput) -> Result<Vec<GetWebsiteOutput>, ClientError> {
+        self.json(Method::POST, "/websites/pause", &[], Some(input)).await
+    }
+
+    pub async fn resume_websites(&self, input: &BulkWebsiteInput) -> Result<Vec<GetWebsiteOutput>, ClientError> {
+        self.json(Method::POST, "/websites/resume", &[], Some(input)).await
+    }
+
+    pub async fn get_dependencies(&self, website_id: &str) -> Result<DependenciesOutput, ClientError> {
+        self.get(&format!("/website/{}/dependencies", website_id)).await
+    }
+
+    pub async fn set_dependencies(&self, website_id: &str, input: &SetDependenciesInput) -> Result<DependenciesOutput, ClientError> {
+        self.json(Method::PUT, &format!("/website/{}/dependencies", website_id), &[], Some(input)).await
+    }
+
+    pub async fn set_website_group(&self, website_id: &str, input: &SetWebsiteGroupInput) -> Result<GetWebsiteOutput, ClientError> {
+        self.json(Method::PUT, &format!("/website/{}/group", website_id), &[], Some(input)).await
+    }
+
+    pub async fn set_website_tags(&self, website_id: &str, input: &SetTagsInput) -> Result<TagsOutput, ClientError> {
+        self.json(Method::PUT, &format!("/website/{}/tags", website_id), &[], Some(input)).await
+    }
+
+    // groups
+
+    pub async fn create_website_group(&self, input: &WebsiteGroupInput) -> Result<WebsiteGroupOutput, ClientError> {
+        self.json(Method::POST, "/website-group", &[], Some(input)).await
+    }
+
+    pub async fn get_website_groups(&self) -> Result<Vec<WebsiteGroupOutput>, ClientError> {
+        self.get("/website-groups").await
+    }
+
+    pub async fn get_website_group(&self, id: &str) -> Result<WebsiteGroupOutput, ClientError> {
+        self.get(&format!("/website-group/{}", id)).await
+    }
+
+    pub async fn update_website_group(&self, id: &str, input: &WebsiteGroupInput) -> Result<WebsiteGroupOutput, ClientError> {
+        self.json(Method::PUT, &format!("/website-group/{}", id), &[], Some(input)).await
+    }
+
+    pub async fn delete_website_group(&self, id: &str) -> Result<(), ClientError> {
+        self.no_content(Method::DELETE, &format!("/website-group/{}", id), NO_BODY).await
+    }
+
+    // incidents
+
+    /// The most recent incidents, newest first; [`Client::incidents`] walks all of them.
+    pub async fn get_incidents(&self) -> Result<Vec<IncidentOutput>, ClientError> {
+        self.get("/incidents").await
+    }
+
+    /// Every incident, newest first, fetching a page at a time as the stream is read.
+    pub fn incidents(&self) -> impl Stream<Item = Result<IncidentOutput, ClientError>> + '_ {
+        self.paginate("/incidents".to_string(), Vec::new(), |i: &IncidentOutput| &i.id)
+    }
+
+    pub async fn acknowledge_incident(&self, id: &str) -> Result<IncidentOutput, ClientError> {
+        self.json(Method::POST, &format!("/incident/{}/acknowledge", id), &[], NO_BODY).await
+    }
+
+    pub async fn resolve_incident(&self, id: &str) -> Result<IncidentOutput, ClientError> {
+        self.json(Method::POST, &format!("/incident/{}/resolve", id), &[], NO_BODY).await
+    }
+
+    pub async fn get_incident_timeline(&self, id: &str) -> Result<Vec<IncidentEventOutput>, ClientError> {
+        self.get(&format!("/incident/{}/timeline", id)).await
+    }
+
+    pub async fn get_incident_deliveries(&self, id: &str) -> Result<Vec<NotificationDeliveryOutput>, ClientError> {
+        self.get(&format!("/incident/{}/deliveries", id)).await
+    }
+
+    /// Redeems the token of a one-click acknowledge/resolve link; needs no sign in.
+    pub async fn apply_incident_action(&self, token: &str) -> Result<(), ClientError> {
+        self.no_content(Method::POST, &format!("/incident/action/{}", token), NO_BODY).await
+    }
+
+    // notification channels
+
+    pub async fn create_notification_channel(&self, input: &CreateNotificationChannelInput) -> Result<NotificationChannelOutput, ClientError> {
+        self.json(Method::POST, "/notification-channel", &[], Some(input)).await
+    }
+
+    pub async fn get_notification_channels(&self) -> Result<Vec<NotificationChannelOutput>, ClientError> {
+        self.get("/notification-channels").await
+    }
+
+    pub async fn delete_notification_channel(&self, id: &str) -> Result<(), ClientError> {
+        self.no_content(Method::DELETE, &format!("/notification-channel/{}", id), NO_BODY).await
+    }
+
+    /// The most recent attempts, newest first; [`Client::channel_deliveries`] walks all of them.
+    pub async fn get_channel_deliveries(&self, id: &str) -> Result<Vec<NotificationDeliveryOutput>, ClientError> {
+        self.get(&format!("/notification-channel/{}/deliveries", id)).await
+    }
+
+    /// Every attempt through the channel, newest first, fetching a page at a time as the stream is read.
+    pub fn channel_deliveries<'a>(&'a self, id: &str) -> impl Stream<Item = Result<NotificationDeliveryOutput, ClientError>> + 'a {
+        self.paginate(format!("/notification-channel/{}/deliveries", id), Vec::new(), |d: &NotificationDeliveryOutput| &d.id)
+    }
+
+    // escalation policies
+
+    pub async fn create_escalation_policy(&self, input: &CreateEscalationPolicyInput) -> Result<EscalationPolicyOutput, ClientError> {
+        self.json(Method::POST, "/escalation-policy", &[], Some(input)).await
+    }
+
+    pub async fn get_escalation_policies(&self) -> Result<Vec<EscalationPolicyOutput>, ClientError> {
+        self.get("/escalation-policies").await
+    }
+
+    pub async fn delete_escalation_policy(&self, id: &str) -> Result<(), ClientError> {
+        self.no_content(Method::DELETE, &format!("/escalation-policy/{}", id), NO_BODY).await
+    }
+
+    // on-call
+
+    pub async fn create_on_call_schedule(&self, input: &CreateOnCallScheduleInput) -> Result<OnCallScheduleOutput, ClientError> {
+        self.json(Method::POST, "/on-call-schedule", &[], Some(input)).await
+    }
+
+    pub async fn get_on_call_schedules(&self) -> Result<Vec<OnCallScheduleOutput>, ClientError> {
+        self.get("/on-call-schedules").await
+    }
+
+    pub async fn get_current_on_call(&self, schedule_id: &str) -> Result<OnCallOutput, ClientError> {
+        self.get(&format!("/on-call-schedule/{}/on-call", schedule_id)).await
+    }
+
+    pub async fn create_on_call_override(&self, schedule_id: &str, input: &CreateOnCallOverrideInput) -> Result<OnCallOverrideOutput, ClientError> {
+        self.json(Method::POST, &format!("/on-call-schedule/{}/override", schedule_id), &[], Some(input)).await
+    }
+
+    // maintenance windows
+
+    pub async fn create_maintenance_window(&self, input: &MaintenanceWindowInput) -> Result<MaintenanceWindowOutput, ClientError> {
+        self.json(Method::POST, "/maintenance-window", &[], Some(input)).await
+    }
+
+    pub async fn get_maintenance_windows(&self, website_id: Option<&str>, group_id: Option<&str>) -> Result<Vec<MaintenanceWindowOutput>, ClientError> {
+        let query = [("website_id", website_id.map(str::to_string)), ("group_id", group_id.map(str::to_string))];
+        self.json(Method::GET, "/maintenance-windows", &query, NO_BODY).await
+    }
+
+    pub async fn update_maintenance_window(&self, id: &str, input: &MaintenanceWindowInput) -> Result<MaintenanceWindowOutput, ClientError> {
+        self.json(Method::PUT, &format!("/maintenance-window/{}", id), &[], Some(input)).await
+    }
+
+    pub async fn delete_maintenance_window(&self, id: &str) -> Result<(), ClientError> {
+        self.no_content(Method::DELETE, &format!("/maintenance-window/{}", id), NO_BODY).await
+    }
+
+    // status pages
+
+    pub async fn create_status_page(&self, input: &StatusPageInput) -> Result<StatusPageOutput, ClientError> {
+        self.json(Method::POST, "/status-page", &[], Some(input)).await
+    }
+
+    pub async fn update_status_page(&self, id: &str, input: &StatusPageInput) -> Result<StatusPageOutput, ClientError> {
+        self.json(Method::PUT, &format!("/status-page/{}", id), &[], Some(input)).await
+    }
+
+    pub async fn get_status_pages(&self) -> Result<Vec<StatusPageOutput>, ClientError> {
+        self.get("/status-pages").await
+    }
+
+    pub async fn delete_status_page(&self, id: &str) -> Result<(), ClientError> {
+        self.no_content(Method::DELETE, &format!("/status-page/{}", id), NO_BODY).await
+    }
+
+    /// What the public sees; needs no sign in.
+    pub async fn get_public_status_page(&self, slug: &str) -> Result<PublicStatusPageOutput, ClientError> {
+        self.get(&format!("/status-page/{}", slug)).await
+    }
+
+    // status page subscriptions
+
+    pub async fn subscribe_to_status_page(&self, slug: &str, input: &SubscribeInput) -> Result<SubscriptionOutput, ClientError> {
+        self.json(Method::POST, &format!("/status-page/{}/subscribe", slug), &[], Some(input)).await
+    }
+
+    pub async fn confirm_subscription(&self, token: &str) -> Result<(), ClientError> {
+        self.no_content(Method::POST, &format!("/subscription/{}/confirm", token), NO_BODY).await
+    }
+
+    pub async fn unsubscribe(&self, token: &str) -> Result<(), ClientError> {
+        self.no_content(Method::POST, &format!("/subscription/{}/unsubscribe", token), NO_BODY).await
+    }
+
+    // feeds
+
+    pub async fn create_feed_token(&self) -> Result<FeedTokenOutput, ClientError> {
+        self.json(Method::POST, "/user/feed-token", &[], NO_BODY).await
+    }
+
+    /// Atom xml.
+    pub async fn status_page_incident_feed(&self, slug: &str) -> Result<String, ClientError> {
+        self.text(&format!("/status-page/{}/incidents.atom", slug), &[]).await
+    }
+
+    /// iCalendar text.
+    pub async fn status_page_maintenance_calendar(&self, slug: &str) -> Result<String, ClientError> {
+        self.text(&format!("/status-page/{}/maintenance.ics", slug), &[]).await
+    }
+
+    /// Atom xml.
+    pub async fn team_incident_feed(&self, feed_token: &str) -> Result<String, ClientError> {
+        self.text(&format!("/feed/{}/incidents.atom", feed_token), &[]).await
+    }
+
+    /// iCalendar text.
+    pub async fn team_maintenance_calendar(&self, feed_token: &str) -> Result<String, ClientError> {
+        self.text(&format!("/feed/{}/maintenance.ics", feed_token), &[]).await
+    }
+
+    // badges
+
+    pub async fn get_badges(&self, website_id: &str) -> Result<BadgeOutput, ClientError> {
+        self.get(&format!("/website/{}/badges", website_id)).await
+    }
+
+    pub async fn rotate_badge_token(&self, website_id: &str) -> Result<BadgeOutput, ClientError> {
+        self.json(Method::POST, &format!("/website/{}/badge-token", website_id), &[], NO_BODY).await
+    }
+
+    /// Svg markup.
+    pub async fn status_badge(&self, badge_token: &str) -> Result<String, ClientError> {
+        self.text(&format!("/badge/{}/status.svg", badge_token), &[]).await
+    }
+
+    /// Svg markup; `period` like `30d` or `24h`.
+    pub async fn uptime_badge(&self, badge_token: &str, period: Option<&str>) -> Result<String, ClientError> {
+        self.text(&format!("/badge/{}/uptime.svg", badge_token), &[("period", period.map(str::to_string))]).await
+    }
+
+    /// Svg markup; `period` like `30d` or `24h`.
+    pub async fn response_time_badge(&self, badge_token: &str, period: Option<&str>) -> Result<String, ClientError> {
+        self.text(&format!("/badge/{}/response-time.svg", badge_token), &[("period", period.map(str::to_string))]).await
+    }
+
+    // monitors as code
+
+    /// What applying the document would change, without changing anything.
+    pub async fn plan_config(&self, input: &MonitorConfigInput) -> Result<ConfigPlanOutput, ClientError> {
+        self.json(Method::POST, "/config/plan", &[], Some(input)).await
+    }
+
+    /// Converges the account on the document; returns what changed.
+    pub async fn apply_config(&self, input: &MonitorConfigInput) -> Result<ConfigPlanOutput, ClientError> {
+        self.json(Method::POST, "/config/apply", &[], Some(input)).await
+    }
+
+    // import and export
+
+    /// Every website, channel and status page as a document for [`Client::import_config`].
+    pub async fn export_config(&self) -> Result<MonitorConfigInput, ClientError> {
+        self.get("/export").await
+    }
+
+    /// Creates and updates resources to match an export; nothing is deleted.
+    pub async fn import_config(&self, input: &MonitorConfigInput, dry_run: bool) -> Result<ImportOutput, ClientError> {
+        self.json(Method::POST, "/import", &dry_run_query(dry_run), Some(input)).await
+    }
+
+    /// `export` is an UptimeRobot `getMonitors` response, or an array of them.
+    pub async fn import_uptimerobot(&self, export: &serde_json::Value, dry_run: bool) -> Result<ImportOutput, ClientError> {
+        self.json(Method::POST, "/import/uptimerobot", &dry_run_query(dry_run), Some(export)).await
+    }
+
+    /// `export` is a Better Stack `GET /api/v2/monitors` response, or an array of them.
+    pub async fn import_betterstack(&self, export: &serde_json::Value, dry_run: bool) -> Result<ImportOutput, ClientError> {
+        self.json(Method::POST, "/import/betterstack", &dry_run_query(dry_run), Some(export)).await
+    }
+
+    /// `csv` has a url per row, optionally with a header naming the `url` and `name` columns.
+    pub async fn import_csv(&self, csv: &str, dry_run: bool) -> Result<ImportOutput, ClientError> {
+        let response = self
+            .send_with(Method::POST, "/import/csv", &dry_run_query(dry_run), |builder| builder.header(CONTENT_TYPE, "text/plain").body(csv.to_string()))
+            .await?;
+        Ok(response.json().await?)
+    }
+
+    // slack
+
+    /// A code to redeem with `/uptime link <code>` in Slack.
+    pub async fn create_slack_link_code(&self) -> Result<SlackLinkCodeOutput, ClientError> {
+        self.json(Method::POST, "/user/slack-link-code", &[], NO_BODY).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    /// Serves one canned response per request, in order, and counts the requests. The last
+    /// response repeats once the list runs out.
+    async fn serve(responses: &[&'static str]) -> (Client, Arc<Mutex<usize>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let responses = responses.to_vec();
+        let requests = Arc::new(Mutex::new(0));
+        let counter = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = [0; 4096];
+                let _ = socket.read(&mut request).await.unwrap();
+                let served = {
+                    let mut count = counter.lock().unwrap();
+                    *count += 1;
+                    *count
+                };
+                let head = responses[served.min(responses.len()) - 1];
+                let response = format!("{}\r\ncontent-length: 2\r\nconnection: close\r\n\r\n[]", head);
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        let retry = RetryPolicy { max_retries: 2, base_delay: Duration::from_millis(10), max_delay: Duration::from_secs(5) };
+        (Client::new(&base_url).with_retry(retry), requests)
+    }
+
+    #[tokio::test]
+    async fn retries_until_success() {
+        let (client, requests) = serve(&["HTTP/1.1 503 Service Unavailable", "HTTP/1.1 429 Too Many Requests", "HTTP/1.1 200 OK"]).await;
+
+        assert!(client.get_status_pages().await.unwrap().is_empty());
+        assert_eq!(*requests.lock().unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let (client, requests) = serve(&["HTTP/1.1 503 Service Unavailable"]).await;
+
+        assert!(client.get_status_pages().await.is_err());
+        assert_eq!(*requests.lock().unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn waits_as_long_as_retry_after_asks() {
+        let (client, requests) = serve(&["HTTP/1.1 429 Too Many Requests\r\nretry-after: 1", "HTTP/1.1 200 OK"]).await;
+        let started = Instant::now();
+
+        client.get_status_pages().await.unwrap();
+        assert!(started.elapsed() >= Duration::from_secs(1));
+        assert_eq!(*requests.lock().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_other_errors_or_posts() {
+        let (client, requests) = serve(&["HTTP/1.1 500 Internal Server Error"]).await;
+        assert!(client.get_status_pages().await.is_err());
+        assert_eq!(*requests.lock().unwrap(), 1);
+
+        let (client, requests) = serve(&["HTTP/1.1 503 Service Unavailable"]).await;
+        let input = CreateUserInput { username: "alice".to_string(), password: "hunter2".to_string() };
+        assert!(client.sign_in(&input).await.is_err());
+        assert_eq!(*requests.lock().unwrap(), 1);
+    }
+}
@@ -0,0 +1,64 @@
+use std::fmt;
+
+use api_types::request_output::Problem;
+use reqwest::StatusCode;
+
+#[derive(Debug)]
+pub enum ClientError {
+    /// The api answered with an error, described by its problem details.
+    Api(Problem),
+    /// The request didn't get an answer, or the answer wasn't what the endpoint returns.
+    Http(reqwest::Error),
+    /// A live event stream sent something that isn't an event.
+    Stream(String),
+}
+
+impl ClientError {
+    /// Status of an api error, `None` for transport failures.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            ClientError::Api(problem) => StatusCode::from_u16(problem.status).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(StatusCode::NOT_FOUND)
+    }
+
+    /// The body of an error response. Anything in front of the api, like a proxy,
+    /// may answer without problem details; those get one made from the status.
+    pub(crate) async fn from_response(response: reqwest::Response) -> Self {
+        let status = response.status();
+        match response.json::<Problem>().await {
+            Ok(problem) => ClientError::Api(problem),
+            Err(_) => ClientError::Api(Problem {
+                kind: "about:blank".to_string(),
+                title: status.canonical_reason().unwrap_or("Error").to_string(),
+                status: status.as_u16(),
+                detail: None,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Api(problem) => match &problem.detail {
+                Some(detail) => write!(f, "{} ({}): {}", problem.title, problem.status, detail),
+                None => write!(f, "{} ({})", problem.title, problem.status),
+            },
+            ClientError::Http(e) => write!(f, "request failed: {}", e),
+            ClientError::Stream(e) => write!(f, "live stream: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(e: reqwest::Error) -> Self {
+        ClientError::Http(e)
+    }
+}
@@ -0,0 +1,22 @@
+//! Typed async client for the betteruptime api.
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), betteruptime_client::ClientError> {
+//! use betteruptime_client::{Client, types::request_input::CreateWebsiteInput};
+//!
+//! let client = Client::new("https://api.example.com").login("alice", "hunter2").await?;
+//! let website = client.create_website(&CreateWebsiteInput { url: "https://example.com".into() }).await?;
+//! client.pause_website(&website.id).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod client;
+mod error;
+mod live;
+mod retry;
+
+pub use api_types as types;
+pub use client::Client;
+pub use error::ClientError;
+pub use retry::RetryPolicy;
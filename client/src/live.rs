@@ -0,0 +1,79 @@
+use api_types::request_output::LiveEventOutput;
+use futures::{stream, Stream, StreamExt};
+use reqwest::Method;
+
+use crate::{client::NO_BODY, Client, ClientError};
+
+impl Client {
+    /// Follows `/live`: tick results (`tick`) and status transitions (`status`) for the caller's
+    /// websites as they happen. A `resync` event means some were missed and anything cached
+    /// should be fetched again. Ends when the api closes the connection.
+    pub async fn live_events(&self) -> Result<impl Stream<Item = Result<LiveEventOutput, ClientError>> + Send + 'static, ClientError> {
+        let response = self.send(Method::GET, "/live", &[], NO_BODY).await?;
+        let parser = EventParser { bytes: Box::pin(response.bytes_stream()), buffer: Vec::new(), event: None, data: Vec::new() };
+        Ok(stream::unfold(parser, |mut parser| async move { parser.next_event().await.map(|event| (event, parser)) }))
+    }
+}
+
+/// Just enough of the server-sent events format for what the api sends.
+struct EventParser<S> {
+    bytes: S,
+    buffer: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl<S, B> EventParser<S>
+where
+    S: Stream<Item = reqwest::Result<B>> + Unpin,
+    B: AsRef<[u8]>,
+{
+    async fn next_event(&mut self) -> Option<Result<LiveEventOutput, ClientError>> {
+        loop {
+            while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = self.buffer.drain(..=end).collect();
+                let line = match std::str::from_utf8(&line) {
+                    Ok(line) => line.trim_end_matches(['\n', '\r']).to_string(),
+                    Err(e) => return Some(Err(ClientError::Stream(e.to_string()))),
+                };
+                if let Some(event) = self.line(&line) {
+                    return Some(event);
+                }
+            }
+            match self.bytes.next().await {
+                Some(Ok(chunk)) => self.buffer.extend_from_slice(chunk.as_ref()),
+                Some(Err(e)) => return Some(Err(e.into())),
+                None => return None,
+            }
+        }
+    }
+
+    /// A blank line ends an event; comments (`:`), keep-alives among them, are skipped.
+    fn line(&mut self, line: &str) -> Option<Result<LiveEventOutput, ClientError>> {
+        if line.is_empty() {
+            if self.data.is_empty() {
+                self.event = None;
+                return None;
+            }
+            let data = self.data.join("\n");
+            self.data.clear();
+            let event = self.event.take().unwrap_or_else(|| "message".to_string());
+            return Some(
+                serde_json::from_str(&data)
+                    .map(|data| LiveEventOutput { event, data })
+                    .map_err(|e| ClientError::Stream(format!("{} in {:?}", e, data))),
+            );
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            _ => {}
+        }
+        None
+    }
+}
@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+use reqwest::{Method, Response, StatusCode};
+
+/// How requests answered with 429 or 503 are retried. Only methods safe to repeat are
+/// retried: a POST may have been acted on before the error, and isn't sent twice.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; 0 turns retrying off.
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before the given (1-based) retry: base, 2x base, 4x base, ... capped at `max_delay`.
+    pub fn delay_before(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    /// `None` when the response shouldn't be retried, otherwise how long to wait. A
+    /// `Retry-After` in seconds wins over the backoff, still capped at `max_delay`.
+    pub(crate) fn retry_after(&self, method: &Method, response: &Response, retry: u32) -> Option<Duration> {
+        let retryable = matches!(response.status(), StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE);
+        if !retryable || !method.is_idempotent() || retry > self.max_retries {
+            return None;
+        }
+        let requested = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        Some(requested.unwrap_or_else(|| self.delay_before(retry)).min(self.max_delay))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy { max_retries: 2, base_delay: Duration::from_millis(100), max_delay: Duration::from_secs(5) }
+    }
+
+    fn response(status: u16, retry_after: Option<&str>) -> Response {
+        let mut builder = http::Response::builder().status(status);
+        if let Some(retry_after) = retry_after {
+            builder = builder.header("retry-after", retry_after);
+        }
+        Response::from(builder.body("").unwrap())
+    }
+
+    #[test]
+    fn backs_off_exponentially_up_to_the_cap() {
+        assert_eq!(policy().delay_before(1), Duration::from_millis(100));
+        assert_eq!(policy().delay_before(2), Duration::from_millis(200));
+        assert_eq!(policy().delay_before(3), Duration::from_millis(400));
+        assert_eq!(policy().delay_before(40), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn honors_retry_after_within_the_cap() {
+        assert_eq!(policy().retry_after(&Method::GET, &response(429, Some("2")), 1), Some(Duration::from_secs(2)));
+        assert_eq!(policy().retry_after(&Method::GET, &response(503, Some(" 3 ")), 1), Some(Duration::from_secs(3)));
+        assert_eq!(policy().retry_after(&Method::GET, &response(429, Some("3600")), 1), Some(Duration::from_secs(5)));
+        // dates aren't parsed, the backoff applies
+        assert_eq!(policy().retry_after(&Method::GET, &response(429, Some("Wed, 21 Oct 2026 07:28:00 GMT")), 2), Some(Duration::from_millis(200)));
+    }
+
+    #[test]
+    fn stops_after_max_retries() {
+        assert!(policy().retry_after(&Method::GET, &response(503, None), 2).is_some());
+        assert_eq!(policy().retry_after(&Method::GET, &response(503, None), 3), None);
+        let off = RetryPolicy { max_retries: 0, ..policy() };
+        assert_eq!(off.retry_after(&Method::GET, &response(503, None), 1), None);
+    }
+
+    #[test]
+    fn only_retries_rate_limits_and_unavailability_of_idempotent_requests() {
+        for status in [400, 401, 404, 409, 500, 502, 504] {
+            assert_eq!(policy().retry_after(&Method::GET, &response(status, Some("1")), 1), None, "{}", status);
+        }
+        assert_eq!(policy().retry_after(&Method::POST, &response(429, None), 1), None);
+        assert!(policy().retry_after(&Method::PUT, &response(429, None), 1).is_some());
+        assert!(policy().retry_after(&Method::DELETE, &response(503, None), 1).is_some());
+    }
+}
@@ -0,0 +1,33 @@
+-- Your SQL goes here
+
+-- CreateTable
+CREATE TABLE "incident" (
+    "id" TEXT NOT NULL,
+    "website_id" TEXT NOT NULL,
+    "status" TEXT NOT NULL,
+    "opened_at" TIMESTAMP(3) NOT NULL,
+    "resolved_at" TIMESTAMP(3),
+
+    CONSTRAINT "incident_pkey" PRIMARY KEY ("id")
+);
+
+-- CreateTable
+CREATE TABLE "notification_channel" (
+    "id" TEXT NOT NULL,
+    "user_id" TEXT NOT NULL,
+    "kind" TEXT NOT NULL,
+    "target" TEXT NOT NULL,
+    "secret" TEXT,
+    "time_added" TIMESTAMP(3) NOT NULL,
+
+    CONSTRAINT "notification_channel_pkey" PRIMARY KEY ("id")
+);
+
+-- AddForeignKey
+ALTER TABLE "incident" ADD CONSTRAINT "incident_website_id_fkey" FOREIGN KEY ("website_id") REFERENCES "website"("id") ON DELETE RESTRICT ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "notification_channel" ADD CONSTRAINT "notification_channel_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "user"("id") ON DELETE RESTRICT ON UPDATE CASCADE;
+
+-- Only one incident per website may be open at a time
+CREATE UNIQUE INDEX "incident_open_website_id_key" ON "incident"("website_id") WHERE "status" = 'open';
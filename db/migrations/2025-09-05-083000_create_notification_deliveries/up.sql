@@ -0,0 +1,24 @@
+-- Your SQL goes here
+
+-- CreateTable
+CREATE TABLE "notification_deliveries" (
+    "id" TEXT NOT NULL,
+    "incident_id" TEXT NOT NULL,
+    "channel_id" TEXT NOT NULL,
+    "attempt" INTEGER NOT NULL,
+    "status_code" INTEGER,
+    "latency_ms" INTEGER NOT NULL,
+    "error" TEXT,
+    "attempted_at" TIMESTAMP(3) NOT NULL,
+
+    CONSTRAINT "notification_deliveries_pkey" PRIMARY KEY ("id")
+);
+
+-- AddForeignKey
+ALTER TABLE "notification_deliveries" ADD CONSTRAINT "notification_deliveries_incident_id_fkey" FOREIGN KEY ("incident_id") REFERENCES "incident"("id") ON DELETE RESTRICT ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "notification_deliveries" ADD CONSTRAINT "notification_deliveries_channel_id_fkey" FOREIGN KEY ("channel_id") REFERENCES "notification_channel"("id") ON DELETE CASCADE ON UPDATE CASCADE;
+
+CREATE INDEX "notification_deliveries_incident_id_idx" ON "notification_deliveries"("incident_id");
+CREATE INDEX "notification_deliveries_channel_id_idx" ON "notification_deliveries"("channel_id");
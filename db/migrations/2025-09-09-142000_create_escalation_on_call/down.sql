@@ -0,0 +1,14 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX "incident_open_website_id_key";
+CREATE UNIQUE INDEX "incident_open_website_id_key" ON "incident"("website_id") WHERE "status" = 'open';
+
+ALTER TABLE "incident" DROP COLUMN "escalation_level";
+ALTER TABLE "incident" DROP COLUMN "acknowledged_by";
+ALTER TABLE "incident" DROP COLUMN "acknowledged_at";
+
+ALTER TABLE "website" DROP COLUMN "escalation_policy_id";
+
+DROP TABLE "escalation_level";
+DROP TABLE "escalation_policy";
+DROP TABLE "on_call_override";
+DROP TABLE "on_call_schedule";
@@ -0,0 +1,83 @@
+-- Your SQL goes here
+
+-- CreateTable
+CREATE TABLE "on_call_schedule" (
+    "id" TEXT NOT NULL,
+    "user_id" TEXT NOT NULL,
+    "name" TEXT NOT NULL,
+    "rotation" TEXT NOT NULL,
+    "rotation_start" TIMESTAMP(3) NOT NULL,
+    "participants" TEXT[] NOT NULL,
+
+    CONSTRAINT "on_call_schedule_pkey" PRIMARY KEY ("id")
+);
+
+-- CreateTable
+CREATE TABLE "on_call_override" (
+    "id" TEXT NOT NULL,
+    "schedule_id" TEXT NOT NULL,
+    "user_id" TEXT NOT NULL,
+    "starts_at" TIMESTAMP(3) NOT NULL,
+    "ends_at" TIMESTAMP(3) NOT NULL,
+
+    CONSTRAINT "on_call_override_pkey" PRIMARY KEY ("id")
+);
+
+-- CreateTable
+CREATE TABLE "escalation_policy" (
+    "id" TEXT NOT NULL,
+    "user_id" TEXT NOT NULL,
+    "name" TEXT NOT NULL,
+
+    CONSTRAINT "escalation_policy_pkey" PRIMARY KEY ("id")
+);
+
+-- CreateTable
+CREATE TABLE "escalation_level" (
+    "id" TEXT NOT NULL,
+    "policy_id" TEXT NOT NULL,
+    "position" INTEGER NOT NULL,
+    "delay_minutes" INTEGER NOT NULL,
+    "channel_id" TEXT,
+    "schedule_id" TEXT,
+
+    CONSTRAINT "escalation_level_pkey" PRIMARY KEY ("id"),
+    CONSTRAINT "escalation_level_target_check" CHECK (("channel_id" IS NULL) <> ("schedule_id" IS NULL))
+);
+
+ALTER TABLE "website" ADD COLUMN "escalation_policy_id" TEXT;
+
+ALTER TABLE "incident" ADD COLUMN "acknowledged_at" TIMESTAMP(3);
+ALTER TABLE "incident" ADD COLUMN "acknowledged_by" TEXT;
+ALTER TABLE "incident" ADD COLUMN "escalation_level" INTEGER NOT NULL DEFAULT 0;
+
+-- Acknowledged incidents are still unresolved
+DROP INDEX "incident_open_website_id_key";
+CREATE UNIQUE INDEX "incident_open_website_id_key" ON "incident"("website_id") WHERE "resolved_at" IS NULL;
+
+-- AddForeignKey
+ALTER TABLE "on_call_schedule" ADD CONSTRAINT "on_call_schedule_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "user"("id") ON DELETE RESTRICT ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "on_call_override" ADD CONSTRAINT "on_call_override_schedule_id_fkey" FOREIGN KEY ("schedule_id") REFERENCES "on_call_schedule"("id") ON DELETE CASCADE ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "on_call_override" ADD CONSTRAINT "on_call_override_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "user"("id") ON DELETE RESTRICT ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "escalation_policy" ADD CONSTRAINT "escalation_policy_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "user"("id") ON DELETE RESTRICT ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "escalation_level" ADD CONSTRAINT "escalation_level_policy_id_fkey" FOREIGN KEY ("policy_id") REFERENCES "escalation_policy"("id") ON DELETE CASCADE ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "escalation_level" ADD CONSTRAINT "escalation_level_channel_id_fkey" FOREIGN KEY ("channel_id") REFERENCES "notification_channel"("id") ON DELETE RESTRICT ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "escalation_level" ADD CONSTRAINT "escalation_level_schedule_id_fkey" FOREIGN KEY ("schedule_id") REFERENCES "on_call_schedule"("id") ON DELETE RESTRICT ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "website" ADD CONSTRAINT "website_escalation_policy_id_fkey" FOREIGN KEY ("escalation_policy_id") REFERENCES "escalation_policy"("id") ON DELETE SET NULL ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "incident" ADD CONSTRAINT "incident_acknowledged_by_fkey" FOREIGN KEY ("acknowledged_by") REFERENCES "user"("id") ON DELETE SET NULL ON UPDATE CASCADE;
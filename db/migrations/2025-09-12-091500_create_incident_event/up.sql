@@ -0,0 +1,21 @@
+-- Your SQL goes here
+
+-- CreateTable
+CREATE TABLE "incident_event" (
+    "id" TEXT NOT NULL,
+    "incident_id" TEXT NOT NULL,
+    "kind" TEXT NOT NULL,
+    "user_id" TEXT,
+    "message" TEXT NOT NULL,
+    "created_at" TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    CONSTRAINT "incident_event_pkey" PRIMARY KEY ("id")
+);
+
+-- AddForeignKey
+ALTER TABLE "incident_event" ADD CONSTRAINT "incident_event_incident_id_fkey" FOREIGN KEY ("incident_id") REFERENCES "incident"("id") ON DELETE CASCADE ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "incident_event" ADD CONSTRAINT "incident_event_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "user"("id") ON DELETE SET NULL ON UPDATE CASCADE;
+
+CREATE INDEX "incident_event_incident_id_idx" ON "incident_event"("incident_id");
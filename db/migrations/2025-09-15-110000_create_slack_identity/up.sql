@@ -0,0 +1,14 @@
+-- Your SQL goes here
+
+-- CreateTable
+CREATE TABLE "slack_identity" (
+    "team_id" TEXT NOT NULL,
+    "slack_user_id" TEXT NOT NULL,
+    "user_id" TEXT NOT NULL,
+    "time_added" TIMESTAMP(3) NOT NULL,
+
+    CONSTRAINT "slack_identity_pkey" PRIMARY KEY ("team_id", "slack_user_id")
+);
+
+-- AddForeignKey
+ALTER TABLE "slack_identity" ADD CONSTRAINT "slack_identity_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "user"("id") ON DELETE CASCADE ON UPDATE CASCADE;
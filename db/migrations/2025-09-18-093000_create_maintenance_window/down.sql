@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE "website_ticks" DROP COLUMN "in_maintenance";
+
+DROP TABLE "maintenance_window";
@@ -0,0 +1,24 @@
+-- Your SQL goes here
+
+-- CreateTable
+CREATE TABLE "maintenance_window" (
+    "id" TEXT NOT NULL,
+    "website_id" TEXT NOT NULL,
+    "title" TEXT NOT NULL,
+    "starts_at" TIMESTAMP(3) NOT NULL,
+    "duration_minutes" INTEGER NOT NULL,
+    "recurrence" TEXT,
+    "until" TIMESTAMP(3),
+    "time_added" TIMESTAMP(3) NOT NULL,
+
+    CONSTRAINT "maintenance_window_pkey" PRIMARY KEY ("id")
+);
+
+-- CreateIndex
+CREATE INDEX "maintenance_window_website_id_idx" ON "maintenance_window"("website_id");
+
+-- AddForeignKey
+ALTER TABLE "maintenance_window" ADD CONSTRAINT "maintenance_window_website_id_fkey" FOREIGN KEY ("website_id") REFERENCES "website"("id") ON DELETE CASCADE ON UPDATE CASCADE;
+
+-- AlterTable
+ALTER TABLE "website_ticks" ADD COLUMN "in_maintenance" BOOLEAN NOT NULL DEFAULT false;
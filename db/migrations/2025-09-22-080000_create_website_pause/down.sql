@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE "website_pause";
+
+ALTER TABLE "website" DROP COLUMN "paused";
@@ -0,0 +1,20 @@
+-- Your SQL goes here
+
+-- AlterTable
+ALTER TABLE "website" ADD COLUMN "paused" BOOLEAN NOT NULL DEFAULT false;
+
+-- CreateTable
+CREATE TABLE "website_pause" (
+    "id" TEXT NOT NULL,
+    "website_id" TEXT NOT NULL,
+    "paused_at" TIMESTAMP(3) NOT NULL,
+    "resumed_at" TIMESTAMP(3),
+
+    CONSTRAINT "website_pause_pkey" PRIMARY KEY ("id")
+);
+
+-- CreateIndex
+CREATE INDEX "website_pause_website_id_paused_at_idx" ON "website_pause"("website_id", "paused_at");
+
+-- AddForeignKey
+ALTER TABLE "website_pause" ADD CONSTRAINT "website_pause_website_id_fkey" FOREIGN KEY ("website_id") REFERENCES "website"("id") ON DELETE CASCADE ON UPDATE CASCADE;
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE "website_ticks" DROP COLUMN "dependency_down";
+
+DROP TABLE "incident_dependent";
+
+DROP TABLE "website_dependency";
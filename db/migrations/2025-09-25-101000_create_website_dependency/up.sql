@@ -0,0 +1,34 @@
+-- Your SQL goes here
+
+-- CreateTable
+CREATE TABLE "website_dependency" (
+    "website_id" TEXT NOT NULL,
+    "depends_on_id" TEXT NOT NULL,
+
+    CONSTRAINT "website_dependency_pkey" PRIMARY KEY ("website_id", "depends_on_id"),
+    CONSTRAINT "website_dependency_not_self" CHECK ("website_id" <> "depends_on_id")
+);
+
+-- CreateTable
+CREATE TABLE "incident_dependent" (
+    "incident_id" TEXT NOT NULL,
+    "website_id" TEXT NOT NULL,
+    "time_added" TIMESTAMP(3) NOT NULL,
+
+    CONSTRAINT "incident_dependent_pkey" PRIMARY KEY ("incident_id", "website_id")
+);
+
+-- AlterTable
+ALTER TABLE "website_ticks" ADD COLUMN "dependency_down" BOOLEAN NOT NULL DEFAULT false;
+
+-- AddForeignKey
+ALTER TABLE "website_dependency" ADD CONSTRAINT "website_dependency_website_id_fkey" FOREIGN KEY ("website_id") REFERENCES "website"("id") ON DELETE CASCADE ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "website_dependency" ADD CONSTRAINT "website_dependency_depends_on_id_fkey" FOREIGN KEY ("depends_on_id") REFERENCES "website"("id") ON DELETE CASCADE ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "incident_dependent" ADD CONSTRAINT "incident_dependent_incident_id_fkey" FOREIGN KEY ("incident_id") REFERENCES "incident"("id") ON DELETE CASCADE ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "incident_dependent" ADD CONSTRAINT "incident_dependent_website_id_fkey" FOREIGN KEY ("website_id") REFERENCES "website"("id") ON DELETE CASCADE ON UPDATE CASCADE;
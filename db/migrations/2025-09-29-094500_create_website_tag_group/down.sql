@@ -0,0 +1,12 @@
+-- This file should undo anything in `up.sql`
+DELETE FROM "maintenance_window" WHERE "website_id" IS NULL;
+ALTER TABLE "maintenance_window" DROP CONSTRAINT "maintenance_window_target";
+ALTER TABLE "maintenance_window" DROP COLUMN "group_id";
+ALTER TABLE "maintenance_window" ALTER COLUMN "website_id" SET NOT NULL;
+ALTER TABLE "maintenance_window" DROP COLUMN "user_id";
+
+ALTER TABLE "website" DROP COLUMN "group_id";
+
+DROP TABLE "website_tag";
+
+DROP TABLE "website_group";
@@ -0,0 +1,53 @@
+-- Your SQL goes here
+
+-- CreateTable
+CREATE TABLE "website_group" (
+    "id" TEXT NOT NULL,
+    "user_id" TEXT NOT NULL,
+    "name" TEXT NOT NULL,
+    "parent_id" TEXT,
+    "time_added" TIMESTAMP(3) NOT NULL,
+
+    CONSTRAINT "website_group_pkey" PRIMARY KEY ("id")
+);
+
+-- CreateTable
+CREATE TABLE "website_tag" (
+    "website_id" TEXT NOT NULL,
+    "key" TEXT NOT NULL,
+    "value" TEXT NOT NULL,
+
+    CONSTRAINT "website_tag_pkey" PRIMARY KEY ("website_id", "key")
+);
+
+-- CreateIndex
+CREATE INDEX "website_tag_key_value_idx" ON "website_tag"("key", "value");
+
+-- AlterTable
+ALTER TABLE "website" ADD COLUMN "group_id" TEXT;
+
+-- AlterTable: windows can now cover a whole group, so ownership is stored on the window
+ALTER TABLE "maintenance_window" ADD COLUMN "user_id" TEXT;
+UPDATE "maintenance_window" SET "user_id" = "website"."user_id" FROM "website" WHERE "website"."id" = "maintenance_window"."website_id";
+ALTER TABLE "maintenance_window" ALTER COLUMN "user_id" SET NOT NULL;
+ALTER TABLE "maintenance_window" ALTER COLUMN "website_id" DROP NOT NULL;
+ALTER TABLE "maintenance_window" ADD COLUMN "group_id" TEXT;
+ALTER TABLE "maintenance_window" ADD CONSTRAINT "maintenance_window_target" CHECK (("website_id" IS NULL) <> ("group_id" IS NULL));
+
+-- AddForeignKey
+ALTER TABLE "website_group" ADD CONSTRAINT "website_group_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "user"("id") ON DELETE CASCADE ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "website_group" ADD CONSTRAINT "website_group_parent_id_fkey" FOREIGN KEY ("parent_id") REFERENCES "website_group"("id") ON DELETE CASCADE ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "website_tag" ADD CONSTRAINT "website_tag_website_id_fkey" FOREIGN KEY ("website_id") REFERENCES "website"("id") ON DELETE CASCADE ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "website" ADD CONSTRAINT "website_group_id_fkey" FOREIGN KEY ("group_id") REFERENCES "website_group"("id") ON DELETE SET NULL ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "maintenance_window" ADD CONSTRAINT "maintenance_window_group_id_fkey" FOREIGN KEY ("group_id") REFERENCES "website_group"("id") ON DELETE CASCADE ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "maintenance_window" ADD CONSTRAINT "maintenance_window_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "user"("id") ON DELETE CASCADE ON UPDATE CASCADE;
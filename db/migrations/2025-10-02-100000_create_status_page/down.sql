@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE "status_page_component";
+
+DROP TABLE "status_page";
@@ -0,0 +1,39 @@
+-- Your SQL goes here
+
+-- CreateTable
+CREATE TABLE "status_page" (
+    "id" TEXT NOT NULL,
+    "user_id" TEXT NOT NULL,
+    "slug" TEXT NOT NULL,
+    "title" TEXT NOT NULL,
+    "logo_url" TEXT,
+    "time_added" TIMESTAMP(3) NOT NULL,
+
+    CONSTRAINT "status_page_pkey" PRIMARY KEY ("id")
+);
+
+-- CreateTable
+CREATE TABLE "status_page_component" (
+    "id" TEXT NOT NULL,
+    "status_page_id" TEXT NOT NULL,
+    "website_id" TEXT NOT NULL,
+    "name" TEXT NOT NULL,
+    "position" INTEGER NOT NULL,
+
+    CONSTRAINT "status_page_component_pkey" PRIMARY KEY ("id")
+);
+
+-- CreateIndex
+CREATE UNIQUE INDEX "status_page_slug_key" ON "status_page"("slug");
+
+-- CreateIndex
+CREATE INDEX "status_page_component_status_page_id_idx" ON "status_page_component"("status_page_id");
+
+-- AddForeignKey
+ALTER TABLE "status_page" ADD CONSTRAINT "status_page_user_id_fkey" FOREIGN KEY ("user_id") REFERENCES "user"("id") ON DELETE CASCADE ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "status_page_component" ADD CONSTRAINT "status_page_component_status_page_id_fkey" FOREIGN KEY ("status_page_id") REFERENCES "status_page"("id") ON DELETE CASCADE ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "status_page_component" ADD CONSTRAINT "status_page_component_website_id_fkey" FOREIGN KEY ("website_id") REFERENCES "website"("id") ON DELETE CASCADE ON UPDATE CASCADE;
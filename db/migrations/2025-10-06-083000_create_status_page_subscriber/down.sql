@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE "maintenance_window" DROP COLUMN "subscribers_notified_at";
+
+DROP TABLE "status_page_subscriber";
@@ -0,0 +1,28 @@
+-- Your SQL goes here
+
+-- CreateTable
+CREATE TABLE "status_page_subscriber" (
+    "id" TEXT NOT NULL,
+    "status_page_id" TEXT NOT NULL,
+    "kind" TEXT NOT NULL,
+    "target" TEXT NOT NULL,
+    "component_ids" TEXT[] NOT NULL,
+    "token" TEXT NOT NULL,
+    "secret" TEXT,
+    "confirmed_at" TIMESTAMP(3),
+    "time_added" TIMESTAMP(3) NOT NULL,
+
+    CONSTRAINT "status_page_subscriber_pkey" PRIMARY KEY ("id")
+);
+
+-- CreateIndex
+CREATE UNIQUE INDEX "status_page_subscriber_token_key" ON "status_page_subscriber"("token");
+
+-- CreateIndex
+CREATE UNIQUE INDEX "status_page_subscriber_status_page_id_kind_target_key" ON "status_page_subscriber"("status_page_id", "kind", "target");
+
+-- AddForeignKey
+ALTER TABLE "status_page_subscriber" ADD CONSTRAINT "status_page_subscriber_status_page_id_fkey" FOREIGN KEY ("status_page_id") REFERENCES "status_page"("id") ON DELETE CASCADE ON UPDATE CASCADE;
+
+-- AlterTable
+ALTER TABLE "maintenance_window" ADD COLUMN "subscribers_notified_at" TIMESTAMP(3);
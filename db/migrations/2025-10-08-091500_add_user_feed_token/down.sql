@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX "user_feed_token_key";
+
+ALTER TABLE "user" DROP COLUMN "feed_token";
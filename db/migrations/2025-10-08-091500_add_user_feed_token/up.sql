@@ -0,0 +1,7 @@
+-- Your SQL goes here
+
+-- AlterTable
+ALTER TABLE "user" ADD COLUMN "feed_token" TEXT;
+
+-- CreateIndex
+CREATE UNIQUE INDEX "user_feed_token_key" ON "user"("feed_token");
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX "website_badge_token_key";
+
+ALTER TABLE "website" DROP COLUMN "badge_token";
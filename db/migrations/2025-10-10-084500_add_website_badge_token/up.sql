@@ -0,0 +1,11 @@
+-- Your SQL goes here
+
+-- AlterTable
+ALTER TABLE "website" ADD COLUMN "badge_token" TEXT;
+
+UPDATE "website" SET "badge_token" = replace(gen_random_uuid()::text, '-', '') || replace(gen_random_uuid()::text, '-', '');
+
+ALTER TABLE "website" ALTER COLUMN "badge_token" SET NOT NULL;
+
+-- CreateIndex
+CREATE UNIQUE INDEX "website_badge_token_key" ON "website"("badge_token");
@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX "status_page_user_id_external_key_key";
+DROP INDEX "notification_channel_user_id_external_key_key";
+DROP INDEX "website_user_id_external_key_key";
+
+ALTER TABLE "status_page" DROP COLUMN "external_key";
+ALTER TABLE "notification_channel" DROP COLUMN "external_key";
+ALTER TABLE "website" DROP COLUMN "external_key";
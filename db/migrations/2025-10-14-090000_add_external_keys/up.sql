@@ -0,0 +1,11 @@
+-- Your SQL goes here
+
+-- AlterTable
+ALTER TABLE "website" ADD COLUMN "external_key" TEXT;
+ALTER TABLE "notification_channel" ADD COLUMN "external_key" TEXT;
+ALTER TABLE "status_page" ADD COLUMN "external_key" TEXT;
+
+-- CreateIndex
+CREATE UNIQUE INDEX "website_user_id_external_key_key" ON "website"("user_id", "external_key");
+CREATE UNIQUE INDEX "notification_channel_user_id_external_key_key" ON "notification_channel"("user_id", "external_key");
+CREATE UNIQUE INDEX "status_page_user_id_external_key_key" ON "status_page"("user_id", "external_key");
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX "status_page_subscriber_kind_target_idx";
+
+ALTER TABLE "status_page_subscriber" DROP COLUMN "confirmation_sent_at";
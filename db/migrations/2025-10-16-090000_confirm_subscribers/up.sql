@@ -0,0 +1,7 @@
+-- Your SQL goes here
+
+-- AlterTable
+ALTER TABLE "status_page_subscriber" ADD COLUMN "confirmation_sent_at" TIMESTAMP(3);
+
+-- CreateIndex
+CREATE INDEX "status_page_subscriber_kind_target_idx" ON "status_page_subscriber"("kind", "target");
@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE "website" DROP CONSTRAINT "website_user_id_url_key";
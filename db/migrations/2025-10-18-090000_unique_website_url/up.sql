@@ -0,0 +1,41 @@
+-- Your SQL goes here
+
+-- Refuse to migrate while a user monitors the same url twice instead of picking a survivor,
+-- deleting one would take its incidents, ticks and deliveries with it. Urls are compared the
+-- way normalize_url sees them: case of scheme and host, default ports, fragments and trailing
+-- slashes don't count (punycode hosts can't be decoded here and are compared as stored).
+DO $$
+DECLARE
+    "conflicts" TEXT;
+BEGIN
+    WITH "parts" AS (
+        SELECT "id", "user_id", "url", "time_added",
+               lower(substring(btrim("url") FROM '^[^/?#]*//[^/?#]*')) AS "origin",
+               regexp_replace(coalesce(substring(btrim("url") FROM '^[^/?#]*//[^/?#]*(.*)$'), ''), '#.*$', '') AS "rest"
+        FROM "website"
+    ), "normalized" AS (
+        SELECT "id", "user_id", "url", "time_added",
+               regexp_replace(regexp_replace("origin", '^(http://.*):80$', '\1'), '^(https://.*):443$', '\1')
+                   || rtrim(substring("rest" FROM '^[^?]*'), '/')
+                   || coalesce(substring("rest" FROM '\?.*$'), '') AS "normalized_url"
+        FROM "parts"
+    )
+    SELECT string_agg(format('user %s, %s: %s', "user_id", "normalized_url", "websites"), E'\n' ORDER BY "user_id", "normalized_url")
+    INTO "conflicts"
+    FROM (
+        SELECT "user_id", "normalized_url",
+               string_agg(format('%s (%s)', "id", "url"), ', ' ORDER BY "time_added", "id") AS "websites"
+        FROM "normalized"
+        GROUP BY "user_id", "normalized_url"
+        HAVING count(*) > 1
+    ) AS "duplicates";
+
+    IF "conflicts" IS NOT NULL THEN
+        RAISE EXCEPTION E'these websites monitor the same url more than once:\n%', "conflicts"
+            USING HINT = 'Keep one website per url, moving or deleting the others and their history by hand, then run the migration again.';
+    END IF;
+END $$;
+
+-- AddConstraint
+-- Deferrable so a transaction can swap the urls of two websites, checked per statement otherwise
+ALTER TABLE "website" ADD CONSTRAINT "website_user_id_url_key" UNIQUE ("user_id", "url") DEFERRABLE INITIALLY IMMEDIATE;
@@ -0,0 +1,25 @@
+-- Your SQL goes here
+
+-- CreateTable
+CREATE TABLE "team_member" (
+    "id" TEXT NOT NULL,
+    "owner_id" TEXT NOT NULL,
+    "member_id" TEXT NOT NULL,
+    "invited_at" TIMESTAMP(3) NOT NULL,
+    "accepted_at" TIMESTAMP(3),
+
+    CONSTRAINT "team_member_pkey" PRIMARY KEY ("id"),
+    CONSTRAINT "team_member_not_owner_check" CHECK ("owner_id" <> "member_id")
+);
+
+-- CreateIndex
+CREATE UNIQUE INDEX "team_member_owner_id_member_id_key" ON "team_member"("owner_id", "member_id");
+
+-- CreateIndex
+CREATE INDEX "team_member_member_id_idx" ON "team_member"("member_id");
+
+-- AddForeignKey
+ALTER TABLE "team_member" ADD CONSTRAINT "team_member_owner_id_fkey" FOREIGN KEY ("owner_id") REFERENCES "user"("id") ON DELETE CASCADE ON UPDATE CASCADE;
+
+-- AddForeignKey
+ALTER TABLE "team_member" ADD CONSTRAINT "team_member_member_id_fkey" FOREIGN KEY ("member_id") REFERENCES "user"("id") ON DELETE CASCADE ON UPDATE CASCADE;
@@ -5,7 +5,11 @@ use dotenvy::dotenv;
 
 
 pub struct Config{
-    pub db_url:String
+    pub db_url:String,
+    /// Most connections open at once, `DB_POOL_MAX_SIZE`.
+    pub pool_max_size:usize,
+    /// How long a query waits for a free connection, and for a new one to open, `DB_POOL_TIMEOUT_SECS`.
+    pub pool_timeout_secs:u64
 }
 
 impl Default for Config{
@@ -13,9 +17,19 @@ impl Default for Config{
     dotenv().ok();
        let db_url=env::var("DATABASE_URL")
        .unwrap_or_else(|_| panic!("please provide the database url in env file"));
+       let pool_max_size=env::var("DB_POOL_MAX_SIZE")
+       .ok()
+       .and_then(|v| v.parse().ok())
+       .unwrap_or(16);
+       let pool_timeout_secs=env::var("DB_POOL_TIMEOUT_SECS")
+       .ok()
+       .and_then(|v| v.parse().ok())
+       .unwrap_or(5);
 
     Self{
-    db_url
+    db_url,
+    pool_max_size,
+    pool_timeout_secs
     }
    }
 
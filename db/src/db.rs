@@ -1,19 +1,124 @@
-use diesel::{Connection, ConnectionError, PgConnection};
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use std::time::Duration;
+
+use deadpool::Runtime;
+use diesel_async::pooled_connection::deadpool::{BuildError, Object, Pool};
+use diesel_async::pooled_connection::AsyncDieselConnectionManager;
+use diesel_async::scoped_futures::ScopedBoxFuture;
+use diesel_async::{AnsiTransactionManager, AsyncPgConnection, TransactionManager};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+/// For building the callbacks [`Db::transaction`] takes: `async move { .. }.scope_boxed()`.
+pub use diesel_async::scoped_futures::ScopedFutureExt;
 
 use crate::config::Config;
+use crate::error::DbError;
 
+/// Keyset pagination of a listing: the rows after the one with id `after`, in the listing's order.
+#[derive(Debug, Clone, Default)]
+pub struct Page {
+    /// Every remaining row when `None`.
+    pub limit: Option<i64>,
+    pub after: Option<String>,
+}
+
+/// Pool of async Postgres connections. Cheap to clone, every clone shares the pool.
+#[derive(Clone)]
 pub struct Db{
-  pub conn:PgConnection
+  pool:Pool<AsyncPgConnection>,
+  /// The connection every query goes through inside [`Db::transaction`].
+  bound:Option<Arc<Mutex<Object<AsyncPgConnection>>>>
+}
+
+/// A connection from the pool, or the one a transaction is bound to.
+pub(crate) enum Conn{
+  Pooled(Object<AsyncPgConnection>),
+  Bound(OwnedMutexGuard<Object<AsyncPgConnection>>)
+}
+
+impl Deref for Conn{
+  type Target=AsyncPgConnection;
+
+  fn deref(&self)->&AsyncPgConnection{
+    match self{
+      Conn::Pooled(conn)=>conn,
+      Conn::Bound(conn)=>conn
+    }
+  }
+}
+
+impl DerefMut for Conn{
+  fn deref_mut(&mut self)->&mut AsyncPgConnection{
+    match self{
+      Conn::Pooled(conn)=>conn,
+      Conn::Bound(conn)=>conn
+    }
+  }
 }
 
 impl Db {
-    pub fn new() -> Result<Self, ConnectionError> {
+    pub fn new() -> Result<Self, BuildError> {
         let config=Config::default();
-        // 2. Try to establish a Postgres connection
-        let conn = PgConnection::establish(&config.db_url)?;
+        let manager=AsyncDieselConnectionManager::<AsyncPgConnection>::new(&config.db_url);
+        // connections are opened lazily, the first query fails if the database is unreachable
+        let pool=Pool::builder(manager)
+            .max_size(config.pool_max_size)
+            .wait_timeout(Some(Duration::from_secs(config.pool_timeout_secs)))
+            .create_timeout(Some(Duration::from_secs(config.pool_timeout_secs)))
+            .runtime(Runtime::Tokio1)
+            .build()?;
 
         Ok(Self {
-            conn,
+            pool,
+            bound: None,
         })
     }
-}
\ No newline at end of file
+
+    /// A connection from the pool, `Unavailable` when none frees up in time or one can't be opened.
+    /// Inside [`Db::transaction`] it's the transaction's; methods must let go of it before
+    /// calling another method, which would otherwise fail here.
+    pub(crate) async fn conn(&self) -> Result<Conn, DbError> {
+        if let Some(bound) = &self.bound {
+            return bound
+                .clone()
+                .try_lock_owned()
+                .map(Conn::Bound)
+                .map_err(|_| DbError::Unavailable("the transaction's connection is already in use".to_string()));
+        }
+        self.pool.get().await.map(Conn::Pooled).map_err(|e| DbError::Unavailable(e.to_string()))
+    }
+
+    /// Runs `callback` in one transaction, committed when it returns `Ok` and rolled back
+    /// otherwise. Every method of the `Db` it's given runs in the transaction; their own
+    /// transactions become savepoints. Nested calls join the outer transaction.
+    pub async fn transaction<'a, R, E, F>(&self, callback: F) -> Result<R, E>
+    where
+        F: for<'r> FnOnce(&'r Db) -> ScopedBoxFuture<'a, 'r, Result<R, E>> + Send + 'a,
+        E: From<DbError> + Send + 'a,
+        R: Send + 'a,
+    {
+        if self.bound.is_some() {
+            return callback(self).await;
+        }
+
+        let mut conn = self.pool.get().await.map_err(|e| DbError::Unavailable(e.to_string()))?;
+        AnsiTransactionManager::begin_transaction(&mut *conn).await.map_err(DbError::from)?;
+        let bound = Arc::new(Mutex::new(conn));
+        let tx = Db { pool: self.pool.clone(), bound: Some(bound.clone()) };
+        let result = callback(&tx).await;
+
+        let mut conn = bound.lock().await;
+        match result {
+            Ok(value) => {
+                AnsiTransactionManager::commit_transaction(&mut **conn).await.map_err(DbError::from)?;
+                Ok(value)
+            }
+            Err(e) => {
+                // a failed rollback leaves the connection broken, the pool discards it
+                let _ = AnsiTransactionManager::rollback_transaction(&mut **conn).await;
+                Err(e)
+            }
+        }
+    }
+}
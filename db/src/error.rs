@@ -0,0 +1,65 @@
+use std::fmt;
+
+use diesel::result::DatabaseErrorKind;
+
+/// What went wrong with a db call, in terms callers can act on.
+#[derive(Debug)]
+pub enum DbError {
+    /// The row doesn't exist or belongs to another user.
+    NotFound,
+    /// A unique constraint rejected the write, e.g. a taken username or slug.
+    Conflict(String),
+    /// The write points at something that doesn't exist, e.g. an unknown website id.
+    InvalidReference(String),
+    /// Unknown username or wrong password; deliberately doesn't say which.
+    InvalidCredentials,
+    /// No connection could be had from the pool or it dropped mid-query.
+    Unavailable(String),
+    /// Anything else diesel reports.
+    Query(diesel::result::Error),
+}
+
+impl fmt::Display for DbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DbError::NotFound => write!(f, "not found"),
+            DbError::Conflict(e) => write!(f, "conflict: {}", e),
+            DbError::InvalidReference(e) => write!(f, "invalid reference: {}", e),
+            DbError::InvalidCredentials => write!(f, "invalid username or password"),
+            DbError::Unavailable(e) => write!(f, "database unavailable: {}", e),
+            DbError::Query(e) => write!(f, "query failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for DbError {}
+
+impl From<diesel::result::Error> for DbError {
+    fn from(e: diesel::result::Error) -> Self {
+        match e {
+            diesel::result::Error::NotFound => DbError::NotFound,
+            diesel::result::Error::DatabaseError(kind, info) => match kind {
+                DatabaseErrorKind::UniqueViolation => DbError::Conflict(info.message().to_string()),
+                DatabaseErrorKind::ForeignKeyViolation => DbError::InvalidReference(info.message().to_string()),
+                DatabaseErrorKind::ClosedConnection => DbError::Unavailable(info.message().to_string()),
+                _ => DbError::Query(diesel::result::Error::DatabaseError(kind, info)),
+            },
+            e => DbError::Query(e),
+        }
+    }
+}
+
+/// `Ok(None)` for rows that may legitimately be missing, like diesel's `OptionalExtension`.
+pub trait OptionalExt<T> {
+    fn optional(self) -> Result<Option<T>, DbError>;
+}
+
+impl<T> OptionalExt<T> for Result<T, DbError> {
+    fn optional(self) -> Result<Option<T>, DbError> {
+        match self {
+            Ok(value) => Ok(Some(value)),
+            Err(DbError::NotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
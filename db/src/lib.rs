@@ -1,6 +1,7 @@
 pub mod schema;
 pub mod config;
 pub mod db;
+pub mod error;
 pub mod models;
 
 
@@ -0,0 +1,99 @@
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::ExpressionMethods;
+use diesel_async::RunQueryDsl;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::db::Db;
+use crate::error::DbError;
+
+/// Start of every api key, so they're easy to tell from jwts and to spot in leaked text.
+pub const API_KEY_PREFIX: &str = "bu_";
+
+/// Characters of the key kept in the clear so users can tell their keys apart.
+const SHOWN_PREFIX_LEN: usize = 8;
+
+/// A long-lived credential for scripts, acting as the user who created it.
+/// Only a hash of the key is stored; the key itself is shown once, on creation.
+#[derive(Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::api_key)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ApiKey {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    /// The first characters of the key, e.g. `bu_1a2b3`.
+    pub prefix: String,
+    key_hash: String,
+    pub time_added: chrono::NaiveDateTime,
+    /// Updated on every authenticated request.
+    pub last_used_at: Option<chrono::NaiveDateTime>,
+}
+
+fn hash_key(key: &str) -> String {
+    Sha256::digest(key.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl Db {
+    /// Returns the key along with the row; it can't be read back later.
+    pub async fn create_api_key(&self, input_user_id: String, input_name: String) -> Result<(ApiKey, String), DbError> {
+        use crate::schema::api_key::dsl::*;
+
+        let mut conn = self.conn().await?;
+        let key = format!("{}{}{}", API_KEY_PREFIX, Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        let row = ApiKey {
+            id: Uuid::new_v4().to_string(),
+            user_id: input_user_id,
+            name: input_name,
+            prefix: key[..SHOWN_PREFIX_LEN].to_string(),
+            key_hash: hash_key(&key),
+            time_added: Utc::now().naive_utc(),
+            last_used_at: None,
+        };
+        diesel::insert_into(api_key)
+            .values(&row)
+            .execute(&mut conn).await?;
+
+        Ok((row, key))
+    }
+
+    pub async fn get_api_keys(&self, input_user_id: String) -> Result<Vec<ApiKey>, DbError> {
+        use crate::schema::api_key::dsl::*;
+
+        let mut conn = self.conn().await?;
+        api_key
+            .filter(user_id.eq(input_user_id))
+            .order(time_added.asc())
+            .select(ApiKey::as_select())
+            .load(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Revokes the key; requests using it fail from then on.
+    pub async fn delete_api_key(&self, input_user_id: String, input_id: String) -> Result<(), DbError> {
+        use crate::schema::api_key::dsl::*;
+
+        let mut conn = self.conn().await?;
+        let deleted = diesel::delete(api_key.filter(id.eq(input_id)).filter(user_id.eq(input_user_id)))
+            .execute(&mut conn).await?;
+        if deleted == 0 {
+            return Err(DbError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// The user a key acts as, `InvalidCredentials` for unknown or revoked keys.
+    pub async fn get_user_id_by_api_key(&self, key: &str) -> Result<String, DbError> {
+        use crate::schema::api_key::dsl::*;
+
+        let mut conn = self.conn().await?;
+        diesel::update(api_key.filter(key_hash.eq(hash_key(key))))
+            .set(last_used_at.eq(Some(Utc::now().naive_utc())))
+            .returning(user_id)
+            .get_result(&mut conn).await
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => DbError::InvalidCredentials,
+                e => DbError::from(e),
+            })
+    }
+}
@@ -0,0 +1,181 @@
+use diesel::prelude::*;
+use diesel::ExpressionMethods;
+use diesel_async::{RunQueryDsl, AsyncConnection};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use uuid::Uuid;
+
+use crate::db::Db;
+use crate::error::DbError;
+use crate::models::incident::Incident;
+use crate::models::incident_event::EVENT_ESCALATED;
+use crate::models::notification_channel::NotificationChannel;
+use crate::models::website::Website;
+
+#[derive(Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::escalation_policy)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct EscalationPolicy {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+}
+
+/// One step of a policy. Exactly one of `channel_id` / `schedule_id` is set.
+#[derive(Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::escalation_level)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct EscalationLevel {
+    pub id: String,
+    pub policy_id: String,
+    pub position: i32,
+    /// Minutes after the incident opened before this level is notified.
+    pub delay_minutes: i32,
+    pub channel_id: Option<String>,
+    pub schedule_id: Option<String>,
+}
+
+pub struct NewEscalationLevel {
+    pub delay_minutes: i32,
+    pub channel_id: Option<String>,
+    pub schedule_id: Option<String>,
+}
+
+impl Db {
+    /// Creates a policy with its levels in the given order. Every channel and schedule
+    /// referenced must belong to the user, otherwise nothing is created.
+    pub async fn create_escalation_policy(&self, input_user_id: String, name: String, levels: Vec<NewEscalationLevel>) -> Result<(EscalationPolicy, Vec<EscalationLevel>), DbError> {
+        use crate::schema::{notification_channel, on_call_schedule};
+
+        let mut conn = self.conn().await?;
+        conn.transaction::<_, diesel::result::Error, _>(|conn| async move {
+            for level in &levels {
+                if let Some(channel_id) = &level.channel_id {
+                    notification_channel::table
+                        .filter(notification_channel::id.eq(channel_id))
+                        .filter(notification_channel::user_id.eq(&input_user_id))
+                        .select(notification_channel::id)
+                        .first::<String>(conn).await?;
+                }
+                if let Some(schedule_id) = &level.schedule_id {
+                    on_call_schedule::table
+                        .filter(on_call_schedule::id.eq(schedule_id))
+                        .filter(on_call_schedule::user_id.eq(&input_user_id))
+                        .select(on_call_schedule::id)
+                        .first::<String>(conn).await?;
+                }
+            }
+
+            let policy = EscalationPolicy {
+                id: Uuid::new_v4().to_string(),
+                user_id: input_user_id.clone(),
+                name,
+            };
+            diesel::insert_into(crate::schema::escalation_policy::table)
+                .values(&policy)
+                .execute(conn).await?;
+
+            let levels: Vec<EscalationLevel> = levels
+                .into_iter()
+                .enumerate()
+                .map(|(position, level)| EscalationLevel {
+                    id: Uuid::new_v4().to_string(),
+                    policy_id: policy.id.clone(),
+                    position: position as i32,
+                    delay_minutes: level.delay_minutes,
+                    channel_id: level.channel_id,
+                    schedule_id: level.schedule_id,
+                })
+                .collect();
+            diesel::insert_into(crate::schema::escalation_level::table)
+                .values(&levels)
+                .execute(conn).await?;
+
+            Ok((policy, levels))
+        }.scope_boxed()).await.map_err(DbError::from)
+    }
+
+    pub async fn get_escalation_policies(&self, input_user_id: String) -> Result<Vec<(EscalationPolicy, Vec<EscalationLevel>)>, DbError> {
+        let policies = {
+            use crate::schema::escalation_policy::dsl::*;
+
+            let mut conn = self.conn().await?;
+            escalation_policy
+                .filter(user_id.eq(input_user_id))
+                .order(name.asc())
+                .select(EscalationPolicy::as_select())
+                .load(&mut conn).await?
+        };
+
+        let mut result = Vec::with_capacity(policies.len());
+        for policy in policies {
+            let levels = self.get_escalation_levels(policy.id.clone()).await?;
+            result.push((policy, levels));
+        }
+        Ok(result)
+    }
+
+    pub async fn get_escalation_levels(&self, input_policy_id: String) -> Result<Vec<EscalationLevel>, DbError> {
+        use crate::schema::escalation_level::dsl::*;
+
+        let mut conn = self.conn().await?;
+        escalation_level
+            .filter(policy_id.eq(input_policy_id))
+            .order(position.asc())
+            .select(EscalationLevel::as_select())
+            .load(&mut conn).await.map_err(DbError::from)
+    }
+
+    pub async fn delete_escalation_policy(&self, input_user_id: String, input_id: String) -> Result<usize, DbError> {
+        use crate::schema::escalation_policy::dsl::*;
+
+        let mut conn = self.conn().await?;
+        diesel::delete(escalation_policy.filter(id.eq(input_id)).filter(user_id.eq(input_user_id)))
+            .execute(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Channels a level notifies: its channel, or every channel of whoever is on call right now.
+    pub async fn get_escalation_level_channels(&self, level: &EscalationLevel) -> Result<Vec<NotificationChannel>, DbError> {
+        if let Some(channel_id) = &level.channel_id {
+            return Ok(vec![self.get_notification_channel(channel_id.clone()).await?]);
+        }
+        let Some(schedule_id) = &level.schedule_id else {
+            return Ok(Vec::new());
+        };
+
+        match self.get_current_on_call(schedule_id.clone()).await? {
+            Some(on_call_user) => self.get_notification_channels(on_call_user).await,
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Unresolved, unacknowledged incidents on monitored websites that follow an escalation policy.
+    pub async fn get_escalating_incidents(&self) -> Result<Vec<(Incident, Website)>, DbError> {
+        use crate::schema::{incident, website};
+
+        let mut conn = self.conn().await?;
+        incident::table
+            .inner_join(website::table)
+            .filter(incident::resolved_at.is_null())
+            .filter(incident::acknowledged_at.is_null())
+            .filter(website::escalation_policy_id.is_not_null())
+            .filter(website::paused.eq(false))
+            .select((Incident::as_select(), Website::as_select()))
+            .load(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Moves an incident past `from_level`. Returns false when another notifier got there first.
+    pub async fn advance_incident_escalation(&self, input_incident_id: String, from_level: i32) -> Result<bool, DbError> {
+        use crate::schema::incident::dsl::*;
+
+        let updated = {
+            let mut conn = self.conn().await?;
+            diesel::update(incident.filter(id.eq(&input_incident_id)).filter(escalation_level.eq(from_level)))
+                .set(escalation_level.eq(from_level + 1))
+                .execute(&mut conn).await?
+        };
+        if updated == 1 {
+            self.record_incident_event(input_incident_id, EVENT_ESCALATED, None, format!("escalated to level {}", from_level + 1)).await?;
+        }
+        Ok(updated == 1)
+    }
+}
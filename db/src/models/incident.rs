@@ -0,0 +1,258 @@
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::ExpressionMethods;
+use diesel_async::RunQueryDsl;
+use diesel_async::scoped_futures::ScopedFutureExt;
+use uuid::Uuid;
+
+use crate::db::{Db, Page};
+use crate::error::DbError;
+use crate::models::incident_event::{EVENT_ACKNOWLEDGED, EVENT_DEPENDENT_DOWN, EVENT_OPENED, EVENT_RESOLVED};
+use crate::models::website::Website;
+use crate::models::website_tick::WebsiteStatus;
+
+pub const INCIDENT_OPEN: &str = "open";
+pub const INCIDENT_ACKNOWLEDGED: &str = "acknowledged";
+pub const INCIDENT_RESOLVED: &str = "resolved";
+
+#[derive(Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::incident)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Incident {
+    pub id: String,
+    pub website_id: String,
+    pub status: String,
+    pub opened_at: chrono::NaiveDateTime,
+    pub resolved_at: Option<chrono::NaiveDateTime>,
+    pub acknowledged_at: Option<chrono::NaiveDateTime>,
+    pub acknowledged_by: Option<String>,
+    /// Number of escalation levels that have already been notified.
+    pub escalation_level: i32,
+}
+
+/// A website flipping between up and down, together with the incident it opened or resolved.
+pub struct StatusChange {
+    pub website: Website,
+    pub previous_status: WebsiteStatus,
+    pub new_status: WebsiteStatus,
+    pub incident: Incident,
+    pub occurred_at: chrono::NaiveDateTime,
+}
+
+impl Db {
+    /// The website's unresolved incident, acknowledged or not.
+    pub async fn get_open_incident(&self, input_website_id: String) -> Result<Option<Incident>, DbError> {
+        use crate::schema::incident::dsl::*;
+
+        let mut conn = self.conn().await?;
+        incident
+            .filter(website_id.eq(input_website_id))
+            .filter(resolved_at.is_null())
+            .select(Incident::as_select())
+            .first(&mut conn).await
+            .optional().map_err(DbError::from)
+    }
+
+    /// Stores the outcome of a check as a tick and applies it to the website's incident state.
+    ///
+    /// A down result with no open incident opens one, an up result with an open
+    /// incident resolves it. Anything else leaves the state untouched and returns `None`.
+    /// Failures during a maintenance window or of a paused website don't open incidents,
+    /// and failures while a dependency is down are folded into the dependency's incident.
+    /// The decision runs in one transaction holding the website's row, so regions reporting
+    /// at the same time take turns instead of both opening an incident.
+    pub async fn record_website_status(&self, input_website_id: String, region_id: String, new_status: WebsiteStatus, response_time_ms: i32) -> Result<Option<StatusChange>, DbError> {
+        self.transaction(|tx| async move { tx.apply_website_status(input_website_id, region_id, new_status, response_time_ms).await }.scope_boxed()).await
+    }
+
+    async fn apply_website_status(&self, input_website_id: String, region_id: String, new_status: WebsiteStatus, response_time_ms: i32) -> Result<Option<StatusChange>, DbError> {
+        let changed_website = {
+            use crate::schema::website::dsl::*;
+
+            let mut conn = self.conn().await?;
+            website
+                .filter(id.eq(&input_website_id))
+                .select(Website::as_select())
+                .for_update()
+                .first(&mut conn).await?
+        };
+        let now = Utc::now().naive_utc();
+        let in_maintenance = self.is_in_maintenance(input_website_id.clone(), now).await?;
+        let down_dependency = match new_status {
+            WebsiteStatus::Down => self.get_down_dependency(input_website_id.clone()).await?,
+            _ => None,
+        };
+        self.create_website_tick(input_website_id.clone(), region_id, new_status, response_time_ms, in_maintenance, down_dependency.is_some()).await?;
+        let open_incident = self.get_open_incident(input_website_id.clone()).await?;
+
+        let (previous_status, changed_incident) = match (new_status, open_incident) {
+            (WebsiteStatus::Down, None) if !changed_website.paused && !in_maintenance => {
+                if let Some(parent) = down_dependency {
+                    if self.add_incident_dependent(parent.id.clone(), input_website_id.clone()).await? {
+                        self.record_incident_event(parent.id, EVENT_DEPENDENT_DOWN, None, format!("{} is down as well", changed_website.url)).await?;
+                    }
+                    return Ok(None);
+                }
+                let opened = Incident {
+                    id: Uuid::new_v4().to_string(),
+                    website_id: input_website_id.clone(),
+                    status: INCIDENT_OPEN.to_string(),
+                    opened_at: now,
+                    resolved_at: None,
+                    acknowledged_at: None,
+                    acknowledged_by: None,
+                    escalation_level: 0,
+                };
+                {
+                    let mut conn = self.conn().await?;
+                    diesel::insert_into(crate::schema::incident::table)
+                        .values(&opened)
+                        .execute(&mut conn).await?;
+                }
+                self.record_incident_event(opened.id.clone(), EVENT_OPENED, None, "website went down".to_string()).await?;
+                (WebsiteStatus::Up, opened)
+            }
+            (WebsiteStatus::Up, Some(open)) => {
+                let resolved = {
+                    use crate::schema::incident::dsl::*;
+
+                    let mut conn = self.conn().await?;
+                    diesel::update(incident.filter(id.eq(open.id)))
+                        .set((status.eq(INCIDENT_RESOLVED), resolved_at.eq(Some(now))))
+                        .returning(Incident::as_returning())
+                        .get_result(&mut conn).await?
+                };
+                self.record_incident_event(resolved.id.clone(), EVENT_RESOLVED, None, "website recovered".to_string()).await?;
+                (WebsiteStatus::Down, resolved)
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(StatusChange {
+            website: changed_website,
+            previous_status,
+            new_status,
+            incident: changed_incident,
+            occurred_at: now,
+        }))
+    }
+
+    pub async fn get_incident(&self, input_user_id: String, input_id: String) -> Result<Incident, DbError> {
+        use crate::schema::{incident, website};
+
+        let mut conn = self.conn().await?;
+        incident::table
+            .inner_join(website::table)
+            .filter(incident::id.eq(input_id))
+            .filter(website::user_id.eq(input_user_id))
+            .select(Incident::as_select())
+            .first(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Most recent incidents across the user's websites, newest first.
+    /// Newest first; `page.after` must be one of the user's incidents.
+    pub async fn get_incidents(&self, input_user_id: String, page: &Page) -> Result<Vec<Incident>, DbError> {
+        use crate::schema::{incident, website};
+
+        let mut conn = self.conn().await?;
+        let mut query = incident::table
+            .inner_join(website::table)
+            .filter(website::user_id.eq(&input_user_id))
+            .order((incident::opened_at.desc(), incident::id.desc()))
+            .select(Incident::as_select())
+            .into_boxed();
+        if let Some(after) = &page.after {
+            let after_opened: chrono::NaiveDateTime = incident::table
+                .inner_join(website::table)
+                .filter(incident::id.eq(after))
+                .filter(website::user_id.eq(&input_user_id))
+                .select(incident::opened_at)
+                .first(&mut conn).await?;
+            query = query.filter(incident::opened_at.lt(after_opened).or(incident::opened_at.eq(after_opened).and(incident::id.lt(after.clone()))));
+        }
+        if let Some(limit) = page.limit {
+            query = query.limit(limit);
+        }
+        query.load(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Incidents on the given websites that are still open or were opened since `since`, newest first.
+    pub async fn get_website_incidents(&self, website_ids: Vec<String>, since: chrono::NaiveDateTime) -> Result<Vec<Incident>, DbError> {
+        use crate::schema::incident::dsl::*;
+
+        let mut conn = self.conn().await?;
+        incident
+            .filter(website_id.eq_any(website_ids))
+            .filter(opened_at.ge(since).or(resolved_at.is_null()))
+            .order(opened_at.desc())
+            .select(Incident::as_select())
+            .load(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Stops escalation for an incident on one of the user's websites.
+    pub async fn acknowledge_incident(&self, input_user_id: String, input_id: String, via: &str) -> Result<Incident, DbError> {
+        let owned = self.get_incident(input_user_id.clone(), input_id).await?;
+        self.acknowledge_incident_as(owned.id, input_user_id, via).await
+    }
+
+    /// Acknowledges on behalf of a user the caller has already authorized, e.g. through a
+    /// signed link. Acknowledging twice, or after resolution, leaves the incident as it was.
+    pub async fn acknowledge_incident_as(&self, input_id: String, acting_user_id: String, via: &str) -> Result<Incident, DbError> {
+        use crate::schema::incident::dsl::*;
+
+        let updated = {
+            let mut conn = self.conn().await?;
+            diesel::update(incident.filter(id.eq(&input_id)).filter(resolved_at.is_null()).filter(acknowledged_at.is_null()))
+                .set((
+                    status.eq(INCIDENT_ACKNOWLEDGED),
+                    acknowledged_at.eq(Some(Utc::now().naive_utc())),
+                    acknowledged_by.eq(Some(acting_user_id.clone())),
+                ))
+                .returning(Incident::as_returning())
+                .get_result(&mut conn).await
+                .optional()?
+        };
+
+        match updated {
+            Some(acknowledged) => {
+                self.record_incident_event(acknowledged.id.clone(), EVENT_ACKNOWLEDGED, Some(acting_user_id), format!("acknowledged via {}", via)).await?;
+                Ok(acknowledged)
+            }
+            None => {
+                let mut conn = self.conn().await?;
+                incident.filter(id.eq(input_id)).select(Incident::as_select()).first(&mut conn).await.map_err(DbError::from)
+            }
+        }
+    }
+
+    /// Manually resolves an incident on one of the user's websites.
+    pub async fn resolve_incident(&self, input_user_id: String, input_id: String, via: &str) -> Result<Incident, DbError> {
+        let owned = self.get_incident(input_user_id.clone(), input_id).await?;
+        self.resolve_incident_as(owned.id, input_user_id, via).await
+    }
+
+    /// Resolves on behalf of an already authorized user. Resolving twice is a no-op.
+    pub async fn resolve_incident_as(&self, input_id: String, acting_user_id: String, via: &str) -> Result<Incident, DbError> {
+        use crate::schema::incident::dsl::*;
+
+        let updated = {
+            let mut conn = self.conn().await?;
+            diesel::update(incident.filter(id.eq(&input_id)).filter(resolved_at.is_null()))
+                .set((status.eq(INCIDENT_RESOLVED), resolved_at.eq(Some(Utc::now().naive_utc()))))
+                .returning(Incident::as_returning())
+                .get_result(&mut conn).await
+                .optional()?
+        };
+
+        match updated {
+            Some(resolved) => {
+                self.record_incident_event(resolved.id.clone(), EVENT_RESOLVED, Some(acting_user_id), format!("resolved via {}", via)).await?;
+                Ok(resolved)
+            }
+            None => {
+                let mut conn = self.conn().await?;
+                incident.filter(id.eq(input_id)).select(Incident::as_select()).first(&mut conn).await.map_err(DbError::from)
+            }
+        }
+    }
+}
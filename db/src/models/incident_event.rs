@@ -0,0 +1,73 @@
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::ExpressionMethods;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::db::Db;
+use crate::error::DbError;
+
+pub const EVENT_OPENED: &str = "opened";
+pub const EVENT_ESCALATED: &str = "escalated";
+pub const EVENT_ACKNOWLEDGED: &str = "acknowledged";
+pub const EVENT_RESOLVED: &str = "resolved";
+/// A website depending on the incident's website failed and was folded into this incident.
+pub const EVENT_DEPENDENT_DOWN: &str = "dependent_down";
+
+/// One entry on an incident's timeline.
+#[derive(Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::incident_event)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct IncidentEvent {
+    pub id: String,
+    pub incident_id: String,
+    pub kind: String,
+    /// Who acted, `None` for things the monitor did on its own.
+    pub user_id: Option<String>,
+    pub message: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl Db {
+    pub async fn record_incident_event(&self, incident_id: String, kind: &str, user_id: Option<String>, message: String) -> Result<IncidentEvent, DbError> {
+        let mut conn = self.conn().await?;
+        let event = IncidentEvent {
+            id: Uuid::new_v4().to_string(),
+            incident_id,
+            kind: kind.to_string(),
+            user_id,
+            message,
+            created_at: Utc::now().naive_utc(),
+        };
+        diesel::insert_into(crate::schema::incident_event::table)
+            .values(&event)
+            .execute(&mut conn).await?;
+
+        Ok(event)
+    }
+
+    /// Timeline of an incident on one of the user's websites, oldest first.
+    pub async fn get_incident_timeline(&self, input_user_id: String, input_incident_id: String) -> Result<Vec<IncidentEvent>, DbError> {
+        let owned = self.get_incident(input_user_id, input_incident_id).await?;
+        let mut conn = self.conn().await?;
+
+        use crate::schema::incident_event::dsl::*;
+        incident_event
+            .filter(incident_id.eq(owned.id))
+            .order(created_at.asc())
+            .select(IncidentEvent::as_select())
+            .load(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Timelines of several incidents at once, oldest first.
+    pub async fn get_incident_events(&self, incident_ids: &[String]) -> Result<Vec<IncidentEvent>, DbError> {
+        use crate::schema::incident_event::dsl::*;
+
+        let mut conn = self.conn().await?;
+        incident_event
+            .filter(incident_id.eq_any(incident_ids))
+            .order(created_at.asc())
+            .select(IncidentEvent::as_select())
+            .load(&mut conn).await.map_err(DbError::from)
+    }
+}
@@ -0,0 +1,388 @@
+use std::str::FromStr;
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use cron::Schedule;
+use diesel::prelude::*;
+use diesel::ExpressionMethods;
+use diesel_async::{RunQueryDsl, AsyncConnection, AsyncPgConnection};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use uuid::Uuid;
+
+use crate::db::Db;
+use crate::error::DbError;
+
+/// A period during which a website, or every website in a group and its
+/// subgroups, is still checked but failures don't open incidents and the
+/// results don't count against uptime.
+#[derive(Queryable, Insertable, Selectable, AsChangeset, Clone)]
+#[diesel(table_name = crate::schema::maintenance_window)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[diesel(treat_none_as_null = true)]
+pub struct MaintenanceWindow {
+    pub id: String,
+    /// Exactly one of `website_id` / `group_id` is set.
+    pub website_id: Option<String>,
+    pub title: String,
+    /// Start of a one-off window, or the moment a recurring one takes effect.
+    pub starts_at: NaiveDateTime,
+    pub duration_minutes: i32,
+    /// Cron expression (UTC) for recurring windows, each occurrence lasting `duration_minutes`.
+    pub recurrence: Option<String>,
+    /// No occurrence of a recurring window starts at or after this moment.
+    pub until: Option<NaiveDateTime>,
+    pub time_added: NaiveDateTime,
+    pub user_id: String,
+    pub group_id: Option<String>,
+    /// When status page subscribers were told about the current schedule.
+    pub subscribers_notified_at: Option<NaiveDateTime>,
+}
+
+pub struct NewMaintenanceWindow {
+    pub website_id: Option<String>,
+    pub group_id: Option<String>,
+    pub title: String,
+    pub starts_at: NaiveDateTime,
+    pub duration_minutes: i32,
+    pub recurrence: Option<String>,
+    pub until: Option<NaiveDateTime>,
+}
+
+/// Parses a recurrence rule. Plain 5-field crontab lines are accepted and run at second 0.
+pub fn parse_recurrence(expression: &str) -> Result<Schedule, cron::error::Error> {
+    if expression.split_whitespace().count() == 5 {
+        Schedule::from_str(&format!("0 {}", expression))
+    } else {
+        Schedule::from_str(expression)
+    }
+}
+
+impl MaintenanceWindow {
+    pub fn duration(&self) -> Duration {
+        Duration::minutes(self.duration_minutes as i64)
+    }
+
+    /// Start of the occurrence covering `at`, if any.
+    pub fn occurrence_at(&self, at: NaiveDateTime) -> Option<NaiveDateTime> {
+        if at < self.starts_at {
+            return None;
+        }
+        let Some(expression) = &self.recurrence else {
+            return (at < self.starts_at + self.duration()).then_some(self.starts_at);
+        };
+
+        // an occurrence covering `at` started within the last `duration`; `after` is exclusive
+        let earliest = (at - self.duration()).max(self.starts_at) - Duration::seconds(1);
+        let schedule = parse_recurrence(expression).ok()?;
+        let start = schedule.after(&earliest.and_utc()).next()?.naive_utc();
+        let started_before_until = self.until.is_none_or(|until| start < until);
+        (start <= at && at < start + self.duration() && started_before_until).then_some(start)
+    }
+
+    /// Next `count` occurrences starting after `from`, as (start, end).
+    pub fn occurrences_after(&self, from: NaiveDateTime, count: usize) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+        let Some(expression) = &self.recurrence else {
+            return if self.starts_at + self.duration() > from {
+                vec![(self.starts_at, self.starts_at + self.duration())]
+            } else {
+                Vec::new()
+            };
+        };
+        let Ok(schedule) = parse_recurrence(expression) else {
+            return Vec::new();
+        };
+
+        let from = from.max(self.starts_at - Duration::seconds(1));
+        schedule
+            .after(&from.and_utc())
+            .map(|start| start.naive_utc())
+            .take_while(|start| self.until.is_none_or(|until| *start < until))
+            .take(count)
+            .map(|start| (start, start + self.duration()))
+            .collect()
+    }
+}
+
+impl Db {
+    /// Fails with `NotFound` unless the window's website or group belongs to the user.
+    async fn check_maintenance_target(conn: &mut AsyncPgConnection, input_user_id: &str, window: &NewMaintenanceWindow) -> Result<(), diesel::result::Error> {
+        use crate::schema::{website, website_group};
+
+        if let Some(input_website_id) = &window.website_id {
+            website::table
+                .filter(website::id.eq(input_website_id))
+                .filter(website::user_id.eq(input_user_id))
+                .select(website::id)
+                .first::<String>(conn).await?;
+        }
+        if let Some(input_group_id) = &window.group_id {
+            website_group::table
+                .filter(website_group::id.eq(input_group_id))
+                .filter(website_group::user_id.eq(input_user_id))
+                .select(website_group::id)
+                .first::<String>(conn).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn create_maintenance_window(&self, input_user_id: String, window: NewMaintenanceWindow) -> Result<MaintenanceWindow, DbError> {
+        let mut conn = self.conn().await?;
+        conn.transaction::<_, diesel::result::Error, _>(|conn| async move {
+            Self::check_maintenance_target(conn, &input_user_id, &window).await?;
+
+            let window = MaintenanceWindow {
+                id: Uuid::new_v4().to_string(),
+                website_id: window.website_id,
+                title: window.title,
+                starts_at: window.starts_at,
+                duration_minutes: window.duration_minutes,
+                recurrence: window.recurrence,
+                until: window.until,
+                time_added: Utc::now().naive_utc(),
+                user_id: input_user_id,
+                group_id: window.group_id,
+                subscribers_notified_at: None,
+            };
+            diesel::insert_into(crate::schema::maintenance_window::table)
+                .values(&window)
+                .execute(conn).await?;
+
+            Ok(window)
+        }.scope_boxed()).await.map_err(DbError::from)
+    }
+
+    /// The user's maintenance windows, optionally narrowed to those set directly on one website or group.
+    pub async fn get_maintenance_windows(&self, input_user_id: String, input_website_id: Option<String>, input_group_id: Option<String>) -> Result<Vec<MaintenanceWindow>, DbError> {
+        use crate::schema::maintenance_window::dsl::*;
+
+        let mut conn = self.conn().await?;
+        let mut query = maintenance_window
+            .filter(user_id.eq(input_user_id))
+            .select(MaintenanceWindow::as_select())
+            .order(starts_at.asc())
+            .into_boxed();
+        if let Some(input_website_id) = input_website_id {
+            query = query.filter(website_id.eq(input_website_id));
+        }
+        if let Some(input_group_id) = input_group_id {
+            query = query.filter(group_id.eq(input_group_id));
+        }
+        query.load(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Replaces a window's schedule and target. The new target must belong to the user as well.
+    pub async fn update_maintenance_window(&self, input_user_id: String, input_id: String, window: NewMaintenanceWindow) -> Result<MaintenanceWindow, DbError> {
+        use crate::schema::maintenance_window;
+
+        let mut conn = self.conn().await?;
+        conn.transaction::<_, diesel::result::Error, _>(|conn| async move {
+            let existing = maintenance_window::table
+                .filter(maintenance_window::id.eq(&input_id))
+                .filter(maintenance_window::user_id.eq(&input_user_id))
+                .select(MaintenanceWindow::as_select())
+                .first(conn).await?;
+            Self::check_maintenance_target(conn, &input_user_id, &window).await?;
+
+            let updated = MaintenanceWindow {
+                website_id: window.website_id,
+                group_id: window.group_id,
+                title: window.title,
+                starts_at: window.starts_at,
+                duration_minutes: window.duration_minutes,
+                recurrence: window.recurrence,
+                until: window.until,
+                // a new schedule is announced again
+                subscribers_notified_at: None,
+                ..existing
+            };
+            diesel::update(maintenance_window::table.filter(maintenance_window::id.eq(&input_id)))
+                .set(&updated)
+                .execute(conn).await?;
+            Ok(updated)
+        }.scope_boxed()).await.map_err(DbError::from)
+    }
+
+    pub async fn delete_maintenance_window(&self, input_user_id: String, input_id: String) -> Result<usize, DbError> {
+        use crate::schema::maintenance_window::dsl::*;
+
+        let mut conn = self.conn().await?;
+        diesel::delete(maintenance_window.filter(id.eq(input_id)).filter(user_id.eq(input_user_id)))
+            .execute(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Whether a window on the website, its group or any ancestor group covers `at`.
+    pub async fn is_in_maintenance(&self, input_website_id: String, at: NaiveDateTime) -> Result<bool, DbError> {
+        use crate::schema::maintenance_window::dsl::*;
+
+        let groups = self.get_website_group_chain(input_website_id.clone()).await?;
+        let mut conn = self.conn().await?;
+        let windows = maintenance_window
+            .filter(website_id.eq(input_website_id).or(group_id.eq_any(groups)))
+            .filter(starts_at.le(at))
+            .select(MaintenanceWindow::as_select())
+            .load(&mut conn).await?;
+        Ok(windows.iter().any(|w| w.occurrence_at(at).is_some()))
+    }
+
+    /// Windows on any of the websites or on a group containing one of them.
+    pub async fn get_websites_maintenance_windows(&self, website_ids: &[String]) -> Result<Vec<MaintenanceWindow>, DbError> {
+        let mut groups = Vec::new();
+        for input_website_id in website_ids {
+            for group in self.get_website_group_chain(input_website_id.clone()).await? {
+                if !groups.contains(&group) {
+                    groups.push(group);
+                }
+            }
+        }
+
+        use crate::schema::maintenance_window::dsl::*;
+        let mut conn = self.conn().await?;
+        maintenance_window
+            .filter(website_id.eq_any(website_ids).or(group_id.eq_any(groups)))
+            .order(starts_at.asc())
+            .select(MaintenanceWindow::as_select())
+            .load(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Windows whose current schedule hasn't been announced to status page subscribers yet.
+    pub async fn get_unannounced_maintenance_windows(&self) -> Result<Vec<MaintenanceWindow>, DbError> {
+        use crate::schema::maintenance_window::dsl::*;
+
+        let mut conn = self.conn().await?;
+        maintenance_window
+            .filter(subscribers_notified_at.is_null())
+            .select(MaintenanceWindow::as_select())
+            .load(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Returns false when the window changed or was announced by someone else in the meantime.
+    pub async fn mark_maintenance_window_announced(&self, window: &MaintenanceWindow) -> Result<bool, DbError> {
+        use crate::schema::maintenance_window::dsl::*;
+
+        let mut conn = self.conn().await?;
+        let updated = diesel::update(
+            maintenance_window
+                .filter(id.eq(&window.id))
+                .filter(subscribers_notified_at.is_null())
+                .filter(starts_at.eq(window.starts_at))
+                .filter(duration_minutes.eq(window.duration_minutes)),
+        )
+        .set(subscribers_notified_at.eq(Some(Utc::now().naive_utc())))
+        .execute(&mut conn).await?;
+        Ok(updated == 1)
+    }
+
+    /// Websites a window covers: its website, or every website in its group and subgroups.
+    pub async fn get_maintenance_window_websites(&self, window: &MaintenanceWindow) -> Result<Vec<String>, DbError> {
+        if let Some(input_website_id) = &window.website_id {
+            return Ok(vec![input_website_id.clone()]);
+        }
+        let Some(input_group_id) = &window.group_id else {
+            return Ok(Vec::new());
+        };
+
+        let groups = self.get_group_subtree(window.user_id.clone(), input_group_id.clone()).await?;
+        use crate::schema::website;
+
+        let mut conn = self.conn().await?;
+        website::table
+            .filter(website::group_id.eq_any(groups))
+            .select(website::id)
+            .load(&mut conn).await.map_err(DbError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::models::website_group::{group_ancestors, group_subtree, WebsiteGroup};
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 3, day).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn window(recurrence: Option<&str>, until: Option<NaiveDateTime>) -> MaintenanceWindow {
+        MaintenanceWindow {
+            id: "window".to_string(),
+            website_id: Some("website".to_string()),
+            title: "Database upgrade".to_string(),
+            starts_at: at(2, 0, 0),
+            duration_minutes: 60,
+            recurrence: recurrence.map(str::to_string),
+            until,
+            time_added: at(1, 0, 0),
+            user_id: "user".to_string(),
+            group_id: None,
+            subscribers_notified_at: None,
+        }
+    }
+
+    #[test]
+    fn crontab_lines_run_at_second_zero() {
+        let five = parse_recurrence("30 2 * * *").unwrap();
+        let six = parse_recurrence("0 30 2 * * *").unwrap();
+        let from = at(2, 0, 0).and_utc();
+
+        assert!(five.after(&from).take(3).eq(six.after(&from).take(3)));
+        assert_eq!(five.after(&from).next().unwrap().naive_utc(), at(2, 2, 30));
+        assert!(parse_recurrence("every night").is_err());
+    }
+
+    #[test]
+    fn recurring_window_covers_each_occurrence() {
+        let w = window(Some("30 2 * * *"), Some(at(5, 0, 0)));
+
+        assert_eq!(w.occurrence_at(at(3, 2, 30)), Some(at(3, 2, 30)));
+        assert_eq!(w.occurrence_at(at(3, 3, 29)), Some(at(3, 2, 30)));
+        assert_eq!(w.occurrence_at(at(3, 3, 30)), None);
+        assert_eq!(w.occurrence_at(at(3, 2, 29)), None);
+        // before the window takes effect and after `until`
+        assert_eq!(w.occurrence_at(at(1, 2, 45)), None);
+        assert_eq!(w.occurrence_at(at(5, 2, 45)), None);
+    }
+
+    #[test]
+    fn occurrences_stop_at_until() {
+        let w = window(Some("30 2 * * *"), Some(at(5, 0, 0)));
+
+        assert_eq!(
+            w.occurrences_after(at(1, 0, 0), 10),
+            [(at(2, 2, 30), at(2, 3, 30)), (at(3, 2, 30), at(3, 3, 30)), (at(4, 2, 30), at(4, 3, 30))]
+        );
+        assert_eq!(w.occurrences_after(at(3, 12, 0), 1), [(at(4, 2, 30), at(4, 3, 30))]);
+    }
+
+    #[test]
+    fn one_off_window_covers_its_duration() {
+        let w = window(None, None);
+
+        assert_eq!(w.occurrence_at(at(2, 0, 59)), Some(at(2, 0, 0)));
+        assert_eq!(w.occurrence_at(at(2, 1, 0)), None);
+        assert_eq!(w.occurrences_after(at(2, 0, 30), 5), [(at(2, 0, 0), at(2, 1, 0))]);
+        assert!(w.occurrences_after(at(2, 1, 0), 5).is_empty());
+    }
+
+    #[test]
+    fn group_window_covers_subgroups_only() {
+        let group = |id: &str, parent: Option<&str>| WebsiteGroup {
+            id: id.to_string(),
+            user_id: "user".to_string(),
+            name: id.to_string(),
+            parent_id: parent.map(str::to_string),
+            time_added: at(1, 0, 0),
+        };
+        let groups = [group("prod", None), group("eu", Some("prod")), group("eu-west", Some("eu")), group("staging", None)];
+        let w = MaintenanceWindow { website_id: None, group_id: Some("eu".to_string()), ..window(None, None) };
+        let scope = w.group_id.as_deref().unwrap();
+
+        // which groups the window reaches, as when listing the websites it covers
+        let mut covered = group_subtree(&groups, scope);
+        covered.sort();
+        assert_eq!(covered, ["eu", "eu-west"]);
+        // whether it reaches a website's group, as when checking a website is in maintenance
+        assert!(group_ancestors(&groups, Some("eu-west")).iter().any(|g| g == scope));
+        assert!(!group_ancestors(&groups, Some("prod")).iter().any(|g| g == scope));
+        assert!(!group_ancestors(&groups, Some("staging")).iter().any(|g| g == scope));
+    }
+}
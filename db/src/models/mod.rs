@@ -1,2 +1,19 @@
 pub mod user;
-pub mod website;
\ No newline at end of file
+pub mod api_key;
+pub mod website;
+pub mod website_tick;
+pub mod incident;
+pub mod notification_channel;
+pub mod notification_delivery;
+pub mod on_call;
+pub mod team_member;
+pub mod escalation;
+pub mod incident_event;
+pub mod slack_identity;
+pub mod maintenance_window;
+pub mod website_pause;
+pub mod website_dependency;
+pub mod website_group;
+pub mod website_tag;
+pub mod status_page;
+pub mod status_page_subscriber;
\ No newline at end of file
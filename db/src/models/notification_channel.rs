@@ -0,0 +1,146 @@
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::ExpressionMethods;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::db::Db;
+use crate::error::DbError;
+
+pub const CHANNEL_WEBHOOK: &str = "webhook";
+pub const CHANNEL_SLACK: &str = "slack";
+pub const CHANNEL_DISCORD: &str = "discord";
+pub const CHANNEL_TEAMS: &str = "teams";
+pub const CHANNEL_PAGERDUTY: &str = "pagerduty";
+pub const CHANNEL_OPSGENIE: &str = "opsgenie";
+
+/// Kinds whose `target` is the URL the alert is POSTed to.
+pub const URL_CHANNEL_KINDS: [&str; 4] = [CHANNEL_WEBHOOK, CHANNEL_SLACK, CHANNEL_DISCORD, CHANNEL_TEAMS];
+/// Kinds whose `target` is an integration key for a paging service.
+pub const KEY_CHANNEL_KINDS: [&str; 2] = [CHANNEL_PAGERDUTY, CHANNEL_OPSGENIE];
+
+#[derive(Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::notification_channel)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NotificationChannel {
+    pub id: String,
+    pub user_id: String,
+    pub kind: String,
+    /// Where alerts are delivered: the URL of a webhook receiver, or the
+    /// integration key for PagerDuty / Opsgenie.
+    pub target: String,
+    /// Shared secret used to sign outgoing payloads.
+    pub secret: Option<String>,
+    pub time_added: chrono::NaiveDateTime,
+    /// Name given to the channel in a monitors-as-code document, `None` when it isn't managed by one.
+    pub external_key: Option<String>,
+}
+
+impl Db {
+    pub async fn create_notification_channel(&self, user_id: String, kind: String, target: String, secret: Option<String>) -> Result<NotificationChannel, DbError> {
+        let mut conn = self.conn().await?;
+        let secret = match kind.as_str() {
+            CHANNEL_WEBHOOK => Some(secret.unwrap_or_else(|| Uuid::new_v4().simple().to_string())),
+            _ => secret,
+        };
+        let channel = NotificationChannel {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            kind,
+            target,
+            secret,
+            time_added: Utc::now().naive_utc(),
+            external_key: None,
+        };
+        diesel::insert_into(crate::schema::notification_channel::table)
+            .values(&channel)
+            .execute(&mut conn).await?;
+
+        Ok(channel)
+    }
+
+    pub async fn get_notification_channels(&self, input_user_id: String) -> Result<Vec<NotificationChannel>, DbError> {
+        use crate::schema::notification_channel::dsl::*;
+
+        let mut conn = self.conn().await?;
+        notification_channel
+            .filter(user_id.eq(input_user_id))
+            .order(time_added.asc())
+            .select(NotificationChannel::as_select())
+            .load(&mut conn).await.map_err(DbError::from)
+    }
+
+    pub async fn get_notification_channel(&self, input_id: String) -> Result<NotificationChannel, DbError> {
+        use crate::schema::notification_channel::dsl::*;
+
+        let mut conn = self.conn().await?;
+        notification_channel
+            .filter(id.eq(input_id))
+            .select(NotificationChannel::as_select())
+            .first(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Channels belonging to the owner of a website.
+    pub async fn get_website_notification_channels(&self, input_website_id: String) -> Result<Vec<NotificationChannel>, DbError> {
+        use crate::schema::{notification_channel, website};
+
+        let mut conn = self.conn().await?;
+        notification_channel::table
+            .inner_join(website::table.on(website::user_id.eq(notification_channel::user_id)))
+            .filter(website::id.eq(input_website_id))
+            .select(NotificationChannel::as_select())
+            .load(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Changes where a channel delivers. A `None` secret keeps the current one.
+    pub async fn update_notification_channel(&self, input_user_id: String, input_id: String, input_kind: String, input_target: String, input_secret: Option<String>) -> Result<NotificationChannel, DbError> {
+        use crate::schema::notification_channel::dsl::*;
+
+        let mut conn = self.conn().await?;
+        let current = notification_channel
+            .filter(id.eq(&input_id))
+            .filter(user_id.eq(&input_user_id))
+            .select(NotificationChannel::as_select())
+            .first(&mut conn).await?;
+        diesel::update(notification_channel.filter(id.eq(input_id)))
+            .set((kind.eq(input_kind), target.eq(input_target), secret.eq(input_secret.or(current.secret))))
+            .returning(NotificationChannel::as_returning())
+            .get_result(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Names the channel in a monitors-as-code document, or releases it with `None`.
+    pub async fn set_notification_channel_external_key(&self, input_user_id: String, input_id: String, key: Option<String>) -> Result<NotificationChannel, DbError> {
+        use crate::schema::notification_channel::dsl::*;
+
+        let mut conn = self.conn().await?;
+        diesel::update(notification_channel.filter(id.eq(input_id)).filter(user_id.eq(input_user_id)))
+            .set(external_key.eq(key))
+            .returning(NotificationChannel::as_returning())
+            .get_result(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// A `Conflict` naming the escalation policy when one still notifies the channel.
+    pub async fn delete_notification_channel(&self, input_user_id: String, input_id: String) -> Result<usize, DbError> {
+        use crate::schema::notification_channel::dsl::*;
+        use crate::schema::{escalation_level, escalation_policy};
+
+        let mut conn = self.conn().await?;
+        let policy = escalation_level::table
+            .inner_join(escalation_policy::table)
+            .filter(escalation_level::channel_id.eq(&input_id))
+            .filter(escalation_policy::user_id.eq(&input_user_id))
+            .select(escalation_policy::name)
+            .first::<String>(&mut conn).await
+            .optional()?;
+        if let Some(policy) = policy {
+            return Err(DbError::Conflict(format!("escalation policy `{}` notifies this channel", policy)));
+        }
+        diesel::delete(notification_channel.filter(id.eq(input_id)).filter(user_id.eq(input_user_id)))
+            .execute(&mut conn).await
+            .map_err(|e| match DbError::from(e) {
+                // a level added since the check above
+                DbError::InvalidReference(_) => DbError::Conflict("an escalation policy notifies this channel".to_string()),
+                e => e,
+            })
+    }
+}
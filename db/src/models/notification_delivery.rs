@@ -0,0 +1,97 @@
+use diesel::prelude::*;
+use diesel::ExpressionMethods;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::db::{Db, Page};
+use crate::error::DbError;
+
+#[derive(Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::notification_deliveries)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NotificationDelivery {
+    pub id: String,
+    pub incident_id: String,
+    pub channel_id: String,
+    pub attempt: i32,
+    /// HTTP status returned by the receiver, `None` when the request never got a response.
+    pub status_code: Option<i32>,
+    pub latency_ms: i32,
+    pub error: Option<String>,
+    pub attempted_at: chrono::NaiveDateTime,
+}
+
+/// Result of a single delivery attempt as reported by the notifier.
+pub struct DeliveryOutcome {
+    pub attempt: i32,
+    pub status_code: Option<i32>,
+    pub latency_ms: i32,
+    pub error: Option<String>,
+    pub attempted_at: chrono::NaiveDateTime,
+}
+
+impl Db {
+    pub async fn record_notification_delivery(&self, incident_id: String, channel_id: String, outcome: DeliveryOutcome) -> Result<NotificationDelivery, DbError> {
+        let mut conn = self.conn().await?;
+        let delivery = NotificationDelivery {
+            id: Uuid::new_v4().to_string(),
+            incident_id,
+            channel_id,
+            attempt: outcome.attempt,
+            status_code: outcome.status_code,
+            latency_ms: outcome.latency_ms,
+            error: outcome.error,
+            attempted_at: outcome.attempted_at,
+        };
+        diesel::insert_into(crate::schema::notification_deliveries::table)
+            .values(&delivery)
+            .execute(&mut conn).await?;
+
+        Ok(delivery)
+    }
+
+    /// Delivery history of an incident on one of the user's websites, oldest first.
+    pub async fn get_incident_deliveries(&self, input_user_id: String, input_incident_id: String) -> Result<Vec<NotificationDelivery>, DbError> {
+        use crate::schema::{incident, notification_deliveries, website};
+
+        let mut conn = self.conn().await?;
+        notification_deliveries::table
+            .inner_join(incident::table.inner_join(website::table))
+            .filter(incident::id.eq(input_incident_id))
+            .filter(website::user_id.eq(input_user_id))
+            .order(notification_deliveries::attempted_at.asc())
+            .select(NotificationDelivery::as_select())
+            .load(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Most recent deliveries through one of the user's channels, newest first.
+    /// `page.after` must be a delivery through the channel.
+    pub async fn get_channel_deliveries(&self, input_user_id: String, input_channel_id: String, page: &Page) -> Result<Vec<NotificationDelivery>, DbError> {
+        use crate::schema::{notification_channel, notification_deliveries};
+
+        let mut conn = self.conn().await?;
+        let mut query = notification_deliveries::table
+            .inner_join(notification_channel::table)
+            .filter(notification_channel::id.eq(&input_channel_id))
+            .filter(notification_channel::user_id.eq(&input_user_id))
+            .order((notification_deliveries::attempted_at.desc(), notification_deliveries::id.desc()))
+            .select(NotificationDelivery::as_select())
+            .into_boxed();
+        if let Some(after) = &page.after {
+            let after_attempted: chrono::NaiveDateTime = notification_deliveries::table
+                .filter(notification_deliveries::id.eq(after))
+                .filter(notification_deliveries::channel_id.eq(&input_channel_id))
+                .select(notification_deliveries::attempted_at)
+                .first(&mut conn).await?;
+            query = query.filter(
+                notification_deliveries::attempted_at
+                    .lt(after_attempted)
+                    .or(notification_deliveries::attempted_at.eq(after_attempted).and(notification_deliveries::id.lt(after.clone()))),
+            );
+        }
+        if let Some(limit) = page.limit {
+            query = query.limit(limit);
+        }
+        query.load(&mut conn).await.map_err(DbError::from)
+    }
+}
@@ -0,0 +1,223 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use diesel::ExpressionMethods;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::db::Db;
+use crate::error::DbError;
+
+pub const ROTATION_DAILY: &str = "daily";
+pub const ROTATION_WEEKLY: &str = "weekly";
+
+#[derive(Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::on_call_schedule)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct OnCallSchedule {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    /// `daily` or `weekly`: how long each participant stays on call.
+    pub rotation: String,
+    /// When the first participant's shift starts; every later shift is derived from it.
+    pub rotation_start: NaiveDateTime,
+    /// User ids in rotation order.
+    pub participants: Vec<String>,
+}
+
+#[derive(Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::on_call_override)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct OnCallOverride {
+    pub id: String,
+    pub schedule_id: String,
+    pub user_id: String,
+    pub starts_at: NaiveDateTime,
+    pub ends_at: NaiveDateTime,
+}
+
+impl OnCallSchedule {
+    pub fn shift_length(&self) -> Duration {
+        match self.rotation.as_str() {
+            ROTATION_DAILY => Duration::days(1),
+            _ => Duration::weeks(1),
+        }
+    }
+
+    /// Who is on call at `at`: an override covering that moment wins, otherwise
+    /// the participant whose turn it is in the rotation.
+    pub fn on_call_at(&self, overrides: &[OnCallOverride], at: NaiveDateTime) -> Option<String> {
+        if let Some(o) = overrides.iter().find(|o| o.starts_at <= at && at < o.ends_at) {
+            return Some(o.user_id.clone());
+        }
+        if self.participants.is_empty() || at < self.rotation_start {
+            return None;
+        }
+
+        let shifts = (at - self.rotation_start).num_seconds() / self.shift_length().num_seconds();
+        let index = (shifts % self.participants.len() as i64) as usize;
+        Some(self.participants[index].clone())
+    }
+}
+
+impl Db {
+    pub async fn create_on_call_schedule(&self, user_id: String, name: String, rotation: String, rotation_start: NaiveDateTime, participants: Vec<String>) -> Result<OnCallSchedule, DbError> {
+        let mut conn = self.conn().await?;
+        let schedule = OnCallSchedule {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            name,
+            rotation,
+            rotation_start,
+            participants,
+        };
+        diesel::insert_into(crate::schema::on_call_schedule::table)
+            .values(&schedule)
+            .execute(&mut conn).await?;
+
+        Ok(schedule)
+    }
+
+    pub async fn get_on_call_schedules(&self, input_user_id: String) -> Result<Vec<OnCallSchedule>, DbError> {
+        use crate::schema::on_call_schedule::dsl::*;
+
+        let mut conn = self.conn().await?;
+        on_call_schedule
+            .filter(user_id.eq(input_user_id))
+            .order(name.asc())
+            .select(OnCallSchedule::as_select())
+            .load(&mut conn).await.map_err(DbError::from)
+    }
+
+    pub async fn get_on_call_schedule(&self, input_user_id: String, input_id: String) -> Result<OnCallSchedule, DbError> {
+        use crate::schema::on_call_schedule::dsl::*;
+
+        let mut conn = self.conn().await?;
+        on_call_schedule
+            .filter(id.eq(input_id))
+            .filter(user_id.eq(input_user_id))
+            .select(OnCallSchedule::as_select())
+            .first(&mut conn).await.map_err(DbError::from)
+    }
+
+    pub async fn create_on_call_override(&self, schedule_id: String, user_id: String, starts_at: NaiveDateTime, ends_at: NaiveDateTime) -> Result<OnCallOverride, DbError> {
+        let mut conn = self.conn().await?;
+        let on_call_override = OnCallOverride {
+            id: Uuid::new_v4().to_string(),
+            schedule_id,
+            user_id,
+            starts_at,
+            ends_at,
+        };
+        diesel::insert_into(crate::schema::on_call_override::table)
+            .values(&on_call_override)
+            .execute(&mut conn).await?;
+
+        Ok(on_call_override)
+    }
+
+    /// Overrides of a schedule that haven't ended yet.
+    pub async fn get_on_call_overrides(&self, input_schedule_id: String) -> Result<Vec<OnCallOverride>, DbError> {
+        use crate::schema::on_call_override::dsl::*;
+
+        let mut conn = self.conn().await?;
+        on_call_override
+            .filter(schedule_id.eq(input_schedule_id))
+            .filter(ends_at.gt(Utc::now().naive_utc()))
+            .order(starts_at.asc())
+            .select(OnCallOverride::as_select())
+            .load(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Resolves the user currently on call for a schedule.
+    pub async fn get_current_on_call(&self, input_schedule_id: String) -> Result<Option<String>, DbError> {
+        let schedule = {
+            use crate::schema::on_call_schedule::dsl::*;
+
+            let mut conn = self.conn().await?;
+            on_call_schedule
+                .filter(id.eq(input_schedule_id.clone()))
+                .select(OnCallSchedule::as_select())
+                .first(&mut conn).await?
+        };
+        let overrides = self.get_on_call_overrides(input_schedule_id).await?;
+
+        Ok(schedule.on_call_at(&overrides, Utc::now().naive_utc()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn at(day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 3, day).unwrap().and_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    fn schedule(rotation: &str, participants: &[&str]) -> OnCallSchedule {
+        OnCallSchedule {
+            id: "schedule".to_string(),
+            user_id: "alice".to_string(),
+            name: "Primary".to_string(),
+            rotation: rotation.to_string(),
+            rotation_start: at(2, 9),
+            participants: participants.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    fn on_call_override(user_id: &str, starts_at: NaiveDateTime, ends_at: NaiveDateTime) -> OnCallOverride {
+        OnCallOverride {
+            id: "override".to_string(),
+            schedule_id: "schedule".to_string(),
+            user_id: user_id.to_string(),
+            starts_at,
+            ends_at,
+        }
+    }
+
+    #[test]
+    fn daily_rotation_hands_over_at_the_start_time() {
+        let schedule = schedule(ROTATION_DAILY, &["alice", "bob", "carol"]);
+
+        assert_eq!(schedule.on_call_at(&[], at(2, 9)).as_deref(), Some("alice"));
+        assert_eq!(schedule.on_call_at(&[], at(3, 8)).as_deref(), Some("alice"));
+        assert_eq!(schedule.on_call_at(&[], at(3, 9)).as_deref(), Some("bob"));
+        assert_eq!(schedule.on_call_at(&[], at(4, 9)).as_deref(), Some("carol"));
+        assert_eq!(schedule.on_call_at(&[], at(5, 9)).as_deref(), Some("alice"));
+    }
+
+    #[test]
+    fn weekly_rotation_wraps_around() {
+        let schedule = schedule(ROTATION_WEEKLY, &["alice", "bob"]);
+
+        assert_eq!(schedule.on_call_at(&[], at(8, 23)).as_deref(), Some("alice"));
+        assert_eq!(schedule.on_call_at(&[], at(9, 9)).as_deref(), Some("bob"));
+        assert_eq!(schedule.on_call_at(&[], at(16, 9)).as_deref(), Some("alice"));
+    }
+
+    #[test]
+    fn nobody_before_the_rotation_starts_or_without_participants() {
+        assert_eq!(schedule(ROTATION_DAILY, &["alice"]).on_call_at(&[], at(2, 8)), None);
+        assert_eq!(schedule(ROTATION_DAILY, &[]).on_call_at(&[], at(3, 9)), None);
+    }
+
+    #[test]
+    fn overrides_win_while_they_last() {
+        let schedule = schedule(ROTATION_DAILY, &["alice", "bob"]);
+        let overrides = [on_call_override("carol", at(3, 12), at(3, 18))];
+
+        assert_eq!(schedule.on_call_at(&overrides, at(3, 11)).as_deref(), Some("bob"));
+        assert_eq!(schedule.on_call_at(&overrides, at(3, 12)).as_deref(), Some("carol"));
+        assert_eq!(schedule.on_call_at(&overrides, at(3, 18)).as_deref(), Some("bob"));
+    }
+
+    #[test]
+    fn overrides_cover_gaps_in_the_rotation() {
+        let schedule = schedule(ROTATION_DAILY, &[]);
+        let overrides = [on_call_override("carol", at(1, 0), at(2, 0))];
+
+        assert_eq!(schedule.on_call_at(&overrides, at(1, 12)).as_deref(), Some("carol"));
+    }
+}
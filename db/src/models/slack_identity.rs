@@ -0,0 +1,53 @@
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::ExpressionMethods;
+use diesel_async::RunQueryDsl;
+
+use crate::db::Db;
+use crate::error::DbError;
+
+/// Links a Slack user in a workspace to the account their slash commands act as.
+#[derive(Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::slack_identity)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct SlackIdentity {
+    pub team_id: String,
+    pub slack_user_id: String,
+    pub user_id: String,
+    pub time_added: chrono::NaiveDateTime,
+}
+
+impl Db {
+    /// Links (or re-links) a Slack user to an account.
+    pub async fn link_slack_user(&self, input_team_id: String, input_slack_user_id: String, input_user_id: String) -> Result<SlackIdentity, DbError> {
+        use crate::schema::slack_identity::dsl::*;
+
+        let mut conn = self.conn().await?;
+        let identity = SlackIdentity {
+            team_id: input_team_id,
+            slack_user_id: input_slack_user_id,
+            user_id: input_user_id,
+            time_added: Utc::now().naive_utc(),
+        };
+        diesel::insert_into(slack_identity)
+            .values(&identity)
+            .on_conflict((team_id, slack_user_id))
+            .do_update()
+            .set((user_id.eq(&identity.user_id), time_added.eq(identity.time_added)))
+            .execute(&mut conn).await?;
+
+        Ok(identity)
+    }
+
+    pub async fn get_slack_user(&self, input_team_id: String, input_slack_user_id: String) -> Result<Option<String>, DbError> {
+        use crate::schema::slack_identity::dsl::*;
+
+        let mut conn = self.conn().await?;
+        slack_identity
+            .filter(team_id.eq(input_team_id))
+            .filter(slack_user_id.eq(input_slack_user_id))
+            .select(user_id)
+            .first(&mut conn).await
+            .optional().map_err(DbError::from)
+    }
+}
@@ -0,0 +1,187 @@
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::ExpressionMethods;
+use diesel_async::{RunQueryDsl, AsyncConnection, AsyncPgConnection};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use uuid::Uuid;
+
+use crate::db::Db;
+use crate::error::DbError;
+
+/// A public page at `/status-page/<slug>` showing some of a user's websites.
+#[derive(Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::status_page)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct StatusPage {
+    pub id: String,
+    pub user_id: String,
+    pub slug: String,
+    pub title: String,
+    pub logo_url: Option<String>,
+    pub time_added: chrono::NaiveDateTime,
+    /// Name given to the page in a monitors-as-code document, `None` when it isn't managed by one.
+    pub external_key: Option<String>,
+}
+
+/// A website as it appears on a status page, under a customer-facing name.
+#[derive(Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::status_page_component)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct StatusPageComponent {
+    pub id: String,
+    pub status_page_id: String,
+    pub website_id: String,
+    pub name: String,
+    pub position: i32,
+}
+
+pub struct NewStatusPage {
+    pub slug: String,
+    pub title: String,
+    pub logo_url: Option<String>,
+    /// (website id, display name) in display order.
+    pub components: Vec<(String, String)>,
+}
+
+impl Db {
+    async fn insert_status_page_components(conn: &mut AsyncPgConnection, input_user_id: &str, page_id: &str, components: Vec<(String, String)>) -> Result<Vec<StatusPageComponent>, diesel::result::Error> {
+        use crate::schema::website;
+
+        let mut ids: Vec<&String> = components.iter().map(|(website_id, _)| website_id).collect();
+        ids.sort();
+        ids.dedup();
+        let owned: i64 = website::table
+            .filter(website::id.eq_any(&ids))
+            .filter(website::user_id.eq(input_user_id))
+            .count()
+            .get_result(conn).await?;
+        if owned != ids.len() as i64 {
+            return Err(diesel::result::Error::NotFound);
+        }
+
+        let components: Vec<StatusPageComponent> = components
+            .into_iter()
+            .enumerate()
+            .map(|(position, (website_id, name))| StatusPageComponent {
+                id: Uuid::new_v4().to_string(),
+                status_page_id: page_id.to_string(),
+                website_id,
+                name,
+                position: position as i32,
+            })
+            .collect();
+        diesel::insert_into(crate::schema::status_page_component::table)
+            .values(&components)
+            .execute(conn).await?;
+        Ok(components)
+    }
+
+    /// Creates a page. Every component's website must belong to the user; a taken
+    /// slug fails with a unique violation.
+    pub async fn create_status_page(&self, input_user_id: String, page: NewStatusPage) -> Result<(StatusPage, Vec<StatusPageComponent>), DbError> {
+        let mut conn = self.conn().await?;
+        conn.transaction::<_, diesel::result::Error, _>(|conn| async move {
+            let status_page = StatusPage {
+                id: Uuid::new_v4().to_string(),
+                user_id: input_user_id.clone(),
+                slug: page.slug,
+                title: page.title,
+                logo_url: page.logo_url,
+                time_added: Utc::now().naive_utc(),
+                external_key: None,
+            };
+            diesel::insert_into(crate::schema::status_page::table)
+                .values(&status_page)
+                .execute(conn).await?;
+            let components = Self::insert_status_page_components(conn, &input_user_id, &status_page.id, page.components).await?;
+
+            Ok((status_page, components))
+        }.scope_boxed()).await.map_err(DbError::from)
+    }
+
+    /// Replaces a page's settings and components.
+    pub async fn update_status_page(&self, input_user_id: String, input_id: String, page: NewStatusPage) -> Result<(StatusPage, Vec<StatusPageComponent>), DbError> {
+        use crate::schema::{status_page, status_page_component};
+
+        let mut conn = self.conn().await?;
+        conn.transaction::<_, diesel::result::Error, _>(|conn| async move {
+            let updated = diesel::update(status_page::table.filter(status_page::id.eq(&input_id)).filter(status_page::user_id.eq(&input_user_id)))
+                .set((
+                    status_page::slug.eq(page.slug),
+                    status_page::title.eq(page.title),
+                    status_page::logo_url.eq(page.logo_url),
+                ))
+                .returning(StatusPage::as_returning())
+                .get_result(conn).await?;
+            diesel::delete(status_page_component::table.filter(status_page_component::status_page_id.eq(&input_id))).execute(conn).await?;
+            let components = Self::insert_status_page_components(conn, &input_user_id, &input_id, page.components).await?;
+
+            Ok((updated, components))
+        }.scope_boxed()).await.map_err(DbError::from)
+    }
+
+    pub async fn get_status_pages(&self, input_user_id: String) -> Result<Vec<(StatusPage, Vec<StatusPageComponent>)>, DbError> {
+        let pages = {
+            use crate::schema::status_page::dsl::*;
+
+            let mut conn = self.conn().await?;
+            status_page
+                .filter(user_id.eq(input_user_id))
+                .order(slug.asc())
+                .select(StatusPage::as_select())
+                .load(&mut conn).await?
+        };
+
+        let mut result = Vec::with_capacity(pages.len());
+        for page in pages {
+            let components = self.get_status_page_components(page.id.clone()).await?;
+            result.push((page, components));
+        }
+        Ok(result)
+    }
+
+    pub async fn get_status_page_components(&self, input_status_page_id: String) -> Result<Vec<StatusPageComponent>, DbError> {
+        use crate::schema::status_page_component::dsl::*;
+
+        let mut conn = self.conn().await?;
+        status_page_component
+            .filter(status_page_id.eq(input_status_page_id))
+            .order(position.asc())
+            .select(StatusPageComponent::as_select())
+            .load(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Public lookup, no ownership check.
+    pub async fn get_status_page_by_slug(&self, input_slug: String) -> Result<(StatusPage, Vec<StatusPageComponent>), DbError> {
+        let page = {
+            use crate::schema::status_page::dsl::*;
+
+            let mut conn = self.conn().await?;
+            status_page
+                .filter(slug.eq(input_slug))
+                .select(StatusPage::as_select())
+                .first(&mut conn).await?
+        };
+        let components = self.get_status_page_components(page.id.clone()).await?;
+        Ok((page, components))
+    }
+
+    /// Names the page in a monitors-as-code document, or releases it with `None`.
+    pub async fn set_status_page_external_key(&self, input_user_id: String, input_id: String, key: Option<String>) -> Result<StatusPage, DbError> {
+        use crate::schema::status_page::dsl::*;
+
+        let mut conn = self.conn().await?;
+        diesel::update(status_page.filter(id.eq(input_id)).filter(user_id.eq(input_user_id)))
+            .set(external_key.eq(key))
+            .returning(StatusPage::as_returning())
+            .get_result(&mut conn).await.map_err(DbError::from)
+    }
+
+    pub async fn delete_status_page(&self, input_user_id: String, input_id: String) -> Result<usize, DbError> {
+        use crate::schema::status_page::dsl::*;
+
+        let mut conn = self.conn().await?;
+        diesel::delete(status_page.filter(id.eq(input_id)).filter(user_id.eq(input_user_id)))
+            .execute(&mut conn).await.map_err(DbError::from)
+    }
+}
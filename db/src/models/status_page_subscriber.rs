@@ -0,0 +1,199 @@
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::ExpressionMethods;
+use diesel_async::{RunQueryDsl, AsyncConnection};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use uuid::Uuid;
+
+use crate::db::Db;
+use crate::error::DbError;
+use crate::models::status_page::{StatusPage, StatusPageComponent};
+
+pub const SUBSCRIBER_EMAIL: &str = "email";
+pub const SUBSCRIBER_WEBHOOK: &str = "webhook";
+
+/// Someone following a status page by email or webhook.
+#[derive(Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::status_page_subscriber)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct StatusPageSubscriber {
+    pub id: String,
+    pub status_page_id: String,
+    /// `email` or `webhook`.
+    pub kind: String,
+    /// Email address or webhook url.
+    pub target: String,
+    /// Components to hear about, every component when empty.
+    pub component_ids: Vec<String>,
+    /// Secret behind the confirmation and unsubscribe links.
+    pub token: String,
+    /// Webhook signing secret, same scheme as alert webhooks.
+    pub secret: Option<String>,
+    /// Nothing is sent but the confirmation request until the email's link is opened
+    /// or the webhook receiver posts to it.
+    pub confirmed_at: Option<chrono::NaiveDateTime>,
+    pub time_added: chrono::NaiveDateTime,
+    /// When the last confirmation request went out, to limit resends.
+    pub confirmation_sent_at: Option<chrono::NaiveDateTime>,
+}
+
+impl StatusPageSubscriber {
+    pub fn follows(&self, component_id: &str) -> bool {
+        self.component_ids.is_empty() || self.component_ids.iter().any(|c| c == component_id)
+    }
+}
+
+impl Db {
+    /// Subscribes to a page. Returns false with the existing subscription when the
+    /// target already follows the page; an unconfirmed one gets the new choice of components.
+    pub async fn create_status_page_subscriber(&self, input_status_page_id: String, input_kind: &str, input_target: String, input_component_ids: Vec<String>) -> Result<(StatusPageSubscriber, bool), DbError> {
+        use crate::schema::status_page_subscriber::dsl::*;
+
+        let mut conn = self.conn().await?;
+        conn.transaction::<_, diesel::result::Error, _>(|conn| async move {
+            let existing = status_page_subscriber
+                .filter(status_page_id.eq(&input_status_page_id))
+                .filter(kind.eq(input_kind))
+                .filter(target.eq(&input_target))
+                .select(StatusPageSubscriber::as_select())
+                .first(conn).await
+                .optional()?;
+            if let Some(existing) = existing {
+                if existing.confirmed_at.is_some() {
+                    return Ok((existing, false));
+                }
+                let updated = diesel::update(status_page_subscriber.filter(id.eq(existing.id)))
+                    .set(component_ids.eq(input_component_ids))
+                    .returning(StatusPageSubscriber::as_returning())
+                    .get_result(conn).await?;
+                return Ok((updated, false));
+            }
+
+            let now = Utc::now().naive_utc();
+            let subscriber = StatusPageSubscriber {
+                id: Uuid::new_v4().to_string(),
+                status_page_id: input_status_page_id,
+                kind: input_kind.to_string(),
+                target: input_target,
+                component_ids: input_component_ids,
+                token: format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple()),
+                secret: (input_kind == SUBSCRIBER_WEBHOOK).then(|| Uuid::new_v4().simple().to_string()),
+                confirmed_at: None,
+                time_added: now,
+                confirmation_sent_at: None,
+            };
+            diesel::insert_into(status_page_subscriber)
+                .values(&subscriber)
+                .execute(conn).await?;
+            Ok((subscriber, true))
+        }.scope_boxed()).await.map_err(DbError::from)
+    }
+
+    pub async fn get_status_page_subscriber(&self, input_id: String) -> Result<StatusPageSubscriber, DbError> {
+        use crate::schema::status_page_subscriber::dsl::*;
+
+        let mut conn = self.conn().await?;
+        status_page_subscriber
+            .filter(id.eq(input_id))
+            .select(StatusPageSubscriber::as_select())
+            .first(&mut conn).await.map_err(DbError::from)
+    }
+
+    pub async fn get_status_page_subscriber_by_token(&self, input_token: String) -> Result<StatusPageSubscriber, DbError> {
+        use crate::schema::status_page_subscriber::dsl::*;
+
+        let mut conn = self.conn().await?;
+        status_page_subscriber
+            .filter(token.eq(input_token))
+            .select(StatusPageSubscriber::as_select())
+            .first(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Double opt-in: marks the subscription behind the link as confirmed.
+    pub async fn confirm_status_page_subscriber(&self, input_token: String) -> Result<StatusPageSubscriber, DbError> {
+        use crate::schema::status_page_subscriber::dsl::*;
+
+        let subscriber = self.get_status_page_subscriber_by_token(input_token).await?;
+        let mut conn = self.conn().await?;
+        if subscriber.confirmed_at.is_some() {
+            return Ok(subscriber);
+        }
+        diesel::update(status_page_subscriber.filter(id.eq(subscriber.id)))
+            .set(confirmed_at.eq(Some(Utc::now().naive_utc())))
+            .returning(StatusPageSubscriber::as_returning())
+            .get_result(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Whether a confirmation request may go out to an unconfirmed subscriber now, recording
+    /// it when it may. At most one goes to a target per `interval`, whichever page it's for,
+    /// so the public subscribe endpoint can't be used to flood an inbox or a webhook.
+    pub async fn claim_subscriber_confirmation(&self, subscriber: &StatusPageSubscriber, interval: chrono::Duration) -> Result<bool, DbError> {
+        use crate::schema::status_page_subscriber::dsl::*;
+
+        let subscriber_id = subscriber.id.clone();
+        let input_kind = subscriber.kind.clone();
+        let input_target = subscriber.target.clone();
+        let mut conn = self.conn().await?;
+        conn.transaction::<_, diesel::result::Error, _>(|conn| async move {
+            let now = Utc::now().naive_utc();
+            // serializes concurrent claims for the same target
+            let targets: Vec<String> = status_page_subscriber
+                .filter(kind.eq(&input_kind))
+                .filter(target.eq(&input_target))
+                .select(id)
+                .for_update()
+                .load(conn).await?;
+            let recent: i64 = status_page_subscriber
+                .filter(id.eq_any(&targets))
+                .filter(confirmation_sent_at.gt(now - interval))
+                .count()
+                .get_result(conn).await?;
+            if recent > 0 {
+                return Ok(false);
+            }
+            let claimed = diesel::update(status_page_subscriber.filter(id.eq(&subscriber_id)).filter(confirmed_at.is_null()))
+                .set(confirmation_sent_at.eq(Some(now)))
+                .execute(conn).await?;
+            Ok(claimed == 1)
+        }.scope_boxed()).await.map_err(DbError::from)
+    }
+
+    pub async fn delete_status_page_subscriber(&self, input_token: String) -> Result<usize, DbError> {
+        use crate::schema::status_page_subscriber::dsl::*;
+
+        let mut conn = self.conn().await?;
+        diesel::delete(status_page_subscriber.filter(token.eq(input_token)))
+            .execute(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Confirmed subscribers following a component backed by one of the websites,
+    /// each with their page and the first matching component.
+    pub async fn get_website_subscribers(&self, website_ids: &[String]) -> Result<Vec<(StatusPageSubscriber, StatusPage, StatusPageComponent)>, DbError> {
+        use crate::schema::{status_page, status_page_component, status_page_subscriber};
+
+        let mut conn = self.conn().await?;
+        let components: Vec<(StatusPageComponent, StatusPage)> = status_page_component::table
+            .inner_join(status_page::table)
+            .filter(status_page_component::website_id.eq_any(website_ids))
+            .order(status_page_component::position.asc())
+            .select((StatusPageComponent::as_select(), StatusPage::as_select()))
+            .load(&mut conn).await?;
+        let page_ids: Vec<&String> = components.iter().map(|(_, page)| &page.id).collect();
+        let subscribers: Vec<StatusPageSubscriber> = status_page_subscriber::table
+            .filter(status_page_subscriber::status_page_id.eq_any(page_ids))
+            .filter(status_page_subscriber::confirmed_at.is_not_null())
+            .select(StatusPageSubscriber::as_select())
+            .load(&mut conn).await?;
+
+        Ok(subscribers
+            .into_iter()
+            .filter_map(|subscriber| {
+                let (component, page) = components
+                    .iter()
+                    .find(|(component, page)| page.id == subscriber.status_page_id && subscriber.follows(&component.id))?
+                    .clone();
+                Some((subscriber, page, component))
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,179 @@
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::ExpressionMethods;
+use diesel::PgArrayExpressionMethods;
+use diesel::result::DatabaseErrorKind;
+use diesel_async::{RunQueryDsl, AsyncConnection};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use uuid::Uuid;
+
+use crate::db::Db;
+use crate::error::DbError;
+
+/// A user invited into another user's team, the owner's. Members can be put on the owner's
+/// on-call schedules once they've accepted; until then `accepted_at` is `None`.
+#[derive(Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::team_member)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct TeamMember {
+    pub id: String,
+    pub owner_id: String,
+    pub member_id: String,
+    pub invited_at: chrono::NaiveDateTime,
+    pub accepted_at: Option<chrono::NaiveDateTime>,
+}
+
+/// A membership with the names of both users, for listings.
+pub struct TeamMembership {
+    pub member: TeamMember,
+    pub owner_username: String,
+    pub member_username: String,
+}
+
+impl Db {
+    /// Invites the user called `username` into the owner's team. Inviting yourself or
+    /// someone already invited is a `Conflict`.
+    pub async fn invite_team_member(&self, input_owner_id: String, username: String) -> Result<TeamMembership, DbError> {
+        use crate::schema::{team_member, user};
+
+        let mut conn = self.conn().await?;
+        let input_member_id: String = user::table
+            .filter(user::username.eq(&username))
+            .select(user::id)
+            .first(&mut conn).await?;
+        if input_member_id == input_owner_id {
+            return Err(DbError::Conflict("you can't invite yourself".to_string()));
+        }
+        let owner_username: String = user::table
+            .filter(user::id.eq(&input_owner_id))
+            .select(user::username)
+            .first(&mut conn).await?;
+
+        let member = TeamMember {
+            id: Uuid::new_v4().to_string(),
+            owner_id: input_owner_id,
+            member_id: input_member_id,
+            invited_at: Utc::now().naive_utc(),
+            accepted_at: None,
+        };
+        diesel::insert_into(team_member::table)
+            .values(&member)
+            .execute(&mut conn).await
+            .map_err(|e| match e {
+                diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => DbError::Conflict(format!("{} is already invited", username)),
+                e => DbError::from(e),
+            })?;
+
+        Ok(TeamMembership { member, owner_username, member_username: username })
+    }
+
+    /// Everyone the user invited, accepted or not.
+    pub async fn get_team_members(&self, input_owner_id: String) -> Result<Vec<TeamMembership>, DbError> {
+        self.get_team_memberships(input_owner_id, true).await
+    }
+
+    /// Invitations into other users' teams, accepted or not.
+    pub async fn get_team_invitations(&self, input_member_id: String) -> Result<Vec<TeamMembership>, DbError> {
+        self.get_team_memberships(input_member_id, false).await
+    }
+
+    async fn get_team_memberships(&self, input_user_id: String, as_owner: bool) -> Result<Vec<TeamMembership>, DbError> {
+        use crate::schema::{team_member, user};
+
+        let (owner, member) = diesel::alias!(user as owner, user as member);
+        let mut conn = self.conn().await?;
+        let mut query = team_member::table
+            .inner_join(owner.on(owner.field(user::id).eq(team_member::owner_id)))
+            .inner_join(member.on(member.field(user::id).eq(team_member::member_id)))
+            .into_boxed();
+        query = if as_owner {
+            query.filter(team_member::owner_id.eq(input_user_id))
+        } else {
+            query.filter(team_member::member_id.eq(input_user_id))
+        };
+        let rows: Vec<(TeamMember, String, String)> = query
+            .order(team_member::invited_at.asc())
+            .select((TeamMember::as_select(), owner.field(user::username), member.field(user::username)))
+            .load(&mut conn).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(member, owner_username, member_username)| TeamMembership { member, owner_username, member_username })
+            .collect())
+    }
+
+    /// Accepts an invitation addressed to the user. Accepting twice keeps the first time.
+    pub async fn accept_team_invitation(&self, input_member_id: String, input_id: String) -> Result<TeamMembership, DbError> {
+        {
+            use crate::schema::team_member::dsl::*;
+
+            let mut conn = self.conn().await?;
+            let updated = diesel::update(team_member.filter(id.eq(&input_id)).filter(member_id.eq(&input_member_id)).filter(accepted_at.is_null()))
+                .set(accepted_at.eq(Some(Utc::now().naive_utc())))
+                .execute(&mut conn).await?;
+            if updated == 0 {
+                // already accepted, or not an invitation to this user
+                team_member
+                    .filter(id.eq(&input_id))
+                    .filter(member_id.eq(&input_member_id))
+                    .select(id)
+                    .first::<String>(&mut conn).await?;
+            }
+        }
+
+        self.get_team_invitations(input_member_id)
+            .await?
+            .into_iter()
+            .find(|m| m.member.id == input_id)
+            .ok_or(DbError::NotFound)
+    }
+
+    /// Ids of the users who accepted the owner's invitations.
+    pub async fn get_team_member_ids(&self, input_owner_id: String) -> Result<Vec<String>, DbError> {
+        use crate::schema::team_member::dsl::*;
+
+        let mut conn = self.conn().await?;
+        team_member
+            .filter(owner_id.eq(input_owner_id))
+            .filter(accepted_at.is_not_null())
+            .select(member_id)
+            .load(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Ends a membership, by the owner or by the member leaving. A `Conflict` names the
+    /// owner's schedule the member is still on, as a participant or with an override to come.
+    pub async fn delete_team_member(&self, input_user_id: String, input_id: String) -> Result<(), DbError> {
+        use crate::schema::{on_call_override, on_call_schedule, team_member};
+
+        let mut conn = self.conn().await?;
+        conn.transaction::<_, DbError, _>(|conn| async move {
+            let membership: TeamMember = team_member::table
+                .filter(team_member::id.eq(&input_id))
+                .filter(team_member::owner_id.eq(&input_user_id).or(team_member::member_id.eq(&input_user_id)))
+                .select(TeamMember::as_select())
+                .for_update()
+                .first(conn).await?;
+
+            let mut schedules: Vec<String> = on_call_schedule::table
+                .filter(on_call_schedule::user_id.eq(&membership.owner_id))
+                .filter(on_call_schedule::participants.contains(vec![membership.member_id.clone()]))
+                .select(on_call_schedule::name)
+                .load(conn).await?;
+            schedules.extend(
+                on_call_override::table
+                    .inner_join(on_call_schedule::table)
+                    .filter(on_call_schedule::user_id.eq(&membership.owner_id))
+                    .filter(on_call_override::user_id.eq(&membership.member_id))
+                    .filter(on_call_override::ends_at.gt(Utc::now().naive_utc()))
+                    .select(on_call_schedule::name)
+                    .load::<String>(conn).await?,
+            );
+            if let Some(schedule) = schedules.pop() {
+                return Err(DbError::Conflict(format!("the member is still on call in schedule `{}`", schedule)));
+            }
+
+            diesel::delete(team_member::table.filter(team_member::id.eq(&membership.id))).execute(conn).await?;
+            Ok(())
+        }.scope_boxed()).await
+    }
+}
@@ -1,6 +1,8 @@
 use diesel::prelude::*;
 use diesel::ExpressionMethods; use uuid::Uuid;
+use diesel_async::RunQueryDsl;
 use crate::db::Db;
+use crate::error::DbError;
 
 
 #[derive(Queryable, Insertable,Selectable)]
@@ -9,37 +11,68 @@ use crate::db::Db;
 pub struct User{
     id:String,
     username:String,
-    password:String
+    password:String,
+    /// Unlocks the account's read-only feeds without signing in.
+    feed_token:Option<String>
 }
 
 impl Db{
-    pub fn sign_up(&mut self,username:String,password:String)->Result<String,diesel::result::Error>{
+    pub async fn sign_up(&self,username:String,password:String)->Result<String,DbError>{
+        let mut conn = self.conn().await?;
         let id=Uuid::new_v4();
         let u=User{
         id:id.to_string(),
         username,
         password,
+        feed_token:None,
         };
 
         diesel::insert_into(crate::schema::user::table)
         .values(&u)
         .returning(User::as_returning())
-        .get_result(&mut self.conn)?;
+        .get_result(&mut conn).await?;
 
         Ok(id.to_string())
     }
 
-   pub fn sign_in(&mut self,input_username:String,input_password:String)->Result<String,diesel::result::Error>{
+   pub async fn sign_in(&self,input_username:String,input_password:String)->Result<String,DbError>{
     use crate::schema::user::dsl::*;
 
+    let mut conn = self.conn().await?;
     let user_result=user
-               .or_filter(username.eq(input_username))
+               .filter(username.eq(input_username))
                .select(User::as_select())
-               .first(&mut self.conn)?;
+               .first(&mut conn).await
+               .map_err(|e| match e{
+                   diesel::result::Error::NotFound=>DbError::InvalidCredentials,
+                   e=>DbError::from(e)
+               })?;
     
     if user_result.password!=input_password{
-        return Err(diesel::result::Error::NotFound);
+        return Err(DbError::InvalidCredentials);
     }
     Ok(user_result.id.to_string())
     }
-}
\ No newline at end of file
+
+   /// Replaces the feed token, which invalidates every feed url handed out before.
+   pub async fn rotate_feed_token(&self,input_user_id:String)->Result<String,DbError>{
+    use crate::schema::user::dsl::*;
+
+    let mut conn = self.conn().await?;
+    let token=format!("{}{}",Uuid::new_v4().simple(),Uuid::new_v4().simple());
+    diesel::update(user.filter(id.eq(input_user_id)))
+        .set(feed_token.eq(Some(&token)))
+        .execute(&mut conn).await?;
+    Ok(token)
+   }
+
+   pub async fn get_user_id_by_feed_token(&self,input_token:String)->Result<String,DbError>{
+    use crate::schema::user::dsl::*;
+
+    let mut conn = self.conn().await?;
+    user
+        .filter(feed_token.eq(input_token))
+        .select(id)
+        .first(&mut conn).await.map_err(DbError::from)
+   }
+}
@@ -0,0 +1,185 @@
+use std::collections::HashSet;
+
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::ExpressionMethods;
+use diesel_async::{RunQueryDsl, AsyncConnection};
+use diesel_async::scoped_futures::ScopedFutureExt;
+
+use crate::db::Db;
+use crate::error::DbError;
+use crate::models::incident::Incident;
+
+/// `website_id` can only be reached through `depends_on_id`, e.g. an API endpoint behind a load balancer.
+#[derive(Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::website_dependency)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WebsiteDependency {
+    pub website_id: String,
+    pub depends_on_id: String,
+}
+
+/// A dependent website whose failure was folded into another website's incident.
+#[derive(Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::incident_dependent)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct IncidentDependent {
+    pub incident_id: String,
+    pub website_id: String,
+    pub time_added: chrono::NaiveDateTime,
+}
+
+/// Whether making `website_id` depend on `depends_on` would close a loop given the other
+/// `edges`, i.e. `website_id` is already reachable from one of them.
+pub fn would_create_dependency_cycle(edges: &[WebsiteDependency], website_id: &str, depends_on: &[String]) -> bool {
+    let mut seen = HashSet::new();
+    let mut pending: Vec<&str> = depends_on.iter().map(String::as_str).collect();
+    while let Some(current) = pending.pop() {
+        if current == website_id {
+            return true;
+        }
+        if seen.insert(current) {
+            pending.extend(edges.iter().filter(|e| e.website_id == current).map(|e| e.depends_on_id.as_str()));
+        }
+    }
+    false
+}
+
+impl Db {
+    /// Ids of the websites `input_website_id` directly depends on.
+    pub async fn get_website_dependencies(&self, input_website_id: String) -> Result<Vec<String>, DbError> {
+        use crate::schema::website_dependency::dsl::*;
+
+        let mut conn = self.conn().await?;
+        website_dependency
+            .filter(website_id.eq(input_website_id))
+            .order(depends_on_id.asc())
+            .select(depends_on_id)
+            .load(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Dependencies of all given websites, ordered by website then dependency.
+    pub async fn get_websites_dependencies(&self, website_ids: &[String]) -> Result<Vec<WebsiteDependency>, DbError> {
+        use crate::schema::website_dependency::dsl::*;
+
+        let mut conn = self.conn().await?;
+        website_dependency
+            .filter(website_id.eq_any(website_ids))
+            .order((website_id.asc(), depends_on_id.asc()))
+            .select(WebsiteDependency::as_select())
+            .load(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Replaces a website's dependencies. The website and every dependency must belong to the user,
+    /// and a `Conflict` is returned when they would form a cycle.
+    pub async fn set_website_dependencies(&self, input_user_id: String, input_website_id: String, depends_on: Vec<String>) -> Result<Vec<String>, DbError> {
+        use crate::schema::{website, website_dependency};
+
+        let mut conn = self.conn().await?;
+        conn.transaction::<_, DbError, _>(|conn| async move {
+            let mut ids = depends_on.clone();
+            ids.push(input_website_id.clone());
+            ids.sort();
+            ids.dedup();
+            let owned: i64 = website::table
+                .filter(website::id.eq_any(&ids))
+                .filter(website::user_id.eq(&input_user_id))
+                .count()
+                .get_result(conn).await?;
+            if owned != ids.len() as i64 {
+                return Err(DbError::NotFound);
+            }
+            // the user's whole graph, since a loop can run through websites the request doesn't name
+            let edges: Vec<WebsiteDependency> = website_dependency::table
+                .inner_join(website::table.on(website::id.eq(website_dependency::website_id)))
+                .filter(website::user_id.eq(&input_user_id))
+                .filter(website_dependency::website_id.ne(&input_website_id))
+                .select(WebsiteDependency::as_select())
+                .load(conn).await?;
+            if would_create_dependency_cycle(&edges, &input_website_id, &depends_on) {
+                return Err(DbError::Conflict("dependencies can't form a cycle".to_string()));
+            }
+
+            diesel::delete(website_dependency::table.filter(website_dependency::website_id.eq(&input_website_id))).execute(conn).await?;
+            let rows: Vec<WebsiteDependency> = depends_on
+                .iter()
+                .map(|parent| WebsiteDependency {
+                    website_id: input_website_id.clone(),
+                    depends_on_id: parent.clone(),
+                })
+                .collect();
+            diesel::insert_into(website_dependency::table)
+                .values(&rows)
+                .on_conflict_do_nothing()
+                .execute(conn).await?;
+
+            website_dependency::table
+                .filter(website_dependency::website_id.eq(&input_website_id))
+                .order(website_dependency::depends_on_id.asc())
+                .select(website_dependency::depends_on_id)
+                .load(conn).await.map_err(DbError::from)
+        }.scope_boxed()).await
+    }
+
+    /// An open incident on one of the website's direct dependencies, if any.
+    pub async fn get_down_dependency(&self, input_website_id: String) -> Result<Option<Incident>, DbError> {
+        use crate::schema::{incident, website_dependency};
+
+        let mut conn = self.conn().await?;
+        let parents = website_dependency::table
+            .filter(website_dependency::website_id.eq(input_website_id))
+            .select(website_dependency::depends_on_id);
+        incident::table
+            .filter(incident::website_id.eq_any(parents))
+            .filter(incident::resolved_at.is_null())
+            .order(incident::opened_at.asc())
+            .select(Incident::as_select())
+            .first(&mut conn).await
+            .optional().map_err(DbError::from)
+    }
+
+    /// Folds a website into an incident. Returns false when it already was.
+    pub async fn add_incident_dependent(&self, input_incident_id: String, input_website_id: String) -> Result<bool, DbError> {
+        let mut conn = self.conn().await?;
+        let dependent = IncidentDependent {
+            incident_id: input_incident_id,
+            website_id: input_website_id,
+            time_added: Utc::now().naive_utc(),
+        };
+        let inserted = diesel::insert_into(crate::schema::incident_dependent::table)
+            .values(&dependent)
+            .on_conflict_do_nothing()
+            .execute(&mut conn).await?;
+        Ok(inserted == 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(website_id: &str, depends_on_id: &str) -> WebsiteDependency {
+        WebsiteDependency {
+            website_id: website_id.to_string(),
+            depends_on_id: depends_on_id.to_string(),
+        }
+    }
+
+    #[test]
+    fn finds_loops_through_the_whole_graph() {
+        // api -> lb -> dns
+        let edges = [edge("api", "lb"), edge("lb", "dns")];
+
+        assert!(would_create_dependency_cycle(&edges, "dns", &["api".to_string()]));
+        assert!(would_create_dependency_cycle(&edges, "lb", &["lb".to_string()]));
+        assert!(!would_create_dependency_cycle(&edges, "dns", &["cdn".to_string()]));
+        assert!(!would_create_dependency_cycle(&edges, "api", &["dns".to_string(), "lb".to_string()]));
+    }
+
+    #[test]
+    fn terminates_on_loops_elsewhere() {
+        let edges = [edge("a", "b"), edge("b", "a")];
+
+        assert!(!would_create_dependency_cycle(&edges, "c", &["a".to_string()]));
+    }
+}
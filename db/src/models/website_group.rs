@@ -0,0 +1,322 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::ExpressionMethods;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::db::Db;
+use crate::error::DbError;
+use crate::models::website::Website;
+use crate::models::website_tick::WebsiteStatus;
+
+/// A folder of websites; groups nest through `parent_id`.
+#[derive(Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::website_group)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WebsiteGroup {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub parent_id: Option<String>,
+    pub time_added: chrono::NaiveDateTime,
+}
+
+/// Where a website stands right now. Ordered best to worst, so a group's
+/// state is the max over its websites and paused ones never drag it down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MonitorState {
+    Paused,
+    Up,
+    Unknown,
+    Down,
+}
+
+impl MonitorState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MonitorState::Paused => "paused",
+            MonitorState::Up => "up",
+            MonitorState::Unknown => "unknown",
+            MonitorState::Down => "down",
+        }
+    }
+}
+
+/// Ids of `root` and every group below it.
+pub fn group_subtree(groups: &[WebsiteGroup], root: &str) -> Vec<String> {
+    let mut subtree = vec![root.to_string()];
+    let mut index = 0;
+    while index < subtree.len() {
+        let parent = subtree[index].clone();
+        subtree.extend(groups.iter().filter(|g| g.parent_id.as_deref() == Some(parent.as_str())).map(|g| g.id.clone()));
+        index += 1;
+    }
+    subtree
+}
+
+/// Ids of `group` and every group above it, nearest first.
+pub fn group_ancestors(groups: &[WebsiteGroup], group: Option<&str>) -> Vec<String> {
+    let mut chain: Vec<String> = Vec::new();
+    let mut current = group;
+    // stop on a repeat in case concurrent moves ever left a cycle behind
+    while let Some(group) = current.filter(|g| !chain.iter().any(|c| c == g)) {
+        chain.push(group.to_string());
+        current = groups.iter().find(|g| g.id == group).and_then(|g| g.parent_id.as_deref());
+    }
+    chain
+}
+
+impl Db {
+    /// Creates a group, nested under `parent_id` when given. The parent must belong to the user.
+    pub async fn create_website_group(&self, input_user_id: String, input_name: String, input_parent_id: Option<String>) -> Result<WebsiteGroup, DbError> {
+        use crate::schema::website_group::dsl::*;
+
+        let mut conn = self.conn().await?;
+        if let Some(parent) = &input_parent_id {
+            website_group
+                .filter(id.eq(parent))
+                .filter(user_id.eq(&input_user_id))
+                .select(id)
+                .first::<String>(&mut conn).await?;
+        }
+
+        let group = WebsiteGroup {
+            id: Uuid::new_v4().to_string(),
+            user_id: input_user_id,
+            name: input_name,
+            parent_id: input_parent_id,
+            time_added: Utc::now().naive_utc(),
+        };
+        diesel::insert_into(website_group)
+            .values(&group)
+            .execute(&mut conn).await?;
+
+        Ok(group)
+    }
+
+    pub async fn get_website_groups(&self, input_user_id: String) -> Result<Vec<WebsiteGroup>, DbError> {
+        use crate::schema::website_group::dsl::*;
+
+        let mut conn = self.conn().await?;
+        website_group
+            .filter(user_id.eq(input_user_id))
+            .order(name.asc())
+            .select(WebsiteGroup::as_select())
+            .load(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Renames or moves a group. Returns `Ok(None)` when the move would put the
+    /// group inside its own subtree.
+    pub async fn update_website_group(&self, input_user_id: String, input_id: String, input_name: String, input_parent_id: Option<String>) -> Result<Option<WebsiteGroup>, DbError> {
+        let groups = self.get_website_groups(input_user_id.clone()).await?;
+        let mut conn = self.conn().await?;
+        if !groups.iter().any(|g| g.id == input_id) {
+            return Err(DbError::NotFound);
+        }
+        if let Some(parent) = &input_parent_id {
+            if !groups.iter().any(|g| &g.id == parent) {
+                return Err(DbError::NotFound);
+            }
+            if group_subtree(&groups, &input_id).contains(parent) {
+                return Ok(None);
+            }
+        }
+
+        use crate::schema::website_group::dsl::*;
+
+        diesel::update(website_group.filter(id.eq(input_id)).filter(user_id.eq(input_user_id)))
+            .set((name.eq(input_name), parent_id.eq(input_parent_id)))
+            .returning(WebsiteGroup::as_returning())
+            .get_result(&mut conn).await
+            .map(Some).map_err(DbError::from)
+    }
+
+    /// Deletes a group with its subgroups; their websites become ungrouped.
+    pub async fn delete_website_group(&self, input_user_id: String, input_id: String) -> Result<usize, DbError> {
+        use crate::schema::website_group::dsl::*;
+
+        let mut conn = self.conn().await?;
+        diesel::delete(website_group.filter(id.eq(input_id)).filter(user_id.eq(input_user_id)))
+            .execute(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Moves one of the user's websites into one of their groups, or out of any group with `None`.
+    pub async fn set_website_group(&self, input_user_id: String, input_website_id: String, input_group_id: Option<String>) -> Result<Website, DbError> {
+        let mut conn = self.conn().await?;
+        if let Some(input_group_id) = &input_group_id {
+            use crate::schema::website_group;
+
+            website_group::table
+                .filter(website_group::id.eq(input_group_id))
+                .filter(website_group::user_id.eq(&input_user_id))
+                .select(website_group::id)
+                .first::<String>(&mut conn).await?;
+        }
+
+        use crate::schema::website::dsl::*;
+
+        diesel::update(website.filter(id.eq(input_website_id)).filter(user_id.eq(input_user_id)))
+            .set(group_id.eq(input_group_id))
+            .returning(Website::as_returning())
+            .get_result(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// The group and all of its subgroups, or `NotFound` when it isn't the user's.
+    pub async fn get_group_subtree(&self, input_user_id: String, input_group_id: String) -> Result<Vec<String>, DbError> {
+        let groups = self.get_website_groups(input_user_id).await?;
+        if !groups.iter().any(|g| g.id == input_group_id) {
+            return Err(DbError::NotFound);
+        }
+        Ok(group_subtree(&groups, &input_group_id))
+    }
+
+    /// The website's group followed by each of its ancestors, empty for ungrouped websites.
+    pub async fn get_website_group_chain(&self, input_website_id: String) -> Result<Vec<String>, DbError> {
+        use crate::schema::{website, website_group};
+
+        let mut conn = self.conn().await?;
+        let (owner, group): (String, Option<String>) = website::table
+            .filter(website::id.eq(input_website_id))
+            .select((website::user_id, website::group_id))
+            .first(&mut conn).await?;
+        if group.is_none() {
+            return Ok(Vec::new());
+        }
+        let groups = website_group::table
+            .filter(website_group::user_id.eq(owner))
+            .select(WebsiteGroup::as_select())
+            .load(&mut conn).await?;
+        Ok(group_ancestors(&groups, group.as_deref()))
+    }
+
+    /// Current state of each website: down with an open incident or a failing region,
+    /// up when every region last reported up, unknown before the first check.
+    pub async fn get_monitor_states(&self, websites: &[Website]) -> Result<HashMap<String, MonitorState>, DbError> {
+        use crate::schema::{incident, website_ticks};
+
+        let mut conn = self.conn().await?;
+        let ids: Vec<&String> = websites.iter().map(|w| &w.id).collect();
+        let down: Vec<String> = incident::table
+            .filter(incident::website_id.eq_any(&ids))
+            .filter(incident::resolved_at.is_null())
+            .select(incident::website_id)
+            .load(&mut conn).await?;
+        let latest: Vec<(String, WebsiteStatus)> = website_ticks::table
+            .filter(website_ticks::website_id.eq_any(&ids))
+            .distinct_on((website_ticks::website_id, website_ticks::region_id))
+            .order((website_ticks::website_id, website_ticks::region_id, website_ticks::createdAt.desc()))
+            .select((website_ticks::website_id, website_ticks::status))
+            .load(&mut conn).await?;
+
+        let mut states = HashMap::with_capacity(websites.len());
+        for w in websites {
+            let state = if w.paused {
+                MonitorState::Paused
+            } else if down.contains(&w.id) {
+                MonitorState::Down
+            } else {
+                latest
+                    .iter()
+                    .filter(|(website_id, _)| website_id == &w.id)
+                    .map(|(_, status)| match status {
+                        WebsiteStatus::Up => MonitorState::Up,
+                        WebsiteStatus::Down => MonitorState::Down,
+                        WebsiteStatus::Unknown => MonitorState::Unknown,
+                    })
+                    .max()
+                    .unwrap_or(MonitorState::Unknown)
+            };
+            states.insert(w.id.clone(), state);
+        }
+        Ok(states)
+    }
+
+    /// Worst state among each group's websites, subgroups included. Empty groups are unknown.
+    pub async fn get_group_states(&self, input_user_id: String) -> Result<Vec<(WebsiteGroup, MonitorState, usize)>, DbError> {
+        let groups = self.get_website_groups(input_user_id.clone()).await?;
+        let websites: Vec<Website> = {
+            use crate::schema::website::dsl::*;
+
+            let mut conn = self.conn().await?;
+            website
+                .filter(user_id.eq(input_user_id))
+                .filter(group_id.is_not_null())
+                .select(Website::as_select())
+                .load(&mut conn).await?
+        };
+        let states = self.get_monitor_states(&websites).await?;
+
+        Ok(groups
+            .iter()
+            .map(|group| {
+                let subtree = group_subtree(&groups, &group.id);
+                let members: Vec<&Website> = websites
+                    .iter()
+                    .filter(|w| w.group_id.as_ref().is_some_and(|g| subtree.contains(g)))
+                    .collect();
+                let state = members
+                    .iter()
+                    .filter_map(|w| states.get(&w.id).copied())
+                    .max()
+                    .unwrap_or(MonitorState::Unknown);
+                (group.clone(), state, members.len())
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(group_id: &str, parent: Option<&str>) -> WebsiteGroup {
+        WebsiteGroup {
+            id: group_id.to_string(),
+            user_id: "user".to_string(),
+            name: group_id.to_string(),
+            parent_id: parent.map(str::to_string),
+            time_added: Utc::now().naive_utc(),
+        }
+    }
+
+    #[test]
+    fn subtree_holds_root_and_every_descendant() {
+        let groups = [
+            group("prod", None),
+            group("eu", Some("prod")),
+            group("us", Some("prod")),
+            group("eu-west", Some("eu")),
+            group("staging", None),
+            group("staging-eu", Some("staging")),
+        ];
+
+        let mut subtree = group_subtree(&groups, "prod");
+        subtree.sort();
+        assert_eq!(subtree, ["eu", "eu-west", "prod", "us"]);
+        assert_eq!(group_subtree(&groups, "eu-west"), ["eu-west"]);
+        assert_eq!(group_subtree(&groups, "staging"), ["staging", "staging-eu"]);
+    }
+
+    #[test]
+    fn ancestors_run_from_the_group_to_the_root() {
+        let groups = [group("prod", None), group("eu", Some("prod")), group("eu-west", Some("eu"))];
+
+        assert_eq!(group_ancestors(&groups, Some("eu-west")), ["eu-west", "eu", "prod"]);
+        assert_eq!(group_ancestors(&groups, Some("prod")), ["prod"]);
+        assert!(group_ancestors(&groups, None).is_empty());
+    }
+
+    #[test]
+    fn ancestors_stop_at_a_cycle() {
+        let groups = [group("a", Some("b")), group("b", Some("a"))];
+
+        assert_eq!(group_ancestors(&groups, Some("a")), ["a", "b"]);
+    }
+
+    #[test]
+    fn subtree_of_unknown_group_is_just_the_root() {
+        assert_eq!(group_subtree(&[group("prod", None)], "gone"), ["gone"]);
+    }
+}
@@ -0,0 +1,88 @@
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::ExpressionMethods;
+use diesel_async::{RunQueryDsl, AsyncConnection};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use uuid::Uuid;
+
+use crate::db::Db;
+use crate::error::DbError;
+use crate::models::website::Website;
+
+/// One stretch of time a website spent paused; `resumed_at` is unset while it still is.
+#[derive(Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::website_pause)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WebsitePause {
+    pub id: String,
+    pub website_id: String,
+    pub paused_at: chrono::NaiveDateTime,
+    pub resumed_at: Option<chrono::NaiveDateTime>,
+}
+
+impl Db {
+    /// Pauses the user's websites among `ids`. Returns the websites that were
+    /// running before; ids of other users' or already paused websites are skipped.
+    pub async fn pause_websites(&self, input_user_id: String, ids: Vec<String>) -> Result<Vec<Website>, DbError> {
+        use crate::schema::website;
+
+        let mut conn = self.conn().await?;
+        let now = Utc::now().naive_utc();
+        conn.transaction::<_, diesel::result::Error, _>(|conn| async move {
+            let paused = diesel::update(
+                website::table
+                    .filter(website::id.eq_any(&ids))
+                    .filter(website::user_id.eq(&input_user_id))
+                    .filter(website::paused.eq(false)),
+            )
+            .set(website::paused.eq(true))
+            .returning(Website::as_returning())
+            .get_results(conn).await?;
+
+            let periods: Vec<WebsitePause> = paused
+                .iter()
+                .map(|w| WebsitePause {
+                    id: Uuid::new_v4().to_string(),
+                    website_id: w.id.clone(),
+                    paused_at: now,
+                    resumed_at: None,
+                })
+                .collect();
+            diesel::insert_into(crate::schema::website_pause::table)
+                .values(&periods)
+                .execute(conn).await?;
+
+            Ok(paused)
+        }.scope_boxed()).await.map_err(DbError::from)
+    }
+
+    /// Resumes the user's paused websites among `ids` and closes their pause periods.
+    pub async fn resume_websites(&self, input_user_id: String, ids: Vec<String>) -> Result<Vec<Website>, DbError> {
+        use crate::schema::{website, website_pause};
+
+        let mut conn = self.conn().await?;
+        let now = Utc::now().naive_utc();
+        conn.transaction::<_, diesel::result::Error, _>(|conn| async move {
+            let resumed = diesel::update(
+                website::table
+                    .filter(website::id.eq_any(&ids))
+                    .filter(website::user_id.eq(&input_user_id))
+                    .filter(website::paused.eq(true)),
+            )
+            .set(website::paused.eq(false))
+            .returning(Website::as_returning())
+            .get_results(conn).await?;
+
+            let resumed_ids: Vec<&String> = resumed.iter().map(|w| &w.id).collect();
+            diesel::update(
+                website_pause::table
+                    .filter(website_pause::website_id.eq_any(resumed_ids))
+                    .filter(website_pause::resumed_at.is_null()),
+            )
+            .set(website_pause::resumed_at.eq(Some(now)))
+            .execute(conn).await?;
+
+            Ok(resumed)
+        }.scope_boxed()).await.map_err(DbError::from)
+    }
+}
@@ -0,0 +1,65 @@
+use diesel::prelude::*;
+use diesel::ExpressionMethods;
+use diesel_async::{RunQueryDsl, AsyncConnection};
+use diesel_async::scoped_futures::ScopedFutureExt;
+
+use crate::db::Db;
+use crate::error::DbError;
+
+/// Free-form `key:value` label on a website; a website has at most one value per key.
+#[derive(Queryable, Insertable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::website_tag)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WebsiteTag {
+    pub website_id: String,
+    pub key: String,
+    pub value: String,
+}
+
+impl Db {
+    /// Replaces all tags of one of the user's websites.
+    pub async fn set_website_tags(&self, input_user_id: String, input_website_id: String, tags: Vec<(String, String)>) -> Result<Vec<WebsiteTag>, DbError> {
+        use crate::schema::{website, website_tag};
+
+        let mut conn = self.conn().await?;
+        conn.transaction::<_, diesel::result::Error, _>(|conn| async move {
+            website::table
+                .filter(website::id.eq(&input_website_id))
+                .filter(website::user_id.eq(&input_user_id))
+                .select(website::id)
+                .first::<String>(conn).await?;
+
+            diesel::delete(website_tag::table.filter(website_tag::website_id.eq(&input_website_id))).execute(conn).await?;
+            let rows: Vec<WebsiteTag> = tags
+                .into_iter()
+                .map(|(key, value)| WebsiteTag {
+                    website_id: input_website_id.clone(),
+                    key,
+                    value,
+                })
+                .collect();
+            diesel::insert_into(website_tag::table)
+                .values(&rows)
+                .on_conflict_do_nothing()
+                .execute(conn).await?;
+
+            website_tag::table
+                .filter(website_tag::website_id.eq(&input_website_id))
+                .order(website_tag::key.asc())
+                .select(WebsiteTag::as_select())
+                .load(conn).await
+        }.scope_boxed()).await.map_err(DbError::from)
+    }
+
+    /// Tags of all given websites, ordered by website then key.
+    pub async fn get_website_tags(&self, website_ids: &[String]) -> Result<Vec<WebsiteTag>, DbError> {
+        use crate::schema::website_tag::dsl::*;
+
+        let mut conn = self.conn().await?;
+        website_tag
+            .filter(website_id.eq_any(website_ids))
+            .order((website_id.asc(), key.asc()))
+            .select(WebsiteTag::as_select())
+            .load(&mut conn).await.map_err(DbError::from)
+    }
+}
@@ -0,0 +1,214 @@
+use std::io::Write;
+
+use chrono::Utc;
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::pg::{Pg, PgValue};
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use uuid::Uuid;
+
+use crate::db::Db;
+use crate::error::DbError;
+use crate::schema::sql_types;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromSqlRow, AsExpression)]
+#[diesel(sql_type = sql_types::WebsiteStatus)]
+pub enum WebsiteStatus {
+    Up,
+    Down,
+    Unknown,
+}
+
+impl WebsiteStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebsiteStatus::Up => "up",
+            WebsiteStatus::Down => "down",
+            WebsiteStatus::Unknown => "unknown",
+        }
+    }
+}
+
+impl ToSql<sql_types::WebsiteStatus, Pg> for WebsiteStatus {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        out.write_all(self.as_str().as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::WebsiteStatus, Pg> for WebsiteStatus {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"up" => Ok(WebsiteStatus::Up),
+            b"down" => Ok(WebsiteStatus::Down),
+            b"unknown" => Ok(WebsiteStatus::Unknown),
+            _ => Err("unrecognized website_status variant".into()),
+        }
+    }
+}
+
+#[derive(Queryable, Insertable, Selectable)]
+#[diesel(table_name = crate::schema::website_ticks)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WebsiteTick {
+    pub id: String,
+    pub response_time_ms: i32,
+    pub status: WebsiteStatus,
+    pub region_id: String,
+    pub website_id: String,
+    #[diesel(column_name = createdAt)]
+    pub created_at: chrono::NaiveDateTime,
+    /// Taken while a maintenance window was active; left out of uptime.
+    pub in_maintenance: bool,
+    /// The website failed while one of its dependencies had an open incident.
+    pub dependency_down: bool,
+}
+
+/// Tick counts behind an uptime figure.
+pub struct Uptime {
+    pub up_ticks: i64,
+    pub down_ticks: i64,
+    pub maintenance_ticks: i64,
+}
+
+impl Uptime {
+    /// Share of up results outside maintenance, `None` without any such results.
+    pub fn percentage(&self) -> Option<f64> {
+        let counted = self.up_ticks + self.down_ticks;
+        (counted > 0).then(|| self.up_ticks as f64 * 100.0 / counted as f64)
+    }
+}
+
+/// One day of tick counts for a website, maintenance and paused periods left out.
+#[derive(QueryableByName)]
+pub struct DailyUptime {
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub website_id: String,
+    #[diesel(sql_type = diesel::sql_types::Date)]
+    pub day: chrono::NaiveDate,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub up_ticks: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub down_ticks: i64,
+}
+
+impl DailyUptime {
+    pub fn percentage(&self) -> Option<f64> {
+        let counted = self.up_ticks + self.down_ticks;
+        (counted > 0).then(|| self.up_ticks as f64 * 100.0 / counted as f64)
+    }
+}
+
+impl Db {
+    /// Stores the result of one check from a region.
+    pub async fn create_website_tick(&self, input_website_id: String, input_region_id: String, input_status: WebsiteStatus, input_response_time_ms: i32, input_in_maintenance: bool, input_dependency_down: bool) -> Result<WebsiteTick, DbError> {
+        let mut conn = self.conn().await?;
+        let tick = WebsiteTick {
+            id: Uuid::new_v4().to_string(),
+            response_time_ms: input_response_time_ms,
+            status: input_status,
+            region_id: input_region_id,
+            website_id: input_website_id,
+            created_at: Utc::now().naive_utc(),
+            in_maintenance: input_in_maintenance,
+            dependency_down: input_dependency_down,
+        };
+        diesel::insert_into(crate::schema::website_ticks::table)
+            .values(&tick)
+            .execute(&mut conn).await?;
+
+        Ok(tick)
+    }
+
+    /// Most recent tick reported by each region for a website.
+    pub async fn get_latest_region_ticks(&self, input_website_id: String) -> Result<Vec<WebsiteTick>, DbError> {
+        use crate::schema::website_ticks::dsl::*;
+
+        let mut conn = self.conn().await?;
+        website_ticks
+            .filter(website_id.eq(input_website_id))
+            .distinct_on(region_id)
+            .order((region_id, createdAt.desc()))
+            .select(WebsiteTick::as_select())
+            .load(&mut conn).await.map_err(DbError::from)
+    }
+
+    /// Tick counts since `since`. Ticks landing while the website was paused are left out entirely.
+    pub async fn get_uptime(&self, input_website_id: String, since: chrono::NaiveDateTime) -> Result<Uptime, DbError> {
+        use crate::schema::website_pause;
+        use crate::schema::website_ticks::dsl::*;
+        use diesel::dsl::{count_star, exists, not};
+
+        let mut conn = self.conn().await?;
+        let during_pause = website_pause::table
+            .filter(website_pause::website_id.eq(website_id))
+            .filter(website_pause::paused_at.le(createdAt))
+            .filter(website_pause::resumed_at.is_null().or(website_pause::resumed_at.gt(createdAt.nullable())));
+        let counts: Vec<(WebsiteStatus, bool, i64)> = website_ticks
+            .filter(website_id.eq(input_website_id))
+            .filter(createdAt.ge(since))
+            .filter(not(exists(during_pause)))
+            .group_by((status, in_maintenance))
+            .select((status, in_maintenance, count_star()))
+            .load(&mut conn).await?;
+
+        let mut uptime = Uptime { up_ticks: 0, down_ticks: 0, maintenance_ticks: 0 };
+        for (tick_status, maintenance, count) in counts {
+            match (tick_status, maintenance) {
+                (_, true) => uptime.maintenance_ticks += count,
+                (WebsiteStatus::Up, false) => uptime.up_ticks += count,
+                (WebsiteStatus::Down, false) => uptime.down_ticks += count,
+                (WebsiteStatus::Unknown, false) => {}
+            }
+        }
+        Ok(uptime)
+    }
+
+    /// Mean response time of successful checks since `since` outside maintenance and pauses, `None` without any.
+    pub async fn get_average_response_time(&self, input_website_id: String, since: chrono::NaiveDateTime) -> Result<Option<f64>, DbError> {
+        use crate::schema::website_pause;
+        use crate::schema::website_ticks::dsl::*;
+        use diesel::dsl::{count_star, exists, not};
+
+        let mut conn = self.conn().await?;
+        let during_pause = website_pause::table
+            .filter(website_pause::website_id.eq(website_id))
+            .filter(website_pause::paused_at.le(createdAt))
+            .filter(website_pause::resumed_at.is_null().or(website_pause::resumed_at.gt(createdAt.nullable())));
+        let (total, count): (Option<i64>, i64) = website_ticks
+            .filter(website_id.eq(input_website_id))
+            .filter(createdAt.ge(since))
+            .filter(status.eq(WebsiteStatus::Up))
+            .filter(in_maintenance.eq(false))
+            .filter(not(exists(during_pause)))
+            .select((diesel::dsl::sum(response_time_ms), count_star()))
+            .first(&mut conn).await?;
+
+        Ok(total.filter(|_| count > 0).map(|total| total as f64 / count as f64))
+    }
+
+    /// Per-day tick counts (UTC days) since `since`, only days with results are returned.
+    pub async fn get_daily_uptime(&self, website_ids: Vec<String>, since: chrono::NaiveDateTime) -> Result<Vec<DailyUptime>, DbError> {
+        use diesel::sql_types::{Array, Text, Timestamp};
+
+        let mut conn = self.conn().await?;
+        diesel::sql_query(
+            r#"SELECT t.website_id, date(t."createdAt") AS day,
+                      count(*) FILTER (WHERE t.status = 'up') AS up_ticks,
+                      count(*) FILTER (WHERE t.status = 'down') AS down_ticks
+               FROM website_ticks t
+               WHERE t.website_id = ANY($1) AND t."createdAt" >= $2 AND NOT t.in_maintenance
+                 AND NOT EXISTS (
+                     SELECT 1 FROM website_pause p
+                     WHERE p.website_id = t.website_id AND p.paused_at <= t."createdAt"
+                       AND (p.resumed_at IS NULL OR p.resumed_at > t."createdAt"))
+               GROUP BY t.website_id, day
+               ORDER BY day"#,
+        )
+        .bind::<Array<Text>, _>(website_ids)
+        .bind::<Timestamp, _>(since)
+        .load(&mut conn).await.map_err(DbError::from)
+    }
+}
@@ -6,6 +6,131 @@ pub mod sql_types {
     pub struct WebsiteStatus;
 }
 
+diesel::table! {
+    api_key (id) {
+        id -> Text,
+        user_id -> Text,
+        name -> Text,
+        prefix -> Text,
+        key_hash -> Text,
+        time_added -> Timestamp,
+        last_used_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    escalation_level (id) {
+        id -> Text,
+        policy_id -> Text,
+        position -> Int4,
+        delay_minutes -> Int4,
+        channel_id -> Nullable<Text>,
+        schedule_id -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    escalation_policy (id) {
+        id -> Text,
+        user_id -> Text,
+        name -> Text,
+    }
+}
+
+diesel::table! {
+    incident (id) {
+        id -> Text,
+        website_id -> Text,
+        status -> Text,
+        opened_at -> Timestamp,
+        resolved_at -> Nullable<Timestamp>,
+        acknowledged_at -> Nullable<Timestamp>,
+        acknowledged_by -> Nullable<Text>,
+        escalation_level -> Int4,
+    }
+}
+
+diesel::table! {
+    incident_dependent (incident_id, website_id) {
+        incident_id -> Text,
+        website_id -> Text,
+        time_added -> Timestamp,
+    }
+}
+
+diesel::table! {
+    incident_event (id) {
+        id -> Text,
+        incident_id -> Text,
+        kind -> Text,
+        user_id -> Nullable<Text>,
+        message -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    maintenance_window (id) {
+        id -> Text,
+        website_id -> Nullable<Text>,
+        title -> Text,
+        starts_at -> Timestamp,
+        duration_minutes -> Int4,
+        recurrence -> Nullable<Text>,
+        until -> Nullable<Timestamp>,
+        time_added -> Timestamp,
+        user_id -> Text,
+        group_id -> Nullable<Text>,
+        subscribers_notified_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    notification_channel (id) {
+        id -> Text,
+        user_id -> Text,
+        kind -> Text,
+        target -> Text,
+        secret -> Nullable<Text>,
+        time_added -> Timestamp,
+        external_key -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    notification_deliveries (id) {
+        id -> Text,
+        incident_id -> Text,
+        channel_id -> Text,
+        attempt -> Int4,
+        status_code -> Nullable<Int4>,
+        latency_ms -> Int4,
+        error -> Nullable<Text>,
+        attempted_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    on_call_override (id) {
+        id -> Text,
+        schedule_id -> Text,
+        user_id -> Text,
+        starts_at -> Timestamp,
+        ends_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    on_call_schedule (id) {
+        id -> Text,
+        user_id -> Text,
+        name -> Text,
+        rotation -> Text,
+        rotation_start -> Timestamp,
+        participants -> Array<Text>,
+    }
+}
+
 diesel::table! {
     region (id) {
         id -> Text,
@@ -13,11 +138,68 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    slack_identity (team_id, slack_user_id) {
+        team_id -> Text,
+        slack_user_id -> Text,
+        user_id -> Text,
+        time_added -> Timestamp,
+    }
+}
+
+diesel::table! {
+    status_page (id) {
+        id -> Text,
+        user_id -> Text,
+        slug -> Text,
+        title -> Text,
+        logo_url -> Nullable<Text>,
+        time_added -> Timestamp,
+        external_key -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    status_page_component (id) {
+        id -> Text,
+        status_page_id -> Text,
+        website_id -> Text,
+        name -> Text,
+        position -> Int4,
+    }
+}
+
+diesel::table! {
+    status_page_subscriber (id) {
+        id -> Text,
+        status_page_id -> Text,
+        kind -> Text,
+        target -> Text,
+        component_ids -> Array<Text>,
+        token -> Text,
+        secret -> Nullable<Text>,
+        confirmed_at -> Nullable<Timestamp>,
+        time_added -> Timestamp,
+        confirmation_sent_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    team_member (id) {
+        id -> Text,
+        owner_id -> Text,
+        member_id -> Text,
+        invited_at -> Timestamp,
+        accepted_at -> Nullable<Timestamp>,
+    }
+}
+
 diesel::table! {
     user (id) {
         id -> Text,
         username -> Text,
         password -> Text,
+        feed_token -> Nullable<Text>,
     }
 }
 
@@ -27,6 +209,45 @@ diesel::table! {
         url -> Text,
         time_added -> Timestamp,
         user_id -> Text,
+        escalation_policy_id -> Nullable<Text>,
+        paused -> Bool,
+        group_id -> Nullable<Text>,
+        badge_token -> Text,
+        external_key -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    website_dependency (website_id, depends_on_id) {
+        website_id -> Text,
+        depends_on_id -> Text,
+    }
+}
+
+diesel::table! {
+    website_group (id) {
+        id -> Text,
+        user_id -> Text,
+        name -> Text,
+        parent_id -> Nullable<Text>,
+        time_added -> Timestamp,
+    }
+}
+
+diesel::table! {
+    website_pause (id) {
+        id -> Text,
+        website_id -> Text,
+        paused_at -> Timestamp,
+        resumed_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    website_tag (website_id, key) {
+        website_id -> Text,
+        key -> Text,
+        value -> Text,
     }
 }
 
@@ -41,16 +262,68 @@ diesel::table! {
         region_id -> Text,
         website_id -> Text,
         createdAt -> Timestamp,
+        in_maintenance -> Bool,
+        dependency_down -> Bool,
     }
 }
 
+diesel::joinable!(api_key -> user (user_id));
+diesel::joinable!(escalation_level -> escalation_policy (policy_id));
+diesel::joinable!(escalation_level -> notification_channel (channel_id));
+diesel::joinable!(escalation_level -> on_call_schedule (schedule_id));
+diesel::joinable!(escalation_policy -> user (user_id));
+diesel::joinable!(incident -> user (acknowledged_by));
+diesel::joinable!(incident -> website (website_id));
+diesel::joinable!(incident_dependent -> incident (incident_id));
+diesel::joinable!(incident_dependent -> website (website_id));
+diesel::joinable!(incident_event -> incident (incident_id));
+diesel::joinable!(incident_event -> user (user_id));
+diesel::joinable!(maintenance_window -> user (user_id));
+diesel::joinable!(maintenance_window -> website (website_id));
+diesel::joinable!(maintenance_window -> website_group (group_id));
+diesel::joinable!(notification_channel -> user (user_id));
+diesel::joinable!(notification_deliveries -> incident (incident_id));
+diesel::joinable!(notification_deliveries -> notification_channel (channel_id));
+diesel::joinable!(on_call_override -> on_call_schedule (schedule_id));
+diesel::joinable!(on_call_override -> user (user_id));
+diesel::joinable!(on_call_schedule -> user (user_id));
+diesel::joinable!(slack_identity -> user (user_id));
+diesel::joinable!(status_page -> user (user_id));
+diesel::joinable!(status_page_component -> status_page (status_page_id));
+diesel::joinable!(status_page_component -> website (website_id));
+diesel::joinable!(status_page_subscriber -> status_page (status_page_id));
+diesel::joinable!(website -> escalation_policy (escalation_policy_id));
 diesel::joinable!(website -> user (user_id));
+diesel::joinable!(website -> website_group (group_id));
+diesel::joinable!(website_group -> user (user_id));
+diesel::joinable!(website_pause -> website (website_id));
+diesel::joinable!(website_tag -> website (website_id));
 diesel::joinable!(website_ticks -> region (region_id));
 diesel::joinable!(website_ticks -> website (website_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    api_key,
+    escalation_level,
+    escalation_policy,
+    incident,
+    incident_dependent,
+    incident_event,
+    maintenance_window,
+    notification_channel,
+    notification_deliveries,
+    on_call_override,
+    on_call_schedule,
     region,
+    slack_identity,
+    status_page,
+    status_page_component,
+    status_page_subscriber,
+    team_member,
     user,
     website,
+    website_dependency,
+    website_group,
+    website_pause,
+    website_tag,
     website_ticks,
 );
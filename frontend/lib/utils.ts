@@ -5,4 +5,4 @@ export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs))
 }
 
-export const BACKEND_URL="https://lacalhost:3003"
+export const BACKEND_URL="https://lacalhost:3003/v1"
@@ -0,0 +1,227 @@
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IncidentAction {
+    Acknowledge,
+    Resolve,
+}
+
+impl IncidentAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IncidentAction::Acknowledge => "acknowledge",
+            IncidentAction::Resolve => "resolve",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "acknowledge" => Some(IncidentAction::Acknowledge),
+            "resolve" => Some(IncidentAction::Resolve),
+            _ => None,
+        }
+    }
+}
+
+/// What a valid token allows: `user_id` may apply `action` to `incident_id` until `expires_at`.
+#[derive(Debug, Clone)]
+pub struct ActionClaims {
+    pub incident_id: String,
+    pub user_id: String,
+    pub action: IncidentAction,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenError {
+    Malformed,
+    BadSignature,
+    Expired,
+}
+
+fn mac(secret: &str, body: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("hmac accepts keys of any length");
+    mac.update(body);
+    mac
+}
+
+/// Token format: `<hex(incident_id|user_id|action|expires_unix)>.<hex(hmac-sha256 of the first part)>`.
+/// Ids are uuids so `|` never appears inside a field.
+pub fn generate(secret: &str, claims: &ActionClaims) -> String {
+    let body = format!(
+        "{}|{}|{}|{}",
+        claims.incident_id,
+        claims.user_id,
+        claims.action.as_str(),
+        claims.expires_at.timestamp()
+    );
+    let encoded = hex::encode(body);
+    let signature = hex::encode(mac(secret, encoded.as_bytes()).finalize().into_bytes());
+    format!("{}.{}", encoded, signature)
+}
+
+pub fn verify(secret: &str, token: &str, now: DateTime<Utc>) -> Result<ActionClaims, TokenError> {
+    let (encoded, signature) = token.split_once('.').ok_or(TokenError::Malformed)?;
+    let signature = hex::decode(signature).map_err(|_| TokenError::Malformed)?;
+    mac(secret, encoded.as_bytes())
+        .verify_slice(&signature)
+        .map_err(|_| TokenError::BadSignature)?;
+
+    let body = String::from_utf8(hex::decode(encoded).map_err(|_| TokenError::Malformed)?).map_err(|_| TokenError::Malformed)?;
+    let mut parts = body.split('|');
+    let (Some(incident_id), Some(user_id), Some(action), Some(expires), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(TokenError::Malformed);
+    };
+    let action = IncidentAction::parse(action).ok_or(TokenError::Malformed)?;
+    let expires_at = expires
+        .parse::<i64>()
+        .ok()
+        .and_then(|t| DateTime::from_timestamp(t, 0))
+        .ok_or(TokenError::Malformed)?;
+
+    if now >= expires_at {
+        return Err(TokenError::Expired);
+    }
+    Ok(ActionClaims {
+        incident_id: incident_id.to_string(),
+        user_id: user_id.to_string(),
+        action,
+        expires_at,
+    })
+}
+
+/// Signed acknowledge + resolve URLs for one recipient of an alert.
+pub fn action_links(secret: &str, api_url: &str, incident_id: &str, user_id: &str, ttl: Duration) -> (String, String) {
+    let expires_at = Utc::now() + ttl;
+    let link = |action| {
+        let claims = ActionClaims {
+            incident_id: incident_id.to_string(),
+            user_id: user_id.to_string(),
+            action,
+            expires_at,
+        };
+        format!("{}/incident/action/{}", api_url, generate(secret, &claims))
+    };
+    (link(IncidentAction::Acknowledge), link(IncidentAction::Resolve))
+}
+
+/// Short-lived code a user pastes into `/uptime link <code>` to tie their Slack
+/// account to `user_id`. Same format as action tokens, with a `link` marker
+/// so neither kind of token is accepted as the other.
+pub fn generate_link_code(secret: &str, user_id: &str, expires_at: DateTime<Utc>) -> String {
+    let encoded = hex::encode(format!("link|{}|{}", user_id, expires_at.timestamp()));
+    let signature = hex::encode(mac(secret, encoded.as_bytes()).finalize().into_bytes());
+    format!("{}.{}", encoded, signature)
+}
+
+/// Returns the user id a link code was issued to.
+pub fn verify_link_code(secret: &str, code: &str, now: DateTime<Utc>) -> Result<String, TokenError> {
+    let (encoded, signature) = code.split_once('.').ok_or(TokenError::Malformed)?;
+    let signature = hex::decode(signature).map_err(|_| TokenError::Malformed)?;
+    mac(secret, encoded.as_bytes())
+        .verify_slice(&signature)
+        .map_err(|_| TokenError::BadSignature)?;
+
+    let body = String::from_utf8(hex::decode(encoded).map_err(|_| TokenError::Malformed)?).map_err(|_| TokenError::Malformed)?;
+    let mut parts = body.split('|');
+    let (Some("link"), Some(user_id), Some(expires), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+        return Err(TokenError::Malformed);
+    };
+    let expires_at = expires
+        .parse::<i64>()
+        .ok()
+        .and_then(|t| DateTime::from_timestamp(t, 0))
+        .ok_or(TokenError::Malformed)?;
+
+    if now >= expires_at {
+        return Err(TokenError::Expired);
+    }
+    Ok(user_id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "action-secret";
+
+    fn claims(action: IncidentAction) -> ActionClaims {
+        ActionClaims {
+            incident_id: "2f1c7a9e-0d4b-4e53-9a61-5b8f0c3d2e17".to_string(),
+            user_id: "8d3e5b21-6c4f-4a0e-b7d2-1e9f3a6c5b48".to_string(),
+            action,
+            expires_at: DateTime::from_timestamp(1_760_000_000, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn round_trips_claims() {
+        let issued = claims(IncidentAction::Resolve);
+        let now = issued.expires_at - Duration::minutes(1);
+
+        let verified = verify(SECRET, &generate(SECRET, &issued), now).unwrap();
+        assert_eq!(verified.incident_id, issued.incident_id);
+        assert_eq!(verified.user_id, issued.user_id);
+        assert_eq!(verified.action, IncidentAction::Resolve);
+        assert_eq!(verified.expires_at, issued.expires_at);
+    }
+
+    #[test]
+    fn expires_at_expiry() {
+        let issued = claims(IncidentAction::Acknowledge);
+        let token = generate(SECRET, &issued);
+
+        assert_eq!(verify(SECRET, &token, issued.expires_at).unwrap_err(), TokenError::Expired);
+    }
+
+    #[test]
+    fn rejects_forged_tokens() {
+        let issued = claims(IncidentAction::Acknowledge);
+        let now = issued.expires_at - Duration::minutes(1);
+        let token = generate(SECRET, &issued);
+        let (_, signature) = token.split_once('.').unwrap();
+        // the same signature on a resolve body
+        let resolve = generate(SECRET, &claims(IncidentAction::Resolve));
+        let (resolve_body, _) = resolve.split_once('.').unwrap();
+
+        assert_eq!(verify("other", &token, now).unwrap_err(), TokenError::BadSignature);
+        assert_eq!(verify(SECRET, &format!("{}.{}", resolve_body, signature), now).unwrap_err(), TokenError::BadSignature);
+        assert_eq!(verify(SECRET, "no-separator", now).unwrap_err(), TokenError::Malformed);
+        assert_eq!(verify(SECRET, "abc.not-hex", now).unwrap_err(), TokenError::Malformed);
+    }
+
+    #[test]
+    fn links_carry_one_token_per_action() {
+        let (acknowledge, resolve) = action_links(SECRET, "https://api.example.com/v1", "incident", "user", Duration::hours(1));
+        let token = |link: &str| link.strip_prefix("https://api.example.com/v1/incident/action/").unwrap().to_string();
+
+        assert_eq!(verify(SECRET, &token(&acknowledge), Utc::now()).unwrap().action, IncidentAction::Acknowledge);
+        assert_eq!(verify(SECRET, &token(&resolve), Utc::now()).unwrap().action, IncidentAction::Resolve);
+    }
+
+    #[test]
+    fn link_codes_round_trip_and_expire() {
+        let expires_at = DateTime::from_timestamp(1_760_000_000, 0).unwrap();
+        let code = generate_link_code(SECRET, "user", expires_at);
+
+        assert_eq!(verify_link_code(SECRET, &code, expires_at - Duration::seconds(1)).unwrap(), "user");
+        assert_eq!(verify_link_code(SECRET, &code, expires_at).unwrap_err(), TokenError::Expired);
+        assert_eq!(verify_link_code("other", &code, expires_at - Duration::seconds(1)).unwrap_err(), TokenError::BadSignature);
+    }
+
+    #[test]
+    fn link_codes_and_action_tokens_are_not_interchangeable() {
+        let issued = claims(IncidentAction::Acknowledge);
+        let now = issued.expires_at - Duration::minutes(1);
+        let code = generate_link_code(SECRET, &issued.user_id, issued.expires_at);
+
+        assert_eq!(verify(SECRET, &code, now).unwrap_err(), TokenError::Malformed);
+        assert_eq!(verify_link_code(SECRET, &generate(SECRET, &issued), now).unwrap_err(), TokenError::Malformed);
+    }
+}
@@ -0,0 +1,125 @@
+use db::models::notification_channel::NotificationChannel;
+use reqwest::{Client, RequestBuilder};
+use serde_json::{json, Value};
+
+use crate::config::NotifierConfig;
+use crate::dispatch::Channel;
+use crate::payload::StatusChangePayload;
+
+const COLOR_DOWN: u32 = 0xe01e5a;
+const COLOR_UP: u32 = 0x2eb67d;
+
+/// Backslash-escapes Discord markdown, so a url shows as it was typed.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '~' | '`' | '|' | '>' | '[' | ']' | '(' | ')') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Discord webhook, rendered as a single embed.
+pub struct DiscordChannel {
+    id: String,
+    url: String,
+    dashboard_url: String,
+}
+
+impl DiscordChannel {
+    pub fn new(channel: &NotificationChannel, config: &NotifierConfig) -> Self {
+        Self {
+            id: channel.id.clone(),
+            url: channel.target.clone(),
+            dashboard_url: config.dashboard_url.clone(),
+        }
+    }
+
+    pub fn render(&self, payload: &StatusChangePayload) -> Value {
+        let color = if payload.is_down() { COLOR_DOWN } else { COLOR_UP };
+        let fields: Vec<Value> = payload
+            .regions
+            .iter()
+            .map(|r| json!({
+                "name": r.region_id,
+                "value": format!("{} · {} ms", r.status, r.response_time_ms),
+                "inline": true,
+            }))
+            .collect();
+
+        let mut description = format!("Status changed from **{}** to **{}**\nIncident `{}`", payload.previous_status, payload.new_status, payload.incident.id);
+        if let Some(links) = &payload.links {
+            description.push_str(&format!("\n[Acknowledge]({}) · [Resolve]({})", links.acknowledge, links.resolve));
+        }
+
+        json!({
+            "embeds": [{
+                "title": escape_markdown(&payload.headline()),
+                "url": payload.website_link(&self.dashboard_url),
+                "description": description,
+                "color": color,
+                "fields": fields,
+                "timestamp": payload.occurred_at.to_rfc3339(),
+            }]
+        })
+    }
+}
+
+impl Channel for DiscordChannel {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn build_request(&self, http: &Client, payload: &StatusChangePayload) -> RequestBuilder {
+        http.post(&self.url).json(&self.render(payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use db::models::website_tick::WebsiteStatus;
+
+    use super::*;
+    use crate::payload::ActionLinksPayload;
+
+    fn channel() -> DiscordChannel {
+        DiscordChannel {
+            id: "channel".to_string(),
+            url: "https://discord.com/api/webhooks/1/x".to_string(),
+            dashboard_url: "https://app.example.com".to_string(),
+        }
+    }
+
+    #[test]
+    fn renders_one_embed() {
+        let message = channel().render(&StatusChangePayload::example("https://example.com", WebsiteStatus::Down));
+        let embed = &message["embeds"][0];
+
+        assert_eq!(embed["title"], "https://example.com is down");
+        assert_eq!(embed["url"], "https://app.example.com/website/website-1");
+        assert_eq!(embed["color"], COLOR_DOWN);
+        assert_eq!(embed["description"], "Status changed from **up** to **down**\nIncident `incident-1`");
+        assert_eq!(embed["fields"][0], json!({ "name": "eu", "value": "down · 120 ms", "inline": true }));
+        assert_eq!(embed["timestamp"], "2026-03-02T10:00:00+00:00");
+    }
+
+    #[test]
+    fn links_actions_and_colours_recovery() {
+        let mut payload = StatusChangePayload::example("https://example.com", WebsiteStatus::Up);
+        payload.links = Some(ActionLinksPayload { acknowledge: "https://api/ack".to_string(), resolve: "https://api/resolve".to_string() });
+        let embed = &channel().render(&payload)["embeds"][0];
+
+        assert_eq!(embed["title"], "https://example.com is back up");
+        assert_eq!(embed["color"], COLOR_UP);
+        assert!(embed["description"].as_str().unwrap().ends_with("\n[Acknowledge](https://api/ack) · [Resolve](https://api/resolve)"));
+    }
+
+    #[test]
+    fn escapes_markdown_in_urls() {
+        let message = channel().render(&StatusChangePayload::example("https://example.com/__init__*[x](y)", WebsiteStatus::Down));
+
+        assert_eq!(message["embeds"][0]["title"], r"https://example.com/\_\_init\_\_\*\[x\]\(y\) is down");
+    }
+}
@@ -0,0 +1,131 @@
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use db::models::notification_channel::{
+    NotificationChannel, CHANNEL_DISCORD, CHANNEL_OPSGENIE, CHANNEL_PAGERDUTY, CHANNEL_SLACK, CHANNEL_TEAMS,
+    CHANNEL_WEBHOOK,
+};
+use db::models::notification_delivery::DeliveryOutcome;
+use reqwest::{Client, RequestBuilder, StatusCode};
+
+use crate::config::NotifierConfig;
+use crate::discord::DiscordChannel;
+use crate::opsgenie::OpsgenieChannel;
+use crate::pagerduty::PagerDutyChannel;
+use crate::payload::StatusChangePayload;
+use crate::slack::SlackChannel;
+use crate::ssrf::{GuardedClient, SendError};
+use crate::teams::TeamsChannel;
+use crate::webhook::WebhookChannel;
+
+/// A destination alerts can be sent to.
+///
+/// Implementations only describe how a payload is rendered into a request;
+/// retries, backoff and the delivery log are shared by the dispatcher.
+pub trait Channel: Send + Sync {
+    fn id(&self) -> &str;
+
+    /// Builds the request for one delivery attempt. Called again for every retry,
+    /// so anything time-sensitive (signatures, timestamps) is computed fresh.
+    fn build_request(&self, http: &Client, payload: &StatusChangePayload) -> RequestBuilder;
+}
+
+/// Picks the implementation for a stored channel, `None` for kinds this notifier doesn't know.
+pub fn channel_for(channel: &NotificationChannel, config: &NotifierConfig) -> Option<Box<dyn Channel>> {
+    match channel.kind.as_str() {
+        CHANNEL_WEBHOOK => Some(Box::new(WebhookChannel::new(channel))),
+        CHANNEL_SLACK => Some(Box::new(SlackChannel::new(channel, config))),
+        CHANNEL_DISCORD => Some(Box::new(DiscordChannel::new(channel, config))),
+        CHANNEL_TEAMS => Some(Box::new(TeamsChannel::new(channel, config))),
+        CHANNEL_PAGERDUTY => Some(Box::new(PagerDutyChannel::new(channel, config))),
+        CHANNEL_OPSGENIE => Some(Box::new(OpsgenieChannel::new(channel, config))),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retrying after the given (1-based) attempt: base, 2x base, 4x base, ... capped at `max_delay`.
+    pub fn delay_after(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DeliveryAttempt {
+    pub attempt: u32,
+    pub status_code: Option<u16>,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+    pub attempted_at: DateTime<Utc>,
+    /// Whether trying again later could succeed.
+    pub retryable: bool,
+}
+
+impl DeliveryAttempt {
+    pub fn delivered(&self) -> bool {
+        self.error.is_none()
+    }
+
+    pub fn outcome(&self) -> DeliveryOutcome {
+        DeliveryOutcome {
+            attempt: self.attempt as i32,
+            status_code: self.status_code.map(i32::from),
+            latency_ms: self.latency_ms.min(i32::MAX as u64) as i32,
+            error: self.error.clone(),
+            attempted_at: self.attempted_at.naive_utc(),
+        }
+    }
+}
+
+/// Timeouts and throttling are worth retrying, other client errors won't change on their own.
+fn is_retryable(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::REQUEST_TIMEOUT || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Makes a single delivery attempt. Retrying is up to the caller, see [`RetryPolicy`].
+pub async fn attempt_delivery(http: &GuardedClient, channel: &dyn Channel, payload: &StatusChangePayload, attempt: u32) -> DeliveryAttempt {
+    let attempted_at = Utc::now();
+    let start = Instant::now();
+    let result = http.send(channel.build_request(http.http(), payload)).await;
+    let latency_ms = start.elapsed().as_millis() as u64;
+
+    let (status_code, error, retryable) = match result {
+        Ok(resp) if resp.status().is_success() => (Some(resp.status().as_u16()), None, false),
+        Ok(resp) => (
+            Some(resp.status().as_u16()),
+            Some(format!("receiver responded with {}", resp.status())),
+            is_retryable(resp.status()),
+        ),
+        Err(e @ SendError::Refused(_)) => (None, Some(e.to_string()), false),
+        Err(e @ SendError::Http(_)) => (None, Some(e.to_string()), true),
+    };
+
+    tracing::info!(
+        channel_id = %channel.id(),
+        incident_id = %payload.incident.id,
+        attempt,
+        status_code,
+        latency_ms,
+        error = error.as_deref().unwrap_or(""),
+        "notification delivery attempt"
+    );
+    DeliveryAttempt { attempt, status_code, latency_ms, error, attempted_at, retryable }
+}
@@ -0,0 +1,48 @@
+use lettre::message::header::{HeaderName, HeaderValue};
+use lettre::message::Mailbox;
+use lettre::transport::smtp::AsyncSmtpTransport;
+use lettre::{AsyncTransport, Message, Tokio1Executor};
+
+use crate::config::NotifierConfig;
+
+/// SMTP delivery shared by everything that sends email.
+pub struct Mailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+pub struct Email<'a> {
+    pub to: &'a str,
+    pub subject: &'a str,
+    pub body: &'a str,
+    /// Adds `List-Unsubscribe` + `List-Unsubscribe-Post` so mail clients offer one-click unsubscribe.
+    pub unsubscribe_url: Option<&'a str>,
+}
+
+impl Mailer {
+    /// `None` when SMTP isn't configured.
+    pub fn from_config(config: &NotifierConfig) -> Result<Option<Self>, String> {
+        let Some(url) = &config.smtp_url else {
+            return Ok(None);
+        };
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::from_url(url)
+            .map_err(|e| format!("invalid SMTP_URL: {}", e))?
+            .build();
+        let from = config.smtp_from.parse().map_err(|e| format!("invalid SMTP_FROM: {}", e))?;
+        Ok(Some(Self { transport, from }))
+    }
+
+    /// Sends one plain text email. Errors are returned as text for the delivery log.
+    pub async fn send(&self, email: Email<'_>) -> Result<(), String> {
+        let to: Mailbox = email.to.parse().map_err(|e| format!("invalid recipient: {}", e))?;
+        let mut builder = Message::builder().from(self.from.clone()).to(to).subject(email.subject);
+        if let Some(url) = email.unsubscribe_url {
+            builder = builder
+                .raw_header(HeaderValue::new(HeaderName::new_from_ascii_str("List-Unsubscribe"), format!("<{}>", url)))
+                .raw_header(HeaderValue::new(HeaderName::new_from_ascii_str("List-Unsubscribe-Post"), "List-Unsubscribe=One-Click".to_string()));
+        }
+        let message = builder.body(email.body.to_string()).map_err(|e| e.to_string())?;
+
+        self.transport.send(message).await.map(|_| ()).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,33 @@
+use std::fmt;
+
+use db::error::DbError;
+use redisstream::RedisError;
+
+#[derive(Debug)]
+pub enum NotifyError {
+    Db(DbError),
+    Queue(RedisError),
+}
+
+impl fmt::Display for NotifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotifyError::Db(e) => write!(f, "database error: {}", e),
+            NotifyError::Queue(e) => write!(f, "notification queue error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for NotifyError {}
+
+impl From<DbError> for NotifyError {
+    fn from(e: DbError) -> Self {
+        NotifyError::Db(e)
+    }
+}
+
+impl From<RedisError> for NotifyError {
+    fn from(e: RedisError) -> Self {
+        NotifyError::Queue(e)
+    }
+}
@@ -0,0 +1,182 @@
+pub mod action_token;
+pub mod config;
+pub mod discord;
+pub mod dispatch;
+pub mod email;
+pub mod error;
+pub mod opsgenie;
+pub mod pagerduty;
+pub mod payload;
+pub mod slack;
+pub mod ssrf;
+pub mod subscriber;
+pub mod teams;
+pub mod webhook;
+
+use chrono::Utc;
+use db::db::Db;
+use db::error::OptionalExt;
+use db::models::incident::{Incident, StatusChange};
+use db::models::notification_channel::NotificationChannel;
+use db::models::website::Website;
+use db::models::website_tick::WebsiteStatus;
+use redisstream::{schedule_notification_retry, x_add_notifications, NotificationEvent};
+
+use crate::action_token::action_links;
+use crate::config::NotifierConfig;
+use crate::dispatch::{attempt_delivery, channel_for};
+use crate::error::NotifyError;
+use crate::payload::{ActionLinksPayload, StatusChangePayload};
+use crate::ssrf::GuardedClient;
+
+/// Consumer group every notifier instance joins on the notification stream.
+pub const NOTIFIER_GROUP: &str = "notifier";
+
+/// Queues a status change for the channels that should hear about it.
+///
+/// Websites without an escalation policy alert every channel of their owner. With a
+/// policy, going down notifies the levels that are already due (see [`escalate_incident`])
+/// and recovering notifies every level that was paged.
+pub async fn enqueue_status_change(db: &Db, change: &StatusChange) -> Result<usize, NotifyError> {
+    let Some(policy_id) = &change.website.escalation_policy_id else {
+        let channels = db.get_website_notification_channels(change.website.id.clone()).await?;
+        return enqueue(db, change, channels).await;
+    };
+    if change.new_status == WebsiteStatus::Down {
+        return escalate_incident(db, &change.website, &change.incident).await;
+    }
+
+    let channels = {
+        let levels = db.get_escalation_levels(policy_id.clone()).await?;
+        let mut channels = Vec::new();
+        for level in levels.iter().take(change.incident.escalation_level.max(0) as usize) {
+            channels.extend(db.get_escalation_level_channels(level).await?);
+        }
+        channels
+    };
+    enqueue(db, change, channels).await
+}
+
+/// Notifies every level of the website's policy whose delay has elapsed and that hasn't been paged yet.
+pub async fn escalate_incident(db: &Db, website: &Website, incident: &Incident) -> Result<usize, NotifyError> {
+    let Some(policy_id) = &website.escalation_policy_id else {
+        return Ok(0);
+    };
+    let now = Utc::now().naive_utc();
+
+    let channels = {
+        let levels = db.get_escalation_levels(policy_id.clone()).await?;
+        let mut channels = Vec::new();
+        let mut level = incident.escalation_level;
+        while let Some(next) = levels.get(level.max(0) as usize) {
+            if now < incident.opened_at + chrono::Duration::minutes(next.delay_minutes.into()) {
+                break;
+            }
+            // another notifier already paged this level
+            if !db.advance_incident_escalation(incident.id.clone(), level).await? {
+                break;
+            }
+            tracing::info!(incident_id = %incident.id, level = level + 1, "escalating incident");
+            channels.extend(db.get_escalation_level_channels(next).await?);
+            level += 1;
+        }
+        channels
+    };
+    if channels.is_empty() {
+        return Ok(0);
+    }
+
+    let change = StatusChange {
+        website: website.clone(),
+        previous_status: WebsiteStatus::Up,
+        new_status: WebsiteStatus::Down,
+        incident: incident.clone(),
+        occurred_at: incident.opened_at,
+    };
+    enqueue(db, &change, channels).await
+}
+
+/// Runs [`escalate_incident`] for every incident still waiting on an acknowledgement.
+pub async fn escalate_due_incidents(db: &Db) -> Result<usize, NotifyError> {
+    let incidents = db.get_escalating_incidents().await?;
+
+    let mut queued = 0;
+    let now = Utc::now().naive_utc();
+    for (incident, website) in &incidents {
+        // escalation pauses while the website is under maintenance
+        if db.is_in_maintenance(website.id.clone(), now).await? {
+            continue;
+        }
+        queued += escalate_incident(db, website, incident).await?;
+    }
+    Ok(queued)
+}
+
+/// The payload is rendered once here so retries send the region results as they were
+/// when the change happened.
+async fn enqueue(db: &Db, change: &StatusChange, mut channels: Vec<NotificationChannel>) -> Result<usize, NotifyError> {
+    channels.sort_by(|a, b| a.id.cmp(&b.id));
+    channels.dedup_by(|a, b| a.id == b.id);
+    if channels.is_empty() {
+        return Ok(0);
+    }
+
+    let region_ticks = db.get_latest_region_ticks(change.website.id.clone()).await?;
+    let payload = serde_json::to_string(&StatusChangePayload::new(change, &region_ticks)).expect("payload serializes to json");
+    let events: Vec<NotificationEvent> = channels
+        .iter()
+        .map(|c| NotificationEvent {
+            incident_id: change.incident.id.clone(),
+            channel_id: c.id.clone(),
+            payload: payload.clone(),
+            attempt: 1,
+        })
+        .collect();
+
+    x_add_notifications(&events).await?;
+    Ok(events.len())
+}
+
+/// Delivers one queued event, records the attempt and parks the event for a retry when it failed.
+pub async fn dispatch(db: &Db, http: &GuardedClient, config: &NotifierConfig, event: &NotificationEvent) -> Result<(), NotifyError> {
+    let stored = db.get_notification_channel(event.channel_id.clone()).await.optional()?;
+    let Some(stored) = stored else {
+        // channel was deleted after the alert was queued
+        return Ok(());
+    };
+    let Some(channel) = channel_for(&stored, config) else {
+        tracing::warn!(channel_id = %stored.id, kind = %stored.kind, "skipping channel of unknown kind");
+        return Ok(());
+    };
+    let mut payload: StatusChangePayload = match serde_json::from_str(&event.payload) {
+        Ok(payload) => payload,
+        Err(e) => {
+            tracing::warn!(incident_id = %event.incident_id, "dropping unreadable notification payload: {}", e);
+            return Ok(());
+        }
+    };
+
+    // links are bound to the channel's owner, who may be an on-call user rather than the website owner
+    if let (Some(secret), true) = (&config.action_secret, payload.is_down()) {
+        let (acknowledge, resolve) = action_links(secret, &config.versioned_api_url(), &event.incident_id, &stored.user_id, config.action_link_ttl);
+        payload.links = Some(ActionLinksPayload { acknowledge, resolve });
+    }
+
+    let attempt = attempt_delivery(http, channel.as_ref(), &payload, event.attempt).await;
+    db.record_notification_delivery(event.incident_id.clone(), event.channel_id.clone(), attempt.outcome()).await?;
+
+    if attempt.delivered() {
+        return Ok(());
+    }
+    if attempt.retryable && event.attempt < config.retry.max_attempts {
+        let delay = chrono::Duration::from_std(config.retry.delay_after(event.attempt)).unwrap_or(chrono::Duration::MAX);
+        let retry = NotificationEvent {
+            attempt: event.attempt + 1,
+            ..event.clone()
+        };
+        schedule_notification_retry(&retry, (Utc::now() + delay).timestamp_millis()).await?;
+    } else {
+        tracing::warn!(channel_id = %event.channel_id, incident_id = %event.incident_id, "giving up on delivery after {} attempts", event.attempt);
+    }
+    Ok(())
+}
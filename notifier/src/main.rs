@@ -0,0 +1,140 @@
+use std::env;
+use std::sync::Arc;
+
+use chrono::Utc;
+use db::db::Db;
+use futures::future::join_all;
+use tokio::time::Duration;
+
+use notifier::email::Mailer;
+use notifier::ssrf::{GuardedClient, SsrfPolicy};
+use notifier::subscriber::{announce_maintenance_windows, dispatch_subscriber_update};
+use notifier::{config::NotifierConfig, dispatch, escalate_due_incidents, NOTIFIER_GROUP};
+use redisstream::{
+    ensure_notification_group_exists, ensure_subscriber_group_exists, init_redis, promote_due_notifications,
+    promote_due_subscriber_updates, x_ack_notifications, x_ack_subscriber_updates, x_claim_stale_notifications,
+    x_claim_stale_subscriber_updates, x_read_notifications, x_read_subscriber_updates,
+};
+
+/// Unacked entries idle this long are taken over: whoever read them failed to handle them or died.
+const RECLAIM_IDLE_MS: usize = 60_000;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let redis_url = env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1/".into());
+    let consumer = env::var("NOTIFIER_ID").unwrap_or_else(|_| "notifier-1".into());
+
+    init_redis(&redis_url).await?;
+    ensure_notification_group_exists(NOTIFIER_GROUP).await?;
+    ensure_subscriber_group_exists(NOTIFIER_GROUP).await?;
+
+    let db = Db::new()?;
+    let config = NotifierConfig::default();
+    // webhook urls come from users, keep them off our own network like checks
+    let http = GuardedClient::new(Arc::new(SsrfPolicy::from_env()?), Duration::from_secs(10))?;
+    let mailer = Mailer::from_config(&config).map_err(anyhow::Error::msg)?;
+    if mailer.is_none() {
+        tracing::warn!("SMTP_URL is not set, status page email subscribers won't receive updates");
+    }
+
+    // page the next escalation level of unacknowledged incidents once its delay is up
+    let escalation_db = db.clone();
+    tokio::spawn(async move {
+        loop {
+            match escalate_due_incidents(&escalation_db).await {
+                Ok(0) => {}
+                Ok(queued) => tracing::info!("queued {} escalation notifications", queued),
+                Err(e) => tracing::error!("escalation error: {}", e),
+            }
+            tokio::time::sleep(Duration::from_secs(30)).await;
+        }
+    });
+
+    // announce newly scheduled maintenance to status page subscribers
+    let announce_db = db.clone();
+    let announce_config = config.clone();
+    tokio::spawn(async move {
+        loop {
+            match announce_maintenance_windows(&announce_db, &announce_config).await {
+                Ok(0) => {}
+                Ok(queued) => tracing::info!("queued {} maintenance announcements", queued),
+                Err(e) => tracing::error!("maintenance announcement error: {}", e),
+            }
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        }
+    });
+
+    // deliver status page subscriber updates, independently of alert notifications
+    let subscriber_db = db.clone();
+    let subscriber_config = config.clone();
+    let subscriber_http = http.clone();
+    let subscriber_consumer = consumer.clone();
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = deliver_subscriber_updates(&subscriber_db, &subscriber_http, mailer.as_ref(), &subscriber_config, &subscriber_consumer).await {
+                tracing::error!("subscriber delivery error: {}", e);
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    });
+
+    loop {
+        // move retries whose backoff has elapsed back onto the stream
+        let promoted = promote_due_notifications(Utc::now().timestamp_millis()).await?;
+        if promoted > 0 {
+            tracing::info!("re-queued {} notification retries", promoted);
+        }
+
+        // entries a notifier couldn't handle come back before new ones are read
+        let mut msgs = x_claim_stale_notifications(NOTIFIER_GROUP, &consumer, RECLAIM_IDLE_MS, 20).await?;
+        if msgs.is_empty() {
+            // short block so due retries are picked up promptly
+            msgs = match x_read_notifications(NOTIFIER_GROUP, &consumer, 20, 1000).await? {
+                Some(m) if !m.is_empty() => m,
+                _ => continue,
+            };
+        }
+
+        let results = join_all(msgs.iter().map(|msg| dispatch(&db, &http, &config, &msg.message))).await;
+        let mut handled = Vec::new();
+        for (msg, result) in msgs.into_iter().zip(results) {
+            match result {
+                // delivered, parked in the retry set or given up on
+                Ok(()) => handled.push(msg.id),
+                Err(e) => tracing::error!(incident_id = %msg.message.incident_id, channel_id = %msg.message.channel_id, "dispatch error, leaving it pending: {}", e),
+            }
+        }
+        x_ack_notifications(NOTIFIER_GROUP, &handled).await?;
+    }
+}
+
+async fn deliver_subscriber_updates(
+    db: &Db,
+    http: &GuardedClient,
+    mailer: Option<&Mailer>,
+    config: &NotifierConfig,
+    consumer: &str,
+) -> anyhow::Result<()> {
+    promote_due_subscriber_updates(Utc::now().timestamp_millis()).await?;
+
+    let mut msgs = x_claim_stale_subscriber_updates(NOTIFIER_GROUP, consumer, RECLAIM_IDLE_MS, 20).await?;
+    if msgs.is_empty() {
+        msgs = match x_read_subscriber_updates(NOTIFIER_GROUP, consumer, 20, 1000).await? {
+            Some(m) if !m.is_empty() => m,
+            _ => return Ok(()),
+        };
+    }
+
+    let results = join_all(msgs.iter().map(|msg| dispatch_subscriber_update(db, http, mailer, config, &msg.message))).await;
+    let mut handled = Vec::new();
+    for (msg, result) in msgs.into_iter().zip(results) {
+        match result {
+            Ok(()) => handled.push(msg.id),
+            Err(e) => tracing::error!(subscriber_id = %msg.message.subscriber_id, "subscriber dispatch error, leaving it pending: {}", e),
+        }
+    }
+    x_ack_subscriber_updates(NOTIFIER_GROUP, &handled).await?;
+    Ok(())
+}
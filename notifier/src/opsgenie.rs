@@ -0,0 +1,123 @@
+use db::models::notification_channel::NotificationChannel;
+use reqwest::{header::AUTHORIZATION, Client, RequestBuilder};
+use serde_json::{json, Value};
+
+use crate::config::NotifierConfig;
+use crate::dispatch::Channel;
+use crate::payload::StatusChangePayload;
+
+/// Opsgenie Alert API. The channel target is an API integration key.
+///
+/// Alerts are created with the incident id as `alias`, which Opsgenie deduplicates on,
+/// and closed by that alias once the website recovers.
+pub struct OpsgenieChannel {
+    id: String,
+    api_key: String,
+    api_url: String,
+    dashboard_url: String,
+}
+
+impl OpsgenieChannel {
+    pub fn new(channel: &NotificationChannel, config: &NotifierConfig) -> Self {
+        Self {
+            id: channel.id.clone(),
+            api_key: channel.target.clone(),
+            api_url: config.opsgenie_url.clone(),
+            dashboard_url: config.dashboard_url.clone(),
+        }
+    }
+
+    pub fn render(&self, payload: &StatusChangePayload) -> Value {
+        if !payload.is_down() {
+            return json!({
+                "source": "betteruptime",
+                "note": payload.headline(),
+            });
+        }
+
+        json!({
+            "message": payload.headline(),
+            "alias": payload.incident.id,
+            "description": format!("{}\n\n{}", payload.region_lines().join("\n"), payload.website_link(&self.dashboard_url)),
+            "source": "betteruptime",
+            "entity": payload.website.url,
+            "priority": "P1",
+            "details": {
+                "website_id": payload.website.id,
+                "previous_status": payload.previous_status,
+                "new_status": payload.new_status,
+            }
+        })
+    }
+}
+
+impl Channel for OpsgenieChannel {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn build_request(&self, http: &Client, payload: &StatusChangePayload) -> RequestBuilder {
+        let url = if payload.is_down() {
+            format!("{}/v2/alerts", self.api_url)
+        } else {
+            format!("{}/v2/alerts/{}/close?identifierType=alias", self.api_url, payload.incident.id)
+        };
+
+        http.post(url)
+            .header(AUTHORIZATION, format!("GenieKey {}", self.api_key))
+            .json(&self.render(payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use db::models::website_tick::WebsiteStatus;
+
+    use super::*;
+
+    fn channel() -> OpsgenieChannel {
+        OpsgenieChannel {
+            id: "channel".to_string(),
+            api_key: "api-key".to_string(),
+            api_url: "https://api.opsgenie.com".to_string(),
+            dashboard_url: "https://app.example.com".to_string(),
+        }
+    }
+
+    #[test]
+    fn alias_is_the_incident_id() {
+        let first = StatusChangePayload::example("https://example.com", WebsiteStatus::Down);
+        let mut again = first.clone();
+        again.occurred_at += Duration::minutes(5);
+        again.regions[0].response_time_ms = 900;
+
+        assert_eq!(channel().render(&first)["alias"], "incident-1");
+        assert_eq!(channel().render(&again)["alias"], "incident-1");
+    }
+
+    #[test]
+    fn creates_an_alert_while_down() {
+        let payload = StatusChangePayload::example("https://example.com", WebsiteStatus::Down);
+        let request = channel().build_request(&Client::new(), &payload).build().unwrap();
+        let alert = channel().render(&payload);
+
+        assert_eq!(request.url().as_str(), "https://api.opsgenie.com/v2/alerts");
+        assert_eq!(request.headers()[AUTHORIZATION], "GenieKey api-key");
+        assert_eq!(alert["message"], "https://example.com is down");
+        assert_eq!(alert["priority"], "P1");
+        assert_eq!(alert["entity"], "https://example.com");
+        assert_eq!(alert["description"], "eu: down (120 ms)\n\nhttps://app.example.com/website/website-1");
+        assert_eq!(alert["details"]["website_id"], "website-1");
+    }
+
+    #[test]
+    fn closes_the_alert_by_alias_on_recovery() {
+        let payload = StatusChangePayload::example("https://example.com", WebsiteStatus::Up);
+        let request = channel().build_request(&Client::new(), &payload).build().unwrap();
+
+        assert_eq!(request.url().as_str(), "https://api.opsgenie.com/v2/alerts/incident-1/close?identifierType=alias");
+        assert_eq!(request.headers()[AUTHORIZATION], "GenieKey api-key");
+        assert_eq!(channel().render(&payload), json!({ "source": "betteruptime", "note": "https://example.com is back up" }));
+    }
+}
@@ -0,0 +1,137 @@
+use db::models::notification_channel::NotificationChannel;
+use reqwest::{Client, RequestBuilder};
+use serde_json::{json, Value};
+
+use crate::config::NotifierConfig;
+use crate::dispatch::Channel;
+use crate::payload::StatusChangePayload;
+
+/// PagerDuty Events API v2. The channel target is the service's integration (routing) key.
+///
+/// The incident id is used as `dedup_key`, so every failure of the same incident
+/// lands on one PagerDuty incident and the recovery resolves it.
+pub struct PagerDutyChannel {
+    id: String,
+    routing_key: String,
+    events_url: String,
+    dashboard_url: String,
+}
+
+impl PagerDutyChannel {
+    pub fn new(channel: &NotificationChannel, config: &NotifierConfig) -> Self {
+        Self {
+            id: channel.id.clone(),
+            routing_key: channel.target.clone(),
+            events_url: format!("{}/v2/enqueue", config.pagerduty_url),
+            dashboard_url: config.dashboard_url.clone(),
+        }
+    }
+
+    pub fn render(&self, payload: &StatusChangePayload) -> Value {
+        if !payload.is_down() {
+            return json!({
+                "routing_key": self.routing_key,
+                "event_action": "resolve",
+                "dedup_key": payload.incident.id,
+            });
+        }
+
+        let mut links = vec![json!({
+            "href": payload.website_link(&self.dashboard_url),
+            "text": "View in dashboard",
+        })];
+        if let Some(action_links) = &payload.links {
+            links.push(json!({ "href": action_links.resolve, "text": "Resolve in betteruptime" }));
+        }
+
+        json!({
+            "routing_key": self.routing_key,
+            "event_action": "trigger",
+            "dedup_key": payload.incident.id,
+            "payload": {
+                "summary": payload.headline(),
+                "source": payload.website.url,
+                "severity": "critical",
+                "timestamp": payload.occurred_at.to_rfc3339(),
+                "component": payload.website.id,
+                "custom_details": {
+                    "previous_status": payload.previous_status,
+                    "new_status": payload.new_status,
+                    "regions": payload.region_lines(),
+                }
+            },
+            "links": links
+        })
+    }
+}
+
+impl Channel for PagerDutyChannel {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn build_request(&self, http: &Client, payload: &StatusChangePayload) -> RequestBuilder {
+        http.post(&self.events_url).json(&self.render(payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use db::models::website_tick::WebsiteStatus;
+
+    use super::*;
+    use crate::payload::ActionLinksPayload;
+
+    fn channel() -> PagerDutyChannel {
+        PagerDutyChannel {
+            id: "channel".to_string(),
+            routing_key: "routing-key".to_string(),
+            events_url: "https://events.pagerduty.com/v2/enqueue".to_string(),
+            dashboard_url: "https://app.example.com".to_string(),
+        }
+    }
+
+    #[test]
+    fn dedup_key_is_the_incident_id() {
+        let first = StatusChangePayload::example("https://example.com", WebsiteStatus::Down);
+        let mut again = first.clone();
+        again.occurred_at += Duration::minutes(5);
+        again.regions[0].response_time_ms = 900;
+        let recovery = StatusChangePayload::example("https://example.com", WebsiteStatus::Up);
+
+        assert_eq!(channel().render(&first)["dedup_key"], "incident-1");
+        assert_eq!(channel().render(&again)["dedup_key"], "incident-1");
+        assert_eq!(channel().render(&recovery)["dedup_key"], "incident-1");
+    }
+
+    #[test]
+    fn triggers_while_down() {
+        let mut payload = StatusChangePayload::example("https://example.com", WebsiteStatus::Down);
+        payload.links = Some(ActionLinksPayload { acknowledge: "https://api/ack".to_string(), resolve: "https://api/resolve".to_string() });
+        let event = channel().render(&payload);
+
+        assert_eq!(event["routing_key"], "routing-key");
+        assert_eq!(event["event_action"], "trigger");
+        assert_eq!(event["payload"]["summary"], "https://example.com is down");
+        assert_eq!(event["payload"]["source"], "https://example.com");
+        assert_eq!(event["payload"]["severity"], "critical");
+        assert_eq!(event["payload"]["component"], "website-1");
+        assert_eq!(event["payload"]["timestamp"], "2026-03-02T10:00:00+00:00");
+        assert_eq!(event["payload"]["custom_details"]["new_status"], "down");
+        assert_eq!(
+            event["links"],
+            json!([
+                { "href": "https://app.example.com/website/website-1", "text": "View in dashboard" },
+                { "href": "https://api/resolve", "text": "Resolve in betteruptime" },
+            ])
+        );
+    }
+
+    #[test]
+    fn resolves_on_recovery() {
+        let event = channel().render(&StatusChangePayload::example("https://example.com", WebsiteStatus::Up));
+
+        assert_eq!(event, json!({ "routing_key": "routing-key", "event_action": "resolve", "dedup_key": "incident-1" }));
+    }
+}
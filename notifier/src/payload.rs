@@ -0,0 +1,150 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use db::models::incident::StatusChange;
+use db::models::website_tick::{WebsiteStatus, WebsiteTick};
+use serde::{Deserialize, Serialize};
+
+/// Bumped whenever a field is removed or changes meaning; additions keep the version.
+pub const PAYLOAD_VERSION: &str = "1";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusChangePayload {
+    pub version: String,
+    /// `website.down` or `website.up`
+    pub event: String,
+    pub website: WebsitePayload,
+    pub previous_status: String,
+    pub new_status: String,
+    pub regions: Vec<RegionResultPayload>,
+    pub incident: IncidentPayload,
+    pub occurred_at: DateTime<Utc>,
+    /// One-click links for the recipient, only present while the website is down.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub links: Option<ActionLinksPayload>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionLinksPayload {
+    pub acknowledge: String,
+    pub resolve: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebsitePayload {
+    pub id: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionResultPayload {
+    pub region_id: String,
+    pub status: String,
+    pub response_time_ms: i32,
+    pub checked_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncidentPayload {
+    pub id: String,
+    pub status: String,
+    pub opened_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+fn utc(t: NaiveDateTime) -> DateTime<Utc> {
+    t.and_utc()
+}
+
+impl StatusChangePayload {
+    pub fn new(change: &StatusChange, region_ticks: &[WebsiteTick]) -> Self {
+        let event = match change.new_status {
+            WebsiteStatus::Down => "website.down",
+            _ => "website.up",
+        };
+
+        Self {
+            version: PAYLOAD_VERSION.to_string(),
+            event: event.to_string(),
+            website: WebsitePayload {
+                id: change.website.id.clone(),
+                url: change.website.url.clone(),
+            },
+            previous_status: change.previous_status.as_str().to_string(),
+            new_status: change.new_status.as_str().to_string(),
+            regions: region_ticks
+                .iter()
+                .map(|t| RegionResultPayload {
+                    region_id: t.region_id.clone(),
+                    status: t.status.as_str().to_string(),
+                    response_time_ms: t.response_time_ms,
+                    checked_at: utc(t.created_at),
+                })
+                .collect(),
+            incident: IncidentPayload {
+                id: change.incident.id.clone(),
+                status: change.incident.status.clone(),
+                opened_at: utc(change.incident.opened_at),
+                resolved_at: change.incident.resolved_at.map(utc),
+            },
+            occurred_at: utc(change.occurred_at),
+            links: None,
+        }
+    }
+
+    pub fn is_down(&self) -> bool {
+        self.new_status == WebsiteStatus::Down.as_str()
+    }
+
+    /// One-line summary used as the title/fallback text by chat channels.
+    pub fn headline(&self) -> String {
+        if self.is_down() {
+            format!("{} is down", self.website.url)
+        } else {
+            format!("{} is back up", self.website.url)
+        }
+    }
+
+    pub fn website_link(&self, dashboard_url: &str) -> String {
+        format!("{}/website/{}", dashboard_url, self.website.id)
+    }
+
+    /// `region: status (123 ms)` per region, for channels that render plain text.
+    pub fn region_lines(&self) -> Vec<String> {
+        self.regions
+            .iter()
+            .map(|r| format!("{}: {} ({} ms)", r.region_id, r.status, r.response_time_ms))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+impl StatusChangePayload {
+    /// A change of `url` to `new_status` seen from one region, for rendering tests.
+    pub(crate) fn example(url: &str, new_status: WebsiteStatus) -> Self {
+        let at = DateTime::parse_from_rfc3339("2026-03-02T10:00:00Z").unwrap().with_timezone(&Utc);
+        let (event, previous_status) = match new_status {
+            WebsiteStatus::Down => ("website.down", WebsiteStatus::Up),
+            _ => ("website.up", WebsiteStatus::Down),
+        };
+        Self {
+            version: PAYLOAD_VERSION.to_string(),
+            event: event.to_string(),
+            website: WebsitePayload { id: "website-1".to_string(), url: url.to_string() },
+            previous_status: previous_status.as_str().to_string(),
+            new_status: new_status.as_str().to_string(),
+            regions: vec![RegionResultPayload {
+                region_id: "eu".to_string(),
+                status: new_status.as_str().to_string(),
+                response_time_ms: 120,
+                checked_at: at,
+            }],
+            incident: IncidentPayload {
+                id: "incident-1".to_string(),
+                status: if new_status == WebsiteStatus::Down { "open" } else { "resolved" }.to_string(),
+                opened_at: at,
+                resolved_at: (new_status != WebsiteStatus::Down).then_some(at),
+            },
+            occurred_at: at,
+            links: None,
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use chrono::{DateTime, Utc};
+use db::models::notification_channel::NotificationChannel;
+use hmac::{Hmac, Mac};
+use reqwest::{Client, RequestBuilder};
+use serde_json::{json, Value};
+use sha2::Sha256;
+
+use crate::config::NotifierConfig;
+use crate::dispatch::Channel;
+use crate::payload::StatusChangePayload;
+
+const COLOR_DOWN: &str = "#e01e5a";
+const COLOR_UP: &str = "#2eb67d";
+
+/// `action_id`s of the interactive incident buttons; the button `value` is the incident id.
+pub const ACTION_ACKNOWLEDGE: &str = "incident_acknowledge";
+pub const ACTION_RESOLVE: &str = "incident_resolve";
+
+/// Slack rejects replays older than five minutes, and so do we.
+const MAX_REQUEST_AGE_SECS: i64 = 60 * 5;
+
+/// Escapes what mrkdwn gives meaning to, so a url can't turn into a link, mention or command.
+fn escape_mrkdwn(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// A url as the target of a `<url|label>` link, where a `|` would end it early.
+fn escape_link_target(url: &str) -> String {
+    escape_mrkdwn(url).replace('|', "%7C")
+}
+
+/// Slack incoming webhook, rendered with Block Kit inside a coloured attachment.
+pub struct SlackChannel {
+    id: String,
+    url: String,
+    dashboard_url: String,
+    interactive: bool,
+}
+
+impl SlackChannel {
+    pub fn new(channel: &NotificationChannel, config: &NotifierConfig) -> Self {
+        Self {
+            id: channel.id.clone(),
+            url: channel.target.clone(),
+            dashboard_url: config.dashboard_url.clone(),
+            interactive: config.slack_signing_secret.is_some(),
+        }
+    }
+
+    pub fn render(&self, payload: &StatusChangePayload) -> Value {
+        let (emoji, color) = if payload.is_down() {
+            (":red_circle:", COLOR_DOWN)
+        } else {
+            (":large_green_circle:", COLOR_UP)
+        };
+        let regions = payload.region_lines().join("\n");
+        let mut buttons = vec![json!({
+            "type": "button",
+            "text": { "type": "plain_text", "text": "View in dashboard" },
+            "url": payload.website_link(&self.dashboard_url),
+        })];
+        // buttons carry both: the url for plain incoming webhooks, the action_id for
+        // webhooks installed through our Slack app, which posts clicks back to the api
+        if payload.is_down() && (self.interactive || payload.links.is_some()) {
+            let mut acknowledge = json!({
+                "type": "button",
+                "style": "primary",
+                "text": { "type": "plain_text", "text": "Acknowledge" },
+                "action_id": ACTION_ACKNOWLEDGE,
+                "value": payload.incident.id,
+            });
+            let mut resolve = json!({
+                "type": "button",
+                "text": { "type": "plain_text", "text": "Resolve" },
+                "action_id": ACTION_RESOLVE,
+                "value": payload.incident.id,
+            });
+            if let Some(links) = &payload.links {
+                acknowledge["url"] = json!(links.acknowledge);
+                resolve["url"] = json!(links.resolve);
+            }
+            buttons.push(acknowledge);
+            buttons.push(resolve);
+        }
+
+        json!({
+            "text": format!("{} {}", emoji, escape_mrkdwn(&payload.headline())),
+            "attachments": [{
+                "color": color,
+                "blocks": [
+                    {
+                        "type": "section",
+                        "text": {
+                            "type": "mrkdwn",
+                            "text": format!("{} *<{}|{}>*", emoji, escape_link_target(&payload.website.url), escape_mrkdwn(&payload.headline())),
+                        }
+                    },
+                    {
+                        "type": "section",
+                        "fields": [
+                            { "type": "mrkdwn", "text": format!("*Status*\n{} → {}", payload.previous_status, payload.new_status) },
+                            { "type": "mrkdwn", "text": format!("*Incident*\n`{}`", payload.incident.id) },
+                        ]
+                    },
+                    {
+                        "type": "section",
+                        "text": {
+                            "type": "mrkdwn",
+                            "text": if regions.is_empty() { "_no region results yet_".to_string() } else { format!("```{}```", regions) },
+                        }
+                    },
+                    {
+                        "type": "context",
+                        "elements": [
+                            { "type": "mrkdwn", "text": format!("<!date^{}^{{date_short_pretty}} {{time_secs}}|{}>", payload.occurred_at.timestamp(), payload.occurred_at.to_rfc3339()) }
+                        ]
+                    },
+                    {
+                        "type": "actions",
+                        "elements": buttons,
+                    }
+                ]
+            }]
+        })
+    }
+}
+
+impl Channel for SlackChannel {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn build_request(&self, http: &Client, payload: &StatusChangePayload) -> RequestBuilder {
+        http.post(&self.url).json(&self.render(payload))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureError {
+    Missing,
+    Stale,
+    Mismatch,
+}
+
+/// Checks `X-Slack-Signature` (`v0=hex(hmac-sha256("v0:<timestamp>:<body>"))`)
+/// against the raw request body, and that `X-Slack-Request-Timestamp` is recent.
+pub fn verify_request(signing_secret: &str, timestamp: Option<&str>, signature: Option<&str>, body: &[u8], now: DateTime<Utc>) -> Result<(), SignatureError> {
+    let (Some(timestamp), Some(signature)) = (timestamp, signature) else {
+        return Err(SignatureError::Missing);
+    };
+    let sent_at = timestamp.parse::<i64>().map_err(|_| SignatureError::Missing)?;
+    if (now.timestamp() - sent_at).abs() > MAX_REQUEST_AGE_SECS {
+        return Err(SignatureError::Stale);
+    }
+    let signature = signature
+        .strip_prefix("v0=")
+        .and_then(|s| hex::decode(s).ok())
+        .ok_or(SignatureError::Mismatch)?;
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(signing_secret.as_bytes()).expect("hmac accepts keys of any length");
+    mac.update(format!("v0:{}:", timestamp).as_bytes());
+    mac.update(body);
+    mac.verify_slice(&signature).map_err(|_| SignatureError::Mismatch)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use db::models::website_tick::WebsiteStatus;
+
+    use super::*;
+    use crate::payload::ActionLinksPayload;
+
+    fn channel(interactive: bool) -> SlackChannel {
+        SlackChannel {
+            id: "channel".to_string(),
+            url: "https://hooks.slack.com/services/T/B/X".to_string(),
+            dashboard_url: "https://app.example.com".to_string(),
+            interactive,
+        }
+    }
+
+    fn buttons(message: &Value) -> &Vec<Value> {
+        message["attachments"][0]["blocks"][4]["elements"].as_array().unwrap()
+    }
+
+    /// The example request from Slack's "Verifying requests from Slack" docs.
+    const SECRET: &str = "8f742231b10e8888abcd99yyyzzz85a5";
+    const TIMESTAMP: &str = "1531420618";
+    const BODY: &str = "token=xyzz0WbapA4vBCDEFasx0q6G&team_id=T1DC2JH3J&team_domain=testteamnow&channel_id=G8PSS9T3V&channel_name=foobar&user_id=U2CERLKJA&user_name=roadrunner&command=%2Fwebhook-collect&text=&response_url=https%3A%2F%2Fhooks.slack.com%2Fcommands%2FT1DC2JH3J%2F397700885554%2F96rGlfmibIGlgcZRskXaIFfN&trigger_id=398738663015.47445629121.803a0bc887a14d10d2c447fce8b6703c";
+    const SIGNATURE: &str = "v0=a2114d57b48eac39b9ad189dd8316235a7b4a8d21a10bd27519666489c69b503";
+
+    fn sent_at(offset_secs: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(TIMESTAMP.parse::<i64>().unwrap() + offset_secs, 0).unwrap()
+    }
+
+    #[test]
+    fn accepts_recorded_request() {
+        assert_eq!(verify_request(SECRET, Some(TIMESTAMP), Some(SIGNATURE), BODY.as_bytes(), sent_at(30)), Ok(()));
+    }
+
+    #[test]
+    fn rejects_tampered_or_stale_requests() {
+        let tampered = BODY.replace("roadrunner", "coyote");
+        assert_eq!(verify_request(SECRET, Some(TIMESTAMP), Some(SIGNATURE), tampered.as_bytes(), sent_at(0)), Err(SignatureError::Mismatch));
+        assert_eq!(verify_request("other", Some(TIMESTAMP), Some(SIGNATURE), BODY.as_bytes(), sent_at(0)), Err(SignatureError::Mismatch));
+        assert_eq!(verify_request(SECRET, Some(TIMESTAMP), Some(SIGNATURE), BODY.as_bytes(), sent_at(MAX_REQUEST_AGE_SECS + 1)), Err(SignatureError::Stale));
+        assert_eq!(verify_request(SECRET, None, Some(SIGNATURE), BODY.as_bytes(), sent_at(0)), Err(SignatureError::Missing));
+        assert_eq!(verify_request(SECRET, Some(TIMESTAMP), Some("a2114d"), BODY.as_bytes(), sent_at(0)), Err(SignatureError::Mismatch));
+    }
+
+    #[test]
+    fn renders_down_alert() {
+        let message = channel(false).render(&StatusChangePayload::example("https://example.com", WebsiteStatus::Down));
+        let blocks = &message["attachments"][0]["blocks"];
+
+        assert_eq!(message["text"], ":red_circle: https://example.com is down");
+        assert_eq!(message["attachments"][0]["color"], COLOR_DOWN);
+        assert_eq!(blocks[0]["text"]["text"], ":red_circle: *<https://example.com|https://example.com is down>*");
+        assert_eq!(blocks[1]["fields"][0]["text"], "*Status*\nup → down");
+        assert_eq!(blocks[1]["fields"][1]["text"], "*Incident*\n`incident-1`");
+        assert_eq!(blocks[2]["text"]["text"], "```eu: down (120 ms)```");
+        assert_eq!(buttons(&message).len(), 1);
+        assert_eq!(buttons(&message)[0]["url"], "https://app.example.com/website/website-1");
+    }
+
+    #[test]
+    fn recovery_has_no_action_buttons() {
+        let mut payload = StatusChangePayload::example("https://example.com", WebsiteStatus::Up);
+        payload.links = Some(ActionLinksPayload { acknowledge: "https://api/ack".to_string(), resolve: "https://api/resolve".to_string() });
+        let message = channel(true).render(&payload);
+
+        assert_eq!(message["text"], ":large_green_circle: https://example.com is back up");
+        assert_eq!(message["attachments"][0]["color"], COLOR_UP);
+        assert_eq!(buttons(&message).len(), 1);
+    }
+
+    #[test]
+    fn action_buttons_carry_links_and_action_ids() {
+        let mut payload = StatusChangePayload::example("https://example.com", WebsiteStatus::Down);
+        payload.links = Some(ActionLinksPayload { acknowledge: "https://api/ack".to_string(), resolve: "https://api/resolve".to_string() });
+        let message = channel(true).render(&payload);
+        let buttons = buttons(&message);
+
+        assert_eq!(buttons.len(), 3);
+        assert_eq!(buttons[1]["action_id"], ACTION_ACKNOWLEDGE);
+        assert_eq!(buttons[1]["value"], "incident-1");
+        assert_eq!(buttons[1]["url"], "https://api/ack");
+        assert_eq!(buttons[2]["action_id"], ACTION_RESOLVE);
+        assert_eq!(buttons[2]["url"], "https://api/resolve");
+    }
+
+    #[test]
+    fn escapes_urls_in_mrkdwn() {
+        let message = channel(false).render(&StatusChangePayload::example("https://example.com/a|b?q=<!channel>&x=1", WebsiteStatus::Down));
+
+        assert_eq!(message["text"], ":red_circle: https://example.com/a|b?q=&lt;!channel&gt;&amp;x=1 is down");
+        assert_eq!(
+            message["attachments"][0]["blocks"][0]["text"]["text"],
+            ":red_circle: *<https://example.com/a%7Cb?q=&lt;!channel&gt;&amp;x=1|https://example.com/a|b?q=&lt;!channel&gt;&amp;x=1 is down>*"
+        );
+    }
+}
@@ -0,0 +1,225 @@
+//! Keeps checks and outgoing webhooks from reaching our own network. Hostnames are checked
+//! after DNS resolution, so a public name pointing at 127.0.0.1 or the cloud metadata service
+//! is refused just like the literal address.
+
+use std::env;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use hyper::client::connect::dns::Name;
+use ipnet::IpNet;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use reqwest::{redirect, Client, RequestBuilder, Response, Url};
+
+/// Same limit reqwest applies by default.
+const MAX_REDIRECTS: usize = 10;
+
+/// AWS's IPv6 instance metadata endpoint, outside the ranges below.
+const AWS_METADATA_V6: Ipv6Addr = Ipv6Addr::new(0xfd00, 0xec2, 0, 0, 0, 0, 0, 0x254);
+
+pub struct SsrfPolicy {
+    allowed: Vec<IpNet>,
+}
+
+impl SsrfPolicy {
+    /// `MONITOR_ALLOWED_NETWORKS` lists CIDRs checks and webhooks may reach anyway, comma separated,
+    /// for self-hosted deployments that monitor their own LAN, e.g. `10.0.0.0/8,192.168.1.20/32`.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let allowed = env::var("MONITOR_ALLOWED_NETWORKS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|net| !net.is_empty())
+            .map(|net| net.parse().map_err(|e| anyhow::anyhow!("MONITOR_ALLOWED_NETWORKS: {}: {}", net, e)))
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self { allowed })
+    }
+
+    pub fn allows(&self, ip: IpAddr) -> bool {
+        self.allowed.iter().any(|net| net.contains(&ip)) || !is_internal(ip)
+    }
+
+    /// Refuses urls whose host is a forbidden literal address. Hostnames pass here and
+    /// are checked by [`GuardedResolver`], reqwest doesn't resolve literals.
+    pub fn check_url(&self, url: &Url) -> Result<(), String> {
+        let host = url.host_str().ok_or("url has no host")?;
+        let Ok(ip) = host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() else {
+            return Ok(());
+        };
+        if self.allows(ip) {
+            Ok(())
+        } else {
+            Err(format!("{} is not an address outgoing requests may reach", ip))
+        }
+    }
+}
+
+fn is_internal_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    ip.is_loopback()
+        || ip.is_private()
+        // 169.254.0.0/16, home of the metadata service on most clouds
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        // 0.0.0.0/8 reaches the local host on some systems
+        || a == 0
+        // 100.64.0.0/10 carrier-grade NAT, includes Alibaba's metadata service
+        || (a == 100 && (b & 0xc0) == 64)
+        // 198.18.0.0/15 benchmarking, used for internal networks in practice
+        || (a == 198 && (b & 0xfe) == 18)
+        // 240.0.0.0/4 reserved
+        || a >= 240
+}
+
+/// The IPv4 address in two 16 bit segments of an IPv6 address.
+fn embedded_v4(high: u16, low: u16) -> Ipv4Addr {
+    Ipv4Addr::from((u32::from(high) << 16) | u32::from(low))
+}
+
+fn is_internal_v6(ip: Ipv6Addr) -> bool {
+    let segments = ip.segments();
+    let first = segments[0];
+    ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // fc00::/7 unique local
+        || (first & 0xfe00) == 0xfc00
+        // fe80::/10 link-local
+        || (first & 0xffc0) == 0xfe80
+        || ip == AWS_METADATA_V6
+        // 64:ff9b::/96 NAT64 forwards to the IPv4 address in the low 32 bits
+        || (segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] && is_internal_v4(embedded_v4(segments[6], segments[7])))
+        // 64:ff9b:1::/48 NAT64 for local networks
+        || segments[..3] == [0x64, 0xff9b, 1]
+        // 2002::/16 6to4 tunnels to the IPv4 address in the next 32 bits
+        || (first == 0x2002 && is_internal_v4(embedded_v4(segments[1], segments[2])))
+}
+
+fn is_internal(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_internal_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_internal_v4(ip),
+            None => is_internal_v6(ip),
+        },
+    }
+}
+
+/// Resolves like the system resolver but only hands out addresses the policy allows.
+/// Connecting to the addresses checked here means DNS rebinding can't sneak in another one.
+struct GuardedResolver {
+    policy: Arc<SsrfPolicy>,
+}
+
+impl Resolve for GuardedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let policy = self.policy.clone();
+        Box::pin(async move {
+            let host = name.as_str();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, 0))
+                .await?
+                .filter(|addr| policy.allows(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} only resolves to addresses outgoing requests may not reach", host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// HTTP client for checks that enforces the policy on every connection, redirects included.
+pub fn guarded_client(policy: Arc<SsrfPolicy>, timeout: Duration) -> reqwest::Result<Client> {
+    let redirect_policy = policy.clone();
+    Client::builder()
+        .timeout(timeout)
+        .dns_resolver(Arc::new(GuardedResolver { policy }))
+        .redirect(redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                return attempt.error("too many redirects");
+            }
+            match redirect_policy.check_url(attempt.url()) {
+                Ok(()) => attempt.follow(),
+                Err(e) => attempt.error(e),
+            }
+        }))
+        .build()
+}
+
+/// Why [`GuardedClient::send`] didn't get a response.
+#[derive(Debug)]
+pub enum SendError {
+    /// The url points at an address the policy forbids; trying again won't help.
+    Refused(String),
+    Http(reqwest::Error),
+}
+
+impl std::fmt::Display for SendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SendError::Refused(reason) => f.write_str(reason),
+            SendError::Http(e) => e.fmt(f),
+        }
+    }
+}
+
+/// A [`guarded_client`] that also refuses literal addresses, which never reach its resolver.
+/// For requests to urls users gave us, like webhooks.
+#[derive(Clone)]
+pub struct GuardedClient {
+    http: Client,
+    policy: Arc<SsrfPolicy>,
+}
+
+impl GuardedClient {
+    pub fn new(policy: Arc<SsrfPolicy>, timeout: Duration) -> reqwest::Result<Self> {
+        Ok(Self { http: guarded_client(policy.clone(), timeout)?, policy })
+    }
+
+    /// Builds requests; send them with [`GuardedClient::send`].
+    pub fn http(&self) -> &Client {
+        &self.http
+    }
+
+    pub fn check_url(&self, url: &Url) -> Result<(), String> {
+        self.policy.check_url(url)
+    }
+
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response, SendError> {
+        let request = request.build().map_err(SendError::Http)?;
+        self.check_url(request.url()).map_err(SendError::Refused)?;
+        self.http.execute(request).await.map_err(SendError::Http)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn internal(ip: &str) -> bool {
+        is_internal(ip.parse().unwrap())
+    }
+
+    #[test]
+    fn flags_internal_ipv4() {
+        for ip in ["127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254", "0.0.0.0", "100.100.100.200", "198.18.0.1", "198.19.255.255", "224.0.0.1", "239.255.255.250", "240.0.0.1", "255.255.255.255"] {
+            assert!(internal(ip), "{} should be internal", ip);
+        }
+        for ip in ["1.1.1.1", "93.184.216.34", "198.17.255.255", "198.20.0.1", "100.128.0.1"] {
+            assert!(!internal(ip), "{} should be public", ip);
+        }
+    }
+
+    #[test]
+    fn flags_internal_ipv6() {
+        for ip in ["::1", "::", "fd00::1", "fe80::1", "ff02::1", "fd00:ec2::254", "::ffff:127.0.0.1", "64:ff9b::a9fe:a9fe", "64:ff9b::7f00:1", "64:ff9b:1::808:808", "2002:7f00:1::", "2002:a9fe:a9fe::1"] {
+            assert!(internal(ip), "{} should be internal", ip);
+        }
+        for ip in ["2606:4700::1111", "::ffff:1.1.1.1", "64:ff9b::101:101", "2002:101:101::1"] {
+            assert!(!internal(ip), "{} should be public", ip);
+        }
+    }
+}
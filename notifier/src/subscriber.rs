@@ -0,0 +1,300 @@
+use chrono::{DateTime, Utc};
+use db::db::Db;
+use db::error::OptionalExt;
+use db::models::incident::StatusChange;
+use db::models::maintenance_window::MaintenanceWindow;
+use db::models::status_page::{StatusPage, StatusPageComponent};
+use db::models::status_page_subscriber::{StatusPageSubscriber, SUBSCRIBER_EMAIL, SUBSCRIBER_WEBHOOK};
+use db::models::website_tick::WebsiteStatus;
+use redisstream::{schedule_subscriber_retry, x_add_subscriber_updates, SubscriberEvent};
+use serde::{Deserialize, Serialize};
+
+use crate::config::NotifierConfig;
+use crate::email::{Email, Mailer};
+use crate::error::NotifyError;
+use crate::payload::{IncidentPayload, PAYLOAD_VERSION};
+use crate::ssrf::GuardedClient;
+use crate::webhook::signed_request;
+
+/// What status page subscribers receive, as the webhook body or rendered into an email.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriberUpdatePayload {
+    pub version: String,
+    /// `incident.opened`, `incident.resolved` or `maintenance.scheduled`
+    pub event: String,
+    pub status_page: StatusPagePayload,
+    /// Name of the affected component as shown on the page.
+    pub component: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub incident: Option<IncidentPayload>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maintenance: Option<MaintenancePayload>,
+    pub occurred_at: DateTime<Utc>,
+    /// Filled in per subscriber right before delivery.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unsubscribe_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusPagePayload {
+    pub slug: String,
+    pub title: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenancePayload {
+    pub title: String,
+    /// Next occurrence.
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub recurrence: Option<String>,
+}
+
+/// The first request a webhook subscriber receives, signed with the subscriber's secret.
+/// Nothing else is sent until the receiver POSTs to `confirm_url`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionConfirmationPayload {
+    pub version: String,
+    /// Always `subscription.confirm`.
+    pub event: String,
+    pub status_page: StatusPagePayload,
+    pub confirm_url: String,
+}
+
+fn status_page_payload(page: &StatusPage, config: &NotifierConfig) -> StatusPagePayload {
+    StatusPagePayload {
+        slug: page.slug.clone(),
+        title: page.title.clone(),
+        url: format!("{}/status-page/{}", config.versioned_api_url(), page.slug),
+    }
+}
+
+async fn enqueue(subscribers: Vec<(StatusPageSubscriber, SubscriberUpdatePayload)>) -> Result<usize, NotifyError> {
+    if subscribers.is_empty() {
+        return Ok(0);
+    }
+    let events: Vec<SubscriberEvent> = subscribers
+        .iter()
+        .map(|(subscriber, payload)| SubscriberEvent {
+            subscriber_id: subscriber.id.clone(),
+            payload: serde_json::to_string(payload).expect("payload serializes to json"),
+            attempt: 1,
+        })
+        .collect();
+    x_add_subscriber_updates(&events).await?;
+    Ok(events.len())
+}
+
+/// Tells subscribers of every page showing the website that an incident opened or resolved.
+pub async fn enqueue_incident_update(db: &Db, config: &NotifierConfig, change: &StatusChange) -> Result<usize, NotifyError> {
+    let subscribers = db.get_website_subscribers(std::slice::from_ref(&change.website.id)).await?;
+    let (event, verb) = match change.new_status {
+        WebsiteStatus::Down => ("incident.opened", "is experiencing an outage"),
+        _ => ("incident.resolved", "has recovered"),
+    };
+
+    let updates = subscribers
+        .into_iter()
+        .map(|(subscriber, page, component): (StatusPageSubscriber, StatusPage, StatusPageComponent)| {
+            let payload = SubscriberUpdatePayload {
+                version: PAYLOAD_VERSION.to_string(),
+                event: event.to_string(),
+                status_page: status_page_payload(&page, config),
+                message: format!("{} {}.", component.name, verb),
+                component: component.name,
+                incident: Some(IncidentPayload {
+                    id: change.incident.id.clone(),
+                    status: change.incident.status.clone(),
+                    opened_at: change.incident.opened_at.and_utc(),
+                    resolved_at: change.incident.resolved_at.map(|t| t.and_utc()),
+                }),
+                maintenance: None,
+                occurred_at: change.occurred_at.and_utc(),
+                unsubscribe_url: None,
+            };
+            (subscriber, payload)
+        })
+        .collect();
+    enqueue(updates).await
+}
+
+/// Announces newly scheduled or rescheduled maintenance windows, once per schedule.
+pub async fn announce_maintenance_windows(db: &Db, config: &NotifierConfig) -> Result<usize, NotifyError> {
+    let windows = db.get_unannounced_maintenance_windows().await?;
+
+    let mut queued = 0;
+    for window in windows {
+        let Some((starts_at, ends_at)) = window.occurrences_after(Utc::now().naive_utc(), 1).into_iter().next() else {
+            // nothing left to announce for windows entirely in the past
+            db.mark_maintenance_window_announced(&window).await?;
+            continue;
+        };
+        let websites = db.get_maintenance_window_websites(&window).await?;
+        let updates = maintenance_updates(&window, db.get_website_subscribers(&websites).await?, starts_at.and_utc(), ends_at.and_utc(), config);
+        // marked only once queued, so a failed enqueue is retried on the next pass; a window
+        // edited or announced by another notifier in between may be announced twice
+        let count = enqueue(updates).await?;
+        if db.mark_maintenance_window_announced(&window).await? {
+            queued += count;
+        }
+    }
+    Ok(queued)
+}
+
+fn maintenance_updates(
+    window: &MaintenanceWindow,
+    subscribers: Vec<(StatusPageSubscriber, StatusPage, StatusPageComponent)>,
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+    config: &NotifierConfig,
+) -> Vec<(StatusPageSubscriber, SubscriberUpdatePayload)> {
+    let mut updates: Vec<(StatusPageSubscriber, SubscriberUpdatePayload)> = Vec::new();
+    for (subscriber, page, component) in subscribers {
+        // a group window can cover several components of one page, announce it once
+        if updates.iter().any(|(s, _)| s.id == subscriber.id) {
+            continue;
+        }
+        let payload = SubscriberUpdatePayload {
+            version: PAYLOAD_VERSION.to_string(),
+            event: "maintenance.scheduled".to_string(),
+            status_page: status_page_payload(&page, config),
+            message: format!("{}: {} from {} to {}.", window.title, component.name, starts_at.to_rfc3339(), ends_at.to_rfc3339()),
+            component: component.name,
+            incident: None,
+            maintenance: Some(MaintenancePayload {
+                title: window.title.clone(),
+                starts_at,
+                ends_at,
+                recurrence: window.recurrence.clone(),
+            }),
+            occurred_at: Utc::now(),
+            unsubscribe_url: None,
+        };
+        updates.push((subscriber, payload));
+    }
+    updates
+}
+
+pub fn confirm_url(config: &NotifierConfig, subscriber: &StatusPageSubscriber) -> String {
+    format!("{}/subscription/{}/confirm", config.versioned_api_url(), subscriber.token)
+}
+
+pub fn unsubscribe_url(config: &NotifierConfig, subscriber: &StatusPageSubscriber) -> String {
+    format!("{}/subscription/{}/unsubscribe", config.versioned_api_url(), subscriber.token)
+}
+
+/// Double opt-in email sent right after someone subscribes with their address.
+pub async fn send_confirmation(mailer: &Mailer, config: &NotifierConfig, page: &StatusPage, subscriber: &StatusPageSubscriber) -> Result<(), String> {
+    let subject = format!("Confirm your subscription to {}", page.title);
+    let body = format!(
+        "Someone, hopefully you, asked to receive status updates from {title}.\n\n\
+         Confirm the subscription: {confirm}\n\n\
+         If this wasn't you, ignore this email and nothing will be sent.\n",
+        title = page.title,
+        confirm = confirm_url(config, subscriber),
+    );
+    mailer
+        .send(Email {
+            to: &subscriber.target,
+            subject: &subject,
+            body: &body,
+            unsubscribe_url: None,
+        })
+        .await
+}
+
+/// Confirmation handshake for webhooks, so only receivers that want the updates get them.
+pub async fn send_webhook_confirmation(http: &GuardedClient, config: &NotifierConfig, page: &StatusPage, subscriber: &StatusPageSubscriber) -> Result<(), String> {
+    let payload = SubscriptionConfirmationPayload {
+        version: PAYLOAD_VERSION.to_string(),
+        event: "subscription.confirm".to_string(),
+        status_page: status_page_payload(page, config),
+        confirm_url: confirm_url(config, subscriber),
+    };
+    let body = serde_json::to_vec(&payload).expect("payload serializes to json");
+    match http.send(signed_request(http.http(), &subscriber.target, subscriber.secret.as_deref(), body)).await {
+        Ok(resp) if resp.status().is_success() => Ok(()),
+        Ok(resp) => Err(format!("receiver responded with {}", resp.status())),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn email_subject(payload: &SubscriberUpdatePayload) -> String {
+    match &payload.maintenance {
+        Some(maintenance) => format!("[{}] Scheduled maintenance: {}", payload.status_page.title, maintenance.title),
+        None => format!("[{}] {}", payload.status_page.title, payload.message),
+    }
+}
+
+fn email_body(payload: &SubscriberUpdatePayload) -> String {
+    let mut body = format!("{}\n\nCurrent status: {}\n", payload.message, payload.status_page.url);
+    if let Some(url) = &payload.unsubscribe_url {
+        body.push_str(&format!("\nUnsubscribe: {}\n", url));
+    }
+    body
+}
+
+/// Delivers one queued update and parks it for a retry when that failed.
+pub async fn dispatch_subscriber_update(db: &Db, http: &GuardedClient, mailer: Option<&Mailer>, config: &NotifierConfig, event: &SubscriberEvent) -> Result<(), NotifyError> {
+    let subscriber = db.get_status_page_subscriber(event.subscriber_id.clone()).await.optional()?;
+    let Some(subscriber) = subscriber.filter(|s| s.confirmed_at.is_some()) else {
+        // unsubscribed after the update was queued
+        return Ok(());
+    };
+    let mut payload: SubscriberUpdatePayload = match serde_json::from_str(&event.payload) {
+        Ok(payload) => payload,
+        Err(e) => {
+            tracing::warn!(subscriber_id = %event.subscriber_id, "dropping unreadable subscriber payload: {}", e);
+            return Ok(());
+        }
+    };
+    payload.unsubscribe_url = Some(unsubscribe_url(config, &subscriber));
+
+    let result = match subscriber.kind.as_str() {
+        SUBSCRIBER_EMAIL => match mailer {
+            Some(mailer) => {
+                let subject = email_subject(&payload);
+                let body = email_body(&payload);
+                mailer
+                    .send(Email {
+                        to: &subscriber.target,
+                        subject: &subject,
+                        body: &body,
+                        unsubscribe_url: payload.unsubscribe_url.as_deref(),
+                    })
+                    .await
+            }
+            None => {
+                tracing::warn!(subscriber_id = %subscriber.id, "SMTP is not configured, dropping email update");
+                return Ok(());
+            }
+        },
+        SUBSCRIBER_WEBHOOK => {
+            let body = serde_json::to_vec(&payload).expect("payload serializes to json");
+            match http.send(signed_request(http.http(), &subscriber.target, subscriber.secret.as_deref(), body)).await {
+                Ok(resp) if resp.status().is_success() => Ok(()),
+                Ok(resp) => Err(format!("receiver responded with {}", resp.status())),
+                Err(e) => Err(e.to_string()),
+            }
+        }
+        _ => return Ok(()),
+    };
+
+    let Err(error) = result else {
+        return Ok(());
+    };
+    tracing::info!(subscriber_id = %subscriber.id, attempt = event.attempt, error = %error, "subscriber update failed");
+    if event.attempt < config.retry.max_attempts {
+        let delay = chrono::Duration::from_std(config.retry.delay_after(event.attempt)).unwrap_or(chrono::Duration::MAX);
+        let retry = SubscriberEvent {
+            attempt: event.attempt + 1,
+            ..event.clone()
+        };
+        schedule_subscriber_retry(&retry, (Utc::now() + delay).timestamp_millis()).await?;
+    } else {
+        tracing::warn!(subscriber_id = %subscriber.id, "giving up on subscriber update after {} attempts", event.attempt);
+    }
+    Ok(())
+}
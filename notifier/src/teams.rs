@@ -0,0 +1,140 @@
+use db::models::notification_channel::NotificationChannel;
+use reqwest::{Client, RequestBuilder};
+use serde_json::{json, Value};
+
+use crate::config::NotifierConfig;
+use crate::dispatch::Channel;
+use crate::payload::StatusChangePayload;
+
+/// Microsoft Teams incoming webhook / workflow, rendered as an Adaptive Card.
+pub struct TeamsChannel {
+    id: String,
+    url: String,
+    dashboard_url: String,
+}
+
+impl TeamsChannel {
+    pub fn new(channel: &NotificationChannel, config: &NotifierConfig) -> Self {
+        Self {
+            id: channel.id.clone(),
+            url: channel.target.clone(),
+            dashboard_url: config.dashboard_url.clone(),
+        }
+    }
+
+    pub fn render(&self, payload: &StatusChangePayload) -> Value {
+        // adaptive cards only offer named colours
+        let color = if payload.is_down() { "Attention" } else { "Good" };
+        let mut facts = vec![
+            json!({ "title": "Status", "value": format!("{} → {}", payload.previous_status, payload.new_status) }),
+            json!({ "title": "Incident", "value": payload.incident.id }),
+            json!({ "title": "Time", "value": payload.occurred_at.to_rfc3339() }),
+        ];
+        facts.extend(payload.regions.iter().map(|r| json!({
+            "title": r.region_id,
+            "value": format!("{} ({} ms)", r.status, r.response_time_ms),
+        })));
+
+        let mut actions = vec![json!({
+            "type": "Action.OpenUrl",
+            "title": "View in dashboard",
+            "url": payload.website_link(&self.dashboard_url),
+        })];
+        if let Some(links) = &payload.links {
+            actions.push(json!({ "type": "Action.OpenUrl", "title": "Acknowledge", "url": links.acknowledge }));
+            actions.push(json!({ "type": "Action.OpenUrl", "title": "Resolve", "url": links.resolve }));
+        }
+
+        json!({
+            "type": "message",
+            "attachments": [{
+                "contentType": "application/vnd.microsoft.card.adaptive",
+                "content": {
+                    "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+                    "type": "AdaptiveCard",
+                    "version": "1.4",
+                    "msteams": { "width": "Full" },
+                    "body": [
+                        {
+                            // text runs aren't markdown, the url shows as it was typed
+                            "type": "RichTextBlock",
+                            "inlines": [{
+                                "type": "TextRun",
+                                "text": payload.headline(),
+                                "weight": "Bolder",
+                                "size": "Medium",
+                                "color": color,
+                            }]
+                        },
+                        { "type": "FactSet", "facts": facts }
+                    ],
+                    "actions": actions
+                }
+            }]
+        })
+    }
+}
+
+impl Channel for TeamsChannel {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn build_request(&self, http: &Client, payload: &StatusChangePayload) -> RequestBuilder {
+        http.post(&self.url).json(&self.render(payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use db::models::website_tick::WebsiteStatus;
+
+    use super::*;
+    use crate::payload::ActionLinksPayload;
+
+    fn channel() -> TeamsChannel {
+        TeamsChannel {
+            id: "channel".to_string(),
+            url: "https://example.webhook.office.com/webhookb2/x".to_string(),
+            dashboard_url: "https://app.example.com".to_string(),
+        }
+    }
+
+    #[test]
+    fn renders_adaptive_card() {
+        let message = channel().render(&StatusChangePayload::example("https://example.com", WebsiteStatus::Down));
+        let card = &message["attachments"][0]["content"];
+
+        assert_eq!(message["attachments"][0]["contentType"], "application/vnd.microsoft.card.adaptive");
+        assert_eq!(card["type"], "AdaptiveCard");
+        assert_eq!(card["body"][0]["inlines"][0]["text"], "https://example.com is down");
+        assert_eq!(card["body"][0]["inlines"][0]["color"], "Attention");
+        assert_eq!(card["body"][1]["facts"][0], json!({ "title": "Status", "value": "up → down" }));
+        assert_eq!(card["body"][1]["facts"][1], json!({ "title": "Incident", "value": "incident-1" }));
+        assert_eq!(card["body"][1]["facts"][3], json!({ "title": "eu", "value": "down (120 ms)" }));
+        assert_eq!(card["actions"].as_array().unwrap().len(), 1);
+        assert_eq!(card["actions"][0]["url"], "https://app.example.com/website/website-1");
+    }
+
+    #[test]
+    fn adds_action_links_and_colours_recovery() {
+        let mut payload = StatusChangePayload::example("https://example.com", WebsiteStatus::Up);
+        payload.links = Some(ActionLinksPayload { acknowledge: "https://api/ack".to_string(), resolve: "https://api/resolve".to_string() });
+        let card = &channel().render(&payload)["attachments"][0]["content"];
+
+        assert_eq!(card["body"][0]["inlines"][0]["color"], "Good");
+        assert_eq!(card["actions"][1], json!({ "type": "Action.OpenUrl", "title": "Acknowledge", "url": "https://api/ack" }));
+        assert_eq!(card["actions"][2], json!({ "type": "Action.OpenUrl", "title": "Resolve", "url": "https://api/resolve" }));
+    }
+
+    #[test]
+    fn shows_urls_as_plain_text() {
+        let message = channel().render(&StatusChangePayload::example("https://example.com/**bold**_[x](y)", WebsiteStatus::Down));
+        let headline = &message["attachments"][0]["content"]["body"][0];
+
+        // a text run renders no markdown, so the url needs no escaping
+        assert_eq!(headline["type"], "RichTextBlock");
+        assert_eq!(headline["inlines"][0]["type"], "TextRun");
+        assert_eq!(headline["inlines"][0]["text"], "https://example.com/**bold**_[x](y) is down");
+    }
+}
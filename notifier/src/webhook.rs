@@ -0,0 +1,120 @@
+use chrono::Utc;
+use db::models::notification_channel::NotificationChannel;
+use hmac::{Hmac, Mac};
+use reqwest::{header::CONTENT_TYPE, Client, RequestBuilder};
+use sha2::Sha256;
+
+use crate::dispatch::Channel;
+use crate::payload::StatusChangePayload;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Unix timestamp (seconds) the signature was computed with.
+pub const TIMESTAMP_HEADER: &str = "x-betteruptime-timestamp";
+/// `v1=<hex hmac-sha256 of "<timestamp>.<body>">`
+pub const SIGNATURE_HEADER: &str = "x-betteruptime-signature";
+/// Receivers should reject deliveries whose timestamp is further off than this.
+pub const DEFAULT_TOLERANCE_SECS: i64 = 5 * 60;
+
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("hmac accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("v1={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Checks a delivery the way a receiver would: the signature must match and the
+/// timestamp must be within `tolerance_secs` of `now` so captured requests can't be replayed.
+pub fn verify(secret: &str, timestamp: i64, signature: &str, body: &[u8], now: i64, tolerance_secs: i64) -> bool {
+    if (now - timestamp).abs() > tolerance_secs {
+        return false;
+    }
+    let Some(expected) = signature.strip_prefix("v1=").and_then(|s| hex::decode(s).ok()) else {
+        return false;
+    };
+
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("hmac accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Generic JSON webhook carrying the versioned [`StatusChangePayload`] as-is.
+pub struct WebhookChannel {
+    id: String,
+    url: String,
+    secret: Option<String>,
+}
+
+impl WebhookChannel {
+    pub fn new(channel: &NotificationChannel) -> Self {
+        Self {
+            id: channel.id.clone(),
+            url: channel.target.clone(),
+            secret: channel.secret.clone(),
+        }
+    }
+}
+
+impl Channel for WebhookChannel {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn build_request(&self, http: &Client, payload: &StatusChangePayload) -> RequestBuilder {
+        let body = serde_json::to_vec(payload).expect("payload serializes to json");
+        signed_request(http, &self.url, self.secret.as_deref(), body)
+    }
+}
+
+/// JSON POST carrying the timestamp header, and the signature header when there is a secret.
+pub fn signed_request(http: &Client, url: &str, secret: Option<&str>, body: Vec<u8>) -> RequestBuilder {
+    let timestamp = Utc::now().timestamp();
+
+    let mut request = http
+        .post(url)
+        .header(CONTENT_TYPE, "application/json")
+        .header(TIMESTAMP_HEADER, timestamp.to_string());
+    if let Some(secret) = secret {
+        request = request.header(SIGNATURE_HEADER, sign(secret, timestamp, &body));
+    }
+    request.body(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "whsec_test";
+    const BODY: &[u8] = br#"{"event":"incident.opened"}"#;
+    const AT: i64 = 1_760_000_000;
+
+    #[test]
+    fn signs_timestamp_and_body() {
+        // hmac-sha256 of `1760000000.{"event":"incident.opened"}`, as any receiver computes it
+        assert_eq!(sign(SECRET, AT, BODY), "v1=a2ea3edde15c4d0649d4fc853cfe9f4f900fd3d5dfa8fd05cd37ba92c5c883dd");
+    }
+
+    #[test]
+    fn verifies_own_signatures() {
+        let signature = sign(SECRET, AT, BODY);
+
+        assert!(verify(SECRET, AT, &signature, BODY, AT, DEFAULT_TOLERANCE_SECS));
+        assert!(verify(SECRET, AT, &signature, BODY, AT + DEFAULT_TOLERANCE_SECS, DEFAULT_TOLERANCE_SECS));
+        assert!(verify(SECRET, AT, &signature, BODY, AT - DEFAULT_TOLERANCE_SECS, DEFAULT_TOLERANCE_SECS));
+    }
+
+    #[test]
+    fn rejects_tampering_and_replays() {
+        let signature = sign(SECRET, AT, BODY);
+
+        assert!(!verify("other", AT, &signature, BODY, AT, DEFAULT_TOLERANCE_SECS));
+        assert!(!verify(SECRET, AT, &signature, br#"{"event":"incident.resolved"}"#, AT, DEFAULT_TOLERANCE_SECS));
+        assert!(!verify(SECRET, AT + 1, &signature, BODY, AT, DEFAULT_TOLERANCE_SECS));
+        assert!(!verify(SECRET, AT, &signature, BODY, AT + DEFAULT_TOLERANCE_SECS + 1, DEFAULT_TOLERANCE_SECS));
+        assert!(!verify(SECRET, AT, signature.trim_start_matches("v1="), BODY, AT, DEFAULT_TOLERANCE_SECS));
+        assert!(!verify(SECRET, AT, "v1=not-hex", BODY, AT, DEFAULT_TOLERANCE_SECS));
+    }
+}
@@ -1,5 +1,8 @@
-use redis::{aio::Connection, AsyncCommands, Client, RedisError, Value};
-use serde::Deserialize;
+use std::collections::HashMap;
+
+use redis::{aio::MultiplexedConnection, Client, Value};
+pub use redis::RedisError;
+use serde::{Deserialize, Serialize};
 use tokio::sync::OnceCell;
 
 /// Stream name used by pusher & workers (same as Node code)
@@ -15,11 +18,11 @@ pub async fn init_redis(url: &str) -> Result<(), RedisError> {
     Ok(())
 }
 
-async fn get_conn() -> Result<Connection, RedisError> {
+async fn get_conn() -> Result<MultiplexedConnection, RedisError> {
     let client = REDIS_CLIENT
         .get()
         .expect("redis client must be initialized with init_redis");
-    client.get_async_connection().await
+    client.get_multiplexed_async_connection().await
 }
 
 #[derive(Debug, Deserialize)]
@@ -52,7 +55,7 @@ pub async fn x_add_bulk(events: &[WebsiteEvent]) -> Result<(), RedisError> {
             .ignore();
     }
 
-    pipe.query_async(&mut conn).await?;
+    pipe.query_async::<_, ()>(&mut conn).await?;
     Ok(())
 }
 
@@ -66,6 +69,31 @@ pub async fn x_read_group(
     count: usize,
     block_ms: usize,
 ) -> Result<Option<Vec<StreamMessage>>, RedisError> {
+    let entries = read_group(STREAM_NAME, consumer_group, consumer, count, block_ms).await?;
+
+    Ok(entries.map(|entries| {
+        entries
+            .into_iter()
+            .filter_map(|(id, mut fields)| {
+                let url = fields.remove("url")?;
+                let wid = fields.remove("id")?;
+                Some(StreamMessage {
+                    id,
+                    message: WebsiteEvent { url, id: wid },
+                })
+            })
+            .collect()
+    }))
+}
+
+/// XREADGROUP on any stream, returning `(entry id, fields)` pairs.
+async fn read_group(
+    stream: &str,
+    consumer_group: &str,
+    consumer: &str,
+    count: usize,
+    block_ms: usize,
+) -> Result<Option<Vec<(String, HashMap<String, String>)>>, RedisError> {
     let mut conn = get_conn().await?;
 
     // XREADGROUP GROUP <group> <consumer> BLOCK <block_ms> COUNT <count> STREAMS <stream> >
@@ -78,107 +106,145 @@ pub async fn x_read_group(
         .arg("COUNT")
         .arg(count)
         .arg("STREAMS")
-        .arg(STREAM_NAME)
+        .arg(stream)
         .arg(">") // new messages
         .query_async(&mut conn)
         .await?;
 
-    // Parse the nested redis::Value into our StreamMessage vec
+    Ok(parse_entries(res))
+}
+
+/// Parses an XREAD / XREADGROUP reply into `(entry id, fields)` pairs, `None` when nothing was read.
+fn parse_entries(res: Value) -> Option<Vec<(String, HashMap<String, String>)>> {
     // If no messages -> some redis versions return Nil or empty array
     match res {
-        Value::Nil => Ok(None),
-        Value::Bulk(top) if top.is_empty() => Ok(None),
-        Value::Bulk(mut top) => {
+        Value::Nil => None,
+        Value::Bulk(top) if top.is_empty() => None,
+        Value::Bulk(top) => {
             // top is an array of streams; for our case only one stream expected
             // typical form: [[stream_name, [[id, [field1, val1, field2, val2]], ...]]]
-            // We'll parse defensively.
-            let mut msgs = Vec::new();
-
+            let mut entries = Vec::new();
             for stream_ent in top {
                 if let Value::Bulk(mut stream_arr) = stream_ent {
                     // stream_arr[1] should be the messages
                     if stream_arr.len() >= 2 {
-                        if let Value::Bulk(messages) = stream_arr.swap_remove(1) {
-                            for msg in messages {
-                                if let Value::Bulk(mut pair) = msg {
-                                    if pair.len() >= 2 {
-                                        // pair[0] = id, pair[1] = fields array
-                                        let id_val = pair.swap_remove(0);
-                                        let fields_val = pair.swap_remove(0);
-
-                                        let id = match id_val {
-                                            Value::Data(d) => String::from_utf8_lossy(&d).to_string(),
-                                            Value::Bulk(_) => continue,
-                                            _ => continue,
-                                        };
-
-                                        // fields array: [field1, val1, field2, val2, ...]
-                                        let mut url = None;
-                                        let mut wid = None;
-                                        if let Value::Bulk(fields) = fields_val {
-                                            let mut iter = fields.into_iter();
-                                            while let (Some(k), Some(v)) = (iter.next(), iter.next()) {
-                                                if let Value::Data(kb) = k {
-                                                    let key = String::from_utf8_lossy(&kb).to_string();
-                                                    if let Value::Data(vb) = v {
-                                                        let val =
-                                                            String::from_utf8_lossy(&vb).to_string();
-                                                        match key.as_str() {
-                                                            "url" => url = Some(val),
-                                                            "id" => wid = Some(val),
-                                                            _ => {}
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-
-                                        if let (Some(url), Some(wid)) = (url, wid) {
-                                            msgs.push(StreamMessage {
-                                                id,
-                                                message: WebsiteEvent { url, id: wid },
-                                            });
-                                        }
-                                    }
-                                }
-                            }
-                        }
+                        entries.extend(parse_messages(stream_arr.swap_remove(1)));
                     }
                 }
             }
-
-            Ok(Some(msgs))
+            Some(entries)
         }
         other => {
             // unexpected shape
-            tracing::warn!("x_read_group unexpected reply: {:?}", other);
-            Ok(None)
+            tracing::warn!("stream read unexpected reply: {:?}", other);
+            None
+        }
+    }
+}
+
+/// Parses a list of stream entries, `[[id, [field1, val1, ...]], ...]`. Entries deleted
+/// from the stream show up as nil in XAUTOCLAIM replies and are skipped.
+fn parse_messages(messages: Value) -> Vec<(String, HashMap<String, String>)> {
+    let Value::Bulk(messages) = messages else {
+        return Vec::new();
+    };
+    let mut entries = Vec::new();
+    for msg in messages {
+        if let Value::Bulk(mut pair) = msg {
+            if pair.len() >= 2 {
+                // pair[0] = id, pair[1] = fields array
+                let id_val = pair.swap_remove(0);
+                let fields_val = pair.swap_remove(0);
+
+                let id = match id_val {
+                    Value::Data(d) => String::from_utf8_lossy(&d).to_string(),
+                    _ => continue,
+                };
+
+                // fields array: [field1, val1, field2, val2, ...]
+                let mut fields = HashMap::new();
+                if let Value::Bulk(raw) = fields_val {
+                    let mut iter = raw.into_iter();
+                    while let (Some(k), Some(v)) = (iter.next(), iter.next()) {
+                        if let (Value::Data(kb), Value::Data(vb)) = (k, v) {
+                            fields.insert(
+                                String::from_utf8_lossy(&kb).to_string(),
+                                String::from_utf8_lossy(&vb).to_string(),
+                            );
+                        }
+                    }
+                }
+
+                entries.push((id, fields));
+            }
+        }
+    }
+    entries
+}
+
+/// Takes over up to `count` entries another consumer read but didn't ack within `min_idle_ms`,
+/// e.g. because handling them failed or the consumer died.
+async fn claim_stale(
+    stream: &str,
+    consumer_group: &str,
+    consumer: &str,
+    min_idle_ms: usize,
+    count: usize,
+) -> Result<Vec<(String, HashMap<String, String>)>, RedisError> {
+    let mut conn = get_conn().await?;
+
+    // XAUTOCLAIM <stream> <group> <consumer> <min-idle> 0-0 COUNT <count>
+    // -> [next cursor, entries, (deleted ids, redis 7+)]
+    let res: Value = redis::cmd("XAUTOCLAIM")
+        .arg(stream)
+        .arg(consumer_group)
+        .arg(consumer)
+        .arg(min_idle_ms)
+        .arg("0-0")
+        .arg("COUNT")
+        .arg(count)
+        .query_async(&mut conn)
+        .await?;
+
+    match res {
+        Value::Bulk(mut reply) if reply.len() >= 2 => Ok(parse_messages(reply.swap_remove(1))),
+        other => {
+            tracing::warn!("stream claim unexpected reply: {:?}", other);
+            Ok(Vec::new())
         }
     }
 }
 
 /// XACK multiple ids in a single call
 pub async fn x_ack_bulk(consumer_group: &str, ids: &[String]) -> Result<(), RedisError> {
+    ack(STREAM_NAME, consumer_group, ids).await
+}
+
+async fn ack(stream: &str, consumer_group: &str, ids: &[String]) -> Result<(), RedisError> {
     if ids.is_empty() {
         return Ok(());
     }
     let mut conn = get_conn().await?;
 
     let mut cmd = redis::cmd("XACK");
-    cmd.arg(STREAM_NAME).arg(consumer_group);
+    cmd.arg(stream).arg(consumer_group);
     for id in ids {
         cmd.arg(id);
     }
-    cmd.query_async(&mut conn).await?;
+    cmd.query_async::<_, ()>(&mut conn).await?;
     Ok(())
 }
 
 /// Create group if missing (ignore BUSYGROUP)
 pub async fn ensure_group_exists(consumer_group: &str) -> Result<(), RedisError> {
+    create_group(STREAM_NAME, consumer_group).await
+}
+
+async fn create_group(stream: &str, consumer_group: &str) -> Result<(), RedisError> {
     let mut conn = get_conn().await?;
     let res = redis::cmd("XGROUP")
         .arg("CREATE")
-        .arg(STREAM_NAME)
+        .arg(stream)
         .arg(consumer_group)
         .arg("0") // start at beginning
         .arg("MKSTREAM")
@@ -198,3 +264,372 @@ pub async fn ensure_group_exists(consumer_group: &str) -> Result<(), RedisError>
         }
     }
 }
+
+/// Stream the worker publishes alerts to and the notifier dispatches from
+const NOTIFICATION_STREAM_NAME: &str = "betteruptime:notifications";
+/// Sorted set (score = due time in ms) holding failed deliveries waiting for their retry
+const NOTIFICATION_RETRY_SET: &str = "betteruptime:notifications:retry";
+
+/// One pending delivery of an alert to one channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationEvent {
+    pub incident_id: String,
+    pub channel_id: String,
+    /// Serialized alert payload, rendered per channel by the notifier
+    pub payload: String,
+    /// 1-based attempt number this event represents
+    pub attempt: u32,
+}
+
+#[derive(Debug)]
+pub struct NotificationMessage {
+    pub id: String,
+    pub message: NotificationEvent,
+}
+
+pub async fn x_add_notifications(events: &[NotificationEvent]) -> Result<(), RedisError> {
+    let mut conn = get_conn().await?;
+
+    let mut pipe = redis::pipe();
+    for e in events {
+        pipe.cmd("XADD")
+            .arg(NOTIFICATION_STREAM_NAME)
+            .arg("*")
+            .arg("incident_id")
+            .arg(&e.incident_id)
+            .arg("channel_id")
+            .arg(&e.channel_id)
+            .arg("payload")
+            .arg(&e.payload)
+            .arg("attempt")
+            .arg(e.attempt)
+            .ignore();
+    }
+
+    pipe.query_async::<_, ()>(&mut conn).await?;
+    Ok(())
+}
+
+pub async fn x_read_notifications(
+    consumer_group: &str,
+    consumer: &str,
+    count: usize,
+    block_ms: usize,
+) -> Result<Option<Vec<NotificationMessage>>, RedisError> {
+    let entries = read_group(NOTIFICATION_STREAM_NAME, consumer_group, consumer, count, block_ms).await?;
+    Ok(entries.map(|entries| entries.into_iter().filter_map(notification_message).collect()))
+}
+
+/// Takes over notifications other consumers left unacked for `min_idle_ms`, see [`x_ack_notifications`].
+pub async fn x_claim_stale_notifications(
+    consumer_group: &str,
+    consumer: &str,
+    min_idle_ms: usize,
+    count: usize,
+) -> Result<Vec<NotificationMessage>, RedisError> {
+    let entries = claim_stale(NOTIFICATION_STREAM_NAME, consumer_group, consumer, min_idle_ms, count).await?;
+    Ok(entries.into_iter().filter_map(notification_message).collect())
+}
+
+fn notification_message((id, mut fields): (String, HashMap<String, String>)) -> Option<NotificationMessage> {
+    Some(NotificationMessage {
+        id,
+        message: NotificationEvent {
+            incident_id: fields.remove("incident_id")?,
+            channel_id: fields.remove("channel_id")?,
+            payload: fields.remove("payload")?,
+            attempt: fields.remove("attempt")?.parse().ok()?,
+        },
+    })
+}
+
+/// Only ack what was handled: unacked entries stay pending and are picked up again by
+/// [`x_claim_stale_notifications`].
+pub async fn x_ack_notifications(consumer_group: &str, ids: &[String]) -> Result<(), RedisError> {
+    ack(NOTIFICATION_STREAM_NAME, consumer_group, ids).await
+}
+
+pub async fn ensure_notification_group_exists(consumer_group: &str) -> Result<(), RedisError> {
+    create_group(NOTIFICATION_STREAM_NAME, consumer_group).await
+}
+
+/// Adds `member` to a retry set, due at `due_ms` (unix millis)
+async fn schedule_retry<T: Serialize>(set: &str, event: &T, due_ms: i64) -> Result<(), RedisError> {
+    let mut conn = get_conn().await?;
+    let member = serde_json::to_string(event)
+        .map_err(|e| RedisError::from((redis::ErrorKind::TypeError, "failed to encode retry", e.to_string())))?;
+
+    redis::cmd("ZADD")
+        .arg(set)
+        .arg(due_ms)
+        .arg(member)
+        .query_async::<_, ()>(&mut conn)
+        .await
+}
+
+/// Moves up to 100 retries due at `ARGV[1]` from the set `KEYS[1]` onto the stream `KEYS[2]`,
+/// copying the fields named by the remaining arguments out of each JSON member. Runs atomically,
+/// so a retry is never removed without being re-published, nor re-published twice.
+/// Members that aren't JSON objects with those fields are dropped.
+const PROMOTE_RETRIES_SCRIPT: &str = r#"
+local due = redis.call('ZRANGEBYSCORE', KEYS[1], '-inf', ARGV[1], 'LIMIT', 0, 100)
+local promoted = 0
+for _, member in ipairs(due) do
+    redis.call('ZREM', KEYS[1], member)
+    local ok, event = pcall(cjson.decode, member)
+    if ok and type(event) == 'table' then
+        local fields = {}
+        for i = 2, #ARGV do
+            local value = event[ARGV[i]]
+            if value == nil or value == cjson.null then
+                fields = nil
+                break
+            end
+            table.insert(fields, ARGV[i])
+            table.insert(fields, tostring(value))
+        end
+        if fields then
+            redis.call('XADD', KEYS[2], '*', unpack(fields))
+            promoted = promoted + 1
+        end
+    end
+end
+return promoted
+"#;
+
+/// Re-publishes retries due at `now_ms` from `set` onto `stream`, see [`PROMOTE_RETRIES_SCRIPT`].
+async fn promote_due_retries(set: &str, stream: &str, fields: &[&str], now_ms: i64) -> Result<usize, RedisError> {
+    let mut conn = get_conn().await?;
+    let script = redis::Script::new(PROMOTE_RETRIES_SCRIPT);
+    let mut invocation = script.prepare_invoke();
+    invocation.key(set).key(stream).arg(now_ms);
+    for field in fields {
+        invocation.arg(*field);
+    }
+    invocation.invoke_async(&mut conn).await
+}
+
+/// Parks an event until `due_ms` (unix millis); [`promote_due_notifications`] moves it back onto the stream
+pub async fn schedule_notification_retry(event: &NotificationEvent, due_ms: i64) -> Result<(), RedisError> {
+    schedule_retry(NOTIFICATION_RETRY_SET, event, due_ms).await
+}
+
+/// Re-publishes every retry due at `now_ms`. Safe to run from several dispatchers.
+pub async fn promote_due_notifications(now_ms: i64) -> Result<usize, RedisError> {
+    promote_due_retries(NOTIFICATION_RETRY_SET, NOTIFICATION_STREAM_NAME, &["incident_id", "channel_id", "payload", "attempt"], now_ms).await
+}
+
+/// Stream of incident and maintenance updates for status page subscribers
+const SUBSCRIBER_STREAM_NAME: &str = "betteruptime:subscriber-updates";
+/// Sorted set (score = due time in ms) of subscriber deliveries waiting for their retry
+const SUBSCRIBER_RETRY_SET: &str = "betteruptime:subscriber-updates:retry";
+
+/// One pending delivery of a status page update to one subscriber
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriberEvent {
+    pub subscriber_id: String,
+    /// Serialized update payload, rendered as an email or webhook body by the notifier
+    pub payload: String,
+    /// 1-based attempt number this event represents
+    pub attempt: u32,
+}
+
+#[derive(Debug)]
+pub struct SubscriberMessage {
+    pub id: String,
+    pub message: SubscriberEvent,
+}
+
+pub async fn x_add_subscriber_updates(events: &[SubscriberEvent]) -> Result<(), RedisError> {
+    let mut conn = get_conn().await?;
+
+    let mut pipe = redis::pipe();
+    for e in events {
+        pipe.cmd("XADD")
+            .arg(SUBSCRIBER_STREAM_NAME)
+            .arg("*")
+            .arg("subscriber_id")
+            .arg(&e.subscriber_id)
+            .arg("payload")
+            .arg(&e.payload)
+            .arg("attempt")
+            .arg(e.attempt)
+            .ignore();
+    }
+
+    pipe.query_async::<_, ()>(&mut conn).await?;
+    Ok(())
+}
+
+pub async fn x_read_subscriber_updates(
+    consumer_group: &str,
+    consumer: &str,
+    count: usize,
+    block_ms: usize,
+) -> Result<Option<Vec<SubscriberMessage>>, RedisError> {
+    let entries = read_group(SUBSCRIBER_STREAM_NAME, consumer_group, consumer, count, block_ms).await?;
+
+    Ok(entries.map(|entries| entries.into_iter().filter_map(subscriber_message).collect()))
+}
+
+/// Takes over subscriber updates other consumers left unacked for `min_idle_ms`, see [`x_ack_subscriber_updates`].
+pub async fn x_claim_stale_subscriber_updates(
+    consumer_group: &str,
+    consumer: &str,
+    min_idle_ms: usize,
+    count: usize,
+) -> Result<Vec<SubscriberMessage>, RedisError> {
+    let entries = claim_stale(SUBSCRIBER_STREAM_NAME, consumer_group, consumer, min_idle_ms, count).await?;
+    Ok(entries.into_iter().filter_map(subscriber_message).collect())
+}
+
+fn subscriber_message((id, mut fields): (String, HashMap<String, String>)) -> Option<SubscriberMessage> {
+    Some(SubscriberMessage {
+        id,
+        message: SubscriberEvent {
+            subscriber_id: fields.remove("subscriber_id")?,
+            payload: fields.remove("payload")?,
+            attempt: fields.remove("attempt")?.parse().ok()?,
+        },
+    })
+}
+
+/// Only ack what was handled: unacked entries stay pending and are picked up again by
+/// [`x_claim_stale_subscriber_updates`].
+pub async fn x_ack_subscriber_updates(consumer_group: &str, ids: &[String]) -> Result<(), RedisError> {
+    ack(SUBSCRIBER_STREAM_NAME, consumer_group, ids).await
+}
+
+pub async fn ensure_subscriber_group_exists(consumer_group: &str) -> Result<(), RedisError> {
+    create_group(SUBSCRIBER_STREAM_NAME, consumer_group).await
+}
+
+pub async fn schedule_subscriber_retry(event: &SubscriberEvent, due_ms: i64) -> Result<(), RedisError> {
+    schedule_retry(SUBSCRIBER_RETRY_SET, event, due_ms).await
+}
+
+pub async fn promote_due_subscriber_updates(now_ms: i64) -> Result<usize, RedisError> {
+    promote_due_retries(SUBSCRIBER_RETRY_SET, SUBSCRIBER_STREAM_NAME, &["subscriber_id", "payload", "attempt"], now_ms).await
+}
+
+/// Fan-out stream of check results and status transitions for live dashboards.
+/// Read without a consumer group so every api instance sees every entry.
+const LIVE_STREAM_NAME: &str = "betteruptime:live";
+/// Approximate cap on the live stream, readers only ever care about recent entries
+const LIVE_STREAM_MAXLEN: usize = 10_000;
+
+/// Something a live dashboard should show right away
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveEvent {
+    pub website_id: String,
+    /// `tick` for a check result, `status` for an up/down transition
+    pub kind: String,
+    pub status: String,
+    /// Region that ran the check, ticks only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_time_ms: Option<i32>,
+    /// Incident opened or resolved by the transition, status events only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub incident_id: Option<String>,
+    pub at_ms: i64,
+}
+
+/// What one XREAD returned. `last_id` also covers entries that failed to parse,
+/// so the next read continues after them.
+#[derive(Debug)]
+pub struct LiveBatch {
+    pub last_id: String,
+    pub events: Vec<LiveEvent>,
+}
+
+pub async fn x_add_live(events: &[LiveEvent]) -> Result<(), RedisError> {
+    if events.is_empty() {
+        return Ok(());
+    }
+    let mut conn = get_conn().await?;
+
+    let mut pipe = redis::pipe();
+    for e in events {
+        // XADD stream MAXLEN ~ <n> * website_id <id> event <json>
+        pipe.cmd("XADD")
+            .arg(LIVE_STREAM_NAME)
+            .arg("MAXLEN")
+            .arg("~")
+            .arg(LIVE_STREAM_MAXLEN)
+            .arg("*")
+            .arg("website_id")
+            .arg(&e.website_id)
+            .arg("event")
+            .arg(serde_json::to_string(e).expect("live event serializes to json"))
+            .ignore();
+    }
+
+    pipe.query_async::<_, ()>(&mut conn).await?;
+    Ok(())
+}
+
+/// Entries added after `last_id`; pass `$` to start with whatever arrives next.
+pub async fn x_read_live(last_id: &str, count: usize, block_ms: usize) -> Result<Option<LiveBatch>, RedisError> {
+    let mut conn = get_conn().await?;
+
+    // XREAD BLOCK <block_ms> COUNT <count> STREAMS <stream> <last_id>
+    let res: Value = redis::cmd("XREAD")
+        .arg("BLOCK")
+        .arg(block_ms)
+        .arg("COUNT")
+        .arg(count)
+        .arg("STREAMS")
+        .arg(LIVE_STREAM_NAME)
+        .arg(last_id)
+        .query_async(&mut conn)
+        .await?;
+
+    let Some(entries) = parse_entries(res) else {
+        return Ok(None);
+    };
+    let Some(last_id) = entries.last().map(|(id, _)| id.clone()) else {
+        return Ok(None);
+    };
+    let events = entries
+        .into_iter()
+        .filter_map(|(_, mut fields)| serde_json::from_str(&fields.remove("event")?).ok())
+        .collect();
+    Ok(Some(LiveBatch { last_id, events }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(s: &str) -> Value {
+        Value::Data(s.as_bytes().to_vec())
+    }
+
+    fn entry(id: &str, fields: &[&str]) -> Value {
+        Value::Bulk(vec![data(id), Value::Bulk(fields.iter().map(|f| data(f)).collect())])
+    }
+
+    #[test]
+    fn parses_read_group_reply() {
+        let reply = Value::Bulk(vec![Value::Bulk(vec![
+            data(NOTIFICATION_STREAM_NAME),
+            Value::Bulk(vec![entry("1-0", &["incident_id", "i", "channel_id", "c", "payload", "{}", "attempt", "2"])]),
+        ])]);
+        let entries = parse_entries(reply).unwrap();
+        let message = notification_message(entries.into_iter().next().unwrap()).unwrap();
+        assert_eq!(message.id, "1-0");
+        assert_eq!(message.message.channel_id, "c");
+        assert_eq!(message.message.attempt, 2);
+        assert!(parse_entries(Value::Nil).is_none());
+    }
+
+    #[test]
+    fn skips_deleted_entries_in_claim_reply() {
+        // XAUTOCLAIM reports entries trimmed from the stream as nil
+        let claimed = Value::Bulk(vec![entry("1-0", &["subscriber_id", "s"]), Value::Nil, entry("3-0", &["subscriber_id", "t"])]);
+        let ids: Vec<String> = parse_messages(claimed).into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, ["1-0", "3-0"]);
+    }
+}
@@ -1,34 +1,27 @@
 use std::env;
+use std::sync::Arc;
 use std::time::Instant;
 use tokio::time::Duration;
 use tokio::sync::Semaphore;
 use futures::stream::{FuturesUnordered, StreamExt};
 
-use diesel::prelude::*;
-use diesel_async::{AsyncConnection, RunQueryDsl, AsyncPgConnection};
-use reqwest::Client;
+use anyhow::Context;
+use reqwest::Url;
 
-use redisstream::{init_redis, ensure_group_exists, x_read_group, x_ack_bulk, StreamMessage};
+use redisstream::{init_redis, ensure_group_exists, x_read_group, x_ack_bulk, x_add_live, LiveEvent};
+use db::db::Db;
+use db::models::website_tick::WebsiteStatus;
+use notifier::config::NotifierConfig;
+use notifier::enqueue_status_change;
+use notifier::subscriber::enqueue_incident_update;
 
-mod schema {
-    // include your generated schema
-}
-
-#[derive(Insertable)]
-#[table_name = "website_ticks"] // ensure matches your schema module path
-struct NewTick {
-    website_id: uuid::Uuid,
-    response_time_ms: i32,
-    status: String,
-    region_id: String,
-}
+use notifier::ssrf::{guarded_client, SsrfPolicy};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt::init();
 
     let redis_url = env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1/".into());
-    let database_url = env::var("DATABASE_URL")?;
     let region_id = env::var("REGION_ID").expect("REGION_ID");
     let worker_id = env::var("WORKER_ID").expect("WORKER_ID");
 
@@ -36,15 +29,19 @@ async fn main() -> anyhow::Result<()> {
     // Ensure consumer group exists
     ensure_group_exists(&region_id).await?;
 
-    // DB connection
-    let mut db = AsyncPgConnection::establish(&database_url).await?;
+    // ticks, incident state and notification channels live behind the db crate's pool
+    let store = Db::new()?;
+    // status page links in subscriber updates point at the public api
+    let notifier_config = Arc::new(NotifierConfig::default());
 
+    // checks may not reach loopback, private or metadata addresses unless allowlisted
+    let ssrf = Arc::new(SsrfPolicy::from_env()?);
     // HTTP client with default timeout
-    let http = Client::builder().timeout(Duration::from_secs(10)).build()?;
+    let http = guarded_client(ssrf.clone(), Duration::from_secs(10))?;
 
     // concurrency limit for in-flight HTTP checks (adjust)
     let concurrency_limit = 20usize;
-    let sem = Semaphore::new(concurrency_limit);
+    let sem = Arc::new(Semaphore::new(concurrency_limit));
 
     loop {
         // Read up to count messages, block up to 5s (5000 ms) if none
@@ -62,42 +59,67 @@ async fn main() -> anyhow::Result<()> {
         for msg in msgs.into_iter() {
             ids_to_ack.push(msg.id.clone());
 
-            let db_conn = db.clone(); // diesel_async connections are cheaply clonable handles (if using pool; otherwise use pool)
             let http = http.clone();
+            let ssrf = ssrf.clone();
             let region = region_id.clone();
-            let sem_permit = sem.clone().acquire_owned().await.unwrap();
+            let store = store.clone();
+            let notifier_config = notifier_config.clone();
+            let sem_permit = sem.clone().acquire_owned().await.context("check concurrency limit closed")?;
 
             futs.push(tokio::spawn(async move {
                 // permit dropped when function returns (release concurrency slot)
                 let _permit = sem_permit;
 
                 let start = Instant::now();
-                // fetch website
-                let result = http.get(&msg.message.url).send().await;
-
-                let dt_ms = start.elapsed().as_millis().min(i64::from(i32::MAX as i64)) as i32;
-                let status_str = match result {
-                    Ok(resp) if resp.status().is_success() => "Up".to_string(),
-                    _ => "Down".to_string(),
+                // fetch website, a refused address counts as down like any unreachable one
+                let result = match Url::parse(&msg.message.url).map_err(|e| e.to_string()).and_then(|url| ssrf.check_url(&url).map(|()| url)) {
+                    Ok(url) => http.get(url).send().await.ok(),
+                    Err(e) => {
+                        tracing::warn!(website_id = %msg.message.id, "refusing to check: {}", e);
+                        None
+                    }
                 };
 
-                // insert tick into DB
-                // adapt insert logic to your schema & Diesel models
-                let new_tick = NewTick {
-                    website_id: uuid::Uuid::parse_str(&msg.message.id).unwrap(),
-                    response_time_ms: dt_ms,
-                    status: status_str,
-                    region_id: region.clone(),
+                let dt_ms = start.elapsed().as_millis().min(i32::MAX as u128) as i32;
+                let status = match result {
+                    Some(resp) if resp.status().is_success() => WebsiteStatus::Up,
+                    _ => WebsiteStatus::Down,
                 };
 
-                // Insert (run in async)
-                // This assumes you have a website_ticks table & Diesel Insertable derive properly setup
-                diesel::insert_into(schema::website_ticks::table)
-                    .values(&new_tick)
-                    .execute(&mut db_conn.into_inner()) // adjust to your connection/ pool usage
-                    .await?;
-
-                anyhow::Ok::<(), anyhow::Error>(())
+                let mut live = vec![LiveEvent {
+                    website_id: msg.message.id.clone(),
+                    kind: "tick".to_string(),
+                    status: status.as_str().to_string(),
+                    region_id: Some(region.clone()),
+                    response_time_ms: Some(dt_ms),
+                    incident_id: None,
+                    at_ms: chrono::Utc::now().timestamp_millis(),
+                }];
+
+                // store the tick, open / resolve the incident and queue alerts for the notifier,
+                // failures inside a maintenance window or behind a down dependency open nothing.
+                // A malformed or deleted website id fails here and is logged below
+                let change = store.record_website_status(msg.message.id.clone(), region.clone(), status, dt_ms).await?;
+                if let Some(change) = change {
+                    enqueue_status_change(&store, &change).await?;
+                    enqueue_incident_update(&store, &notifier_config, &change).await?;
+                    live.push(LiveEvent {
+                        website_id: change.website.id.clone(),
+                        kind: "status".to_string(),
+                        status: change.new_status.as_str().to_string(),
+                        region_id: None,
+                        response_time_ms: None,
+                        incident_id: Some(change.incident.id.clone()),
+                        at_ms: change.occurred_at.and_utc().timestamp_millis(),
+                    });
+                }
+
+                // live dashboards are best effort, a redis hiccup must not fail the check
+                if let Err(e) = x_add_live(&live).await {
+                    tracing::warn!("live stream error: {:?}", e);
+                }
+
+                anyhow::Ok(())
             }));
         }
 